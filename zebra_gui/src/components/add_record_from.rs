@@ -2,11 +2,13 @@
 //! -- Email: hicarus@yandex.ru
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
 use crate::components::custom_field::CustomFields;
+use crate::components::organize::{self, Choice};
 use crate::components::passgen::{PassGenForm, PassGenState};
 
 use chrono::{Local, MappedLocalTime, TimeZone};
 use iced::widget::{
-    component, text_editor, Button, Column, Component, Container, Row, Scrollable, Space, Text,
+    component, pick_list, text_editor, Button, Checkbox, Column, Component, Container, Row,
+    Scrollable, Space, Text,
 };
 use iced::{Element, Length, Renderer, Theme};
 use rust_i18n::t;
@@ -14,7 +16,11 @@ use std::sync::{Arc, Mutex};
 
 use super::modal::Modal;
 use super::smart_input::SmartInput;
-use zebra_lib::core::record;
+use zebra_lib::core::organize::{Folder, Tag};
+use zebra_lib::core::otp::{OtpAuth, OtpKind};
+use zebra_lib::core::record::{self, ItemKind, LoginUri, UriMatch, Uuid};
+use zebra_lib::errors::ZebraErrors;
+use zebra_ui::components::circular::Circular;
 
 const DATA_FORMAT: &str = "%d.%m.%Y %H:%M:%S";
 const INPUT_PADDING: u16 = 12;
@@ -26,14 +32,28 @@ where
     Message: Clone,
 {
     element: &'a record::Element,
+    folders: &'a [Folder],
+    tags: &'a [Tag],
+    // fields taken from other records: index, source record and its name.
+    references: &'a [(usize, Uuid, String)],
     title: String,
     on_input: Option<Box<dyn Fn(record::Element) -> Message + 'a>>,
     on_copy: Option<Box<dyn Fn(String) -> Message + 'a>>,
+    on_restore_history: Option<Box<dyn Fn(usize, usize) -> Message + 'a>>,
+    on_next_hotp: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+    on_attach: Option<Message>,
+    on_preview_attachment: Option<Box<dyn Fn(Uuid) -> Message + 'a>>,
+    on_save_attachment: Option<Box<dyn Fn(Uuid) -> Message + 'a>>,
+    on_remove_attachment: Option<Box<dyn Fn(Uuid) -> Message + 'a>>,
+    on_open_reference: Option<Box<dyn Fn(Uuid) -> Message + 'a>>,
     on_save: Option<Message>,
     on_edit: Option<Message>,
     on_remove: Option<Message>,
     content: text_editor::Content,
+    // editors of the multiline fields by field index.
+    multiline: Vec<(usize, text_editor::Content)>,
     read_only: bool,
+    show_uris: bool,
     password_modal: bool,
     remove_modal: bool,
     modal_index_element: usize,
@@ -57,6 +77,36 @@ pub enum Event {
     HandleSavePassword,
     HandleChangeCustomField(Vec<record::Item>),
     HandleActionNote(text_editor::Action),
+    HandleActionField(usize, text_editor::Action),
+    HandleHistoryCopy(String),
+    HandleHistoryRestore(usize, usize),
+    HandleSelectFolder(Choice),
+    HandleToggleTag(Uuid),
+    HandleOpen(String),
+    HandleCopyCode(String),
+    HandleNextHotp(usize),
+    HandleAddUri,
+    HandleRemoveUri(usize),
+    HandleUriInput(usize, String),
+    HandleUriMode(usize, UriMatchOption),
+    HandleAttach,
+    HandlePreviewAttachment(Uuid),
+    HandleSaveAttachment(Uuid),
+    HandleRemoveAttachment(Uuid),
+    HandleOpenReference(Uuid),
+    HandleCopyId,
+    HandleToggleReprompt(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UriMatchOption(pub UriMatch);
+
+impl std::fmt::Display for UriMatchOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let key = format!("uri_match_{:?}", self.0).to_lowercase();
+
+        write!(f, "{}", t!(&key))
+    }
 }
 
 impl<'a, Message: Clone> AddRecordForm<'a, Message>
@@ -67,19 +117,38 @@ where
         let pass_gen_state = Arc::new(Mutex::new(PassGenState {
             value: String::new(),
             length: 42,
+            breached: None,
         }));
 
         Self {
             element,
+            folders: &[],
+            tags: &[],
+            references: &[],
             pass_gen_state,
             read_only: false,
+            show_uris: false,
             title: String::new(),
             on_input: None,
             on_copy: None,
+            on_restore_history: None,
+            on_next_hotp: None,
+            on_attach: None,
+            on_preview_attachment: None,
+            on_save_attachment: None,
+            on_remove_attachment: None,
+            on_open_reference: None,
             on_save: None,
             on_edit: None,
             on_remove: None,
             content: text_editor::Content::with_text(&element.note),
+            multiline: element
+                .fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| field.kind == ItemKind::Multiline)
+                .map(|(index, field)| (index, text_editor::Content::with_text(&field.value)))
+                .collect(),
             password_modal: false,
             remove_modal: false,
             modal_index_element: 0,
@@ -104,6 +173,24 @@ where
         self
     }
 
+    pub fn set_folders(mut self, folders: &'a [Folder]) -> Self {
+        self.folders = folders;
+
+        self
+    }
+
+    pub fn set_tags(mut self, tags: &'a [Tag]) -> Self {
+        self.tags = tags;
+
+        self
+    }
+
+    pub fn set_references(mut self, references: &'a [(usize, Uuid, String)]) -> Self {
+        self.references = references;
+
+        self
+    }
+
     pub fn set_title(mut self, title: String) -> Self {
         self.title = title;
 
@@ -116,6 +203,12 @@ where
         self
     }
 
+    pub fn set_show_uris(mut self, value: bool) -> Self {
+        self.show_uris = value;
+
+        self
+    }
+
     pub fn on_copy<F>(mut self, callback: F) -> Self
     where
         F: 'a + Fn(String) -> Message,
@@ -125,6 +218,69 @@ where
         self
     }
 
+    // called with the field index and the history entry index.
+    pub fn on_restore_history<F>(mut self, callback: F) -> Self
+    where
+        F: 'a + Fn(usize, usize) -> Message,
+    {
+        self.on_restore_history = Some(Box::new(callback));
+
+        self
+    }
+
+    // called with the field index of a HOTP secret.
+    pub fn on_next_hotp<F>(mut self, callback: F) -> Self
+    where
+        F: 'a + Fn(usize) -> Message,
+    {
+        self.on_next_hotp = Some(Box::new(callback));
+
+        self
+    }
+
+    pub fn on_attach(mut self, msg: Message) -> Self {
+        self.on_attach = Some(msg);
+
+        self
+    }
+
+    // the callbacks below get the id of the attachment.
+    pub fn on_preview_attachment<F>(mut self, callback: F) -> Self
+    where
+        F: 'a + Fn(Uuid) -> Message,
+    {
+        self.on_preview_attachment = Some(Box::new(callback));
+
+        self
+    }
+
+    pub fn on_save_attachment<F>(mut self, callback: F) -> Self
+    where
+        F: 'a + Fn(Uuid) -> Message,
+    {
+        self.on_save_attachment = Some(Box::new(callback));
+
+        self
+    }
+
+    pub fn on_remove_attachment<F>(mut self, callback: F) -> Self
+    where
+        F: 'a + Fn(Uuid) -> Message,
+    {
+        self.on_remove_attachment = Some(Box::new(callback));
+
+        self
+    }
+
+    pub fn on_open_reference<F>(mut self, callback: F) -> Self
+    where
+        F: 'a + Fn(Uuid) -> Message,
+    {
+        self.on_open_reference = Some(Box::new(callback));
+
+        self
+    }
+
     pub fn on_input<F>(mut self, callback: F) -> Self
     where
         F: 'a + Fn(record::Element) -> Message,
@@ -134,6 +290,25 @@ where
         self
     }
 
+    // a link to the record a field takes its value from.
+    pub fn view_reference(&self, index: usize) -> Option<Button<'a, Event, Theme, Renderer>> {
+        if !self.read_only {
+            return None;
+        }
+
+        let (_, id, name) = self.references.iter().find(|(i, _, _)| *i == index)?;
+        let text = Text::new(t!("reference_from", name => name))
+            .size(13)
+            .width(Length::Fill);
+
+        Some(
+            Button::new(text)
+                .padding(0)
+                .style(zebra_ui::styles::button::ref_primary)
+                .on_press(Event::HandleOpenReference(*id)),
+        )
+    }
+
     pub fn view_timestamp(&self) -> Option<Container<'a, Event, Theme, Renderer>> {
         const TEXT_SIZE: u16 = 13;
         if self.element.created == 0 {
@@ -166,15 +341,426 @@ where
             .size(TEXT_SIZE)
             .style(zebra_ui::styles::text::muted);
 
-        let col = Column::new()
+        let mut col = Column::new()
             .push(created_at)
             .push(upadted_at)
             .width(Length::Fill)
             .align_items(iced::Alignment::Start);
 
+        // the id other records refer to with "{ref:<id>:field}".
+        if self.read_only {
+            let copy_id_btn = Button::new(Text::new(t!("copy_record_id")).size(TEXT_SIZE))
+                .padding(0)
+                .style(zebra_ui::styles::button::ref_primary)
+                .on_press(Event::HandleCopyId);
+
+            col = col.push(copy_id_btn);
+        }
+
         Some(Container::new(col))
     }
 
+    pub fn view_organize(&self) -> Option<Container<'a, Event, Theme, Renderer>> {
+        const TEXT_SIZE: u16 = 13;
+
+        let element: &'a record::Element = self.element;
+
+        if self.read_only {
+            let folder = element
+                .folder
+                .map(|id| organize::folder_path(self.folders, &id))
+                .unwrap_or_default();
+            let tags: Vec<String> = self
+                .tags
+                .iter()
+                .filter(|tag| element.tags.contains(&tag.id))
+                .map(|tag| format!("#{}", tag.name))
+                .collect();
+
+            if folder.is_empty() && tags.is_empty() {
+                return None;
+            }
+
+            let text = Text::new(format!("{}  {}", folder, tags.join(" ")))
+                .size(TEXT_SIZE)
+                .style(zebra_ui::styles::text::muted)
+                .width(Length::Fill);
+
+            return Some(Container::new(text));
+        }
+
+        if self.folders.is_empty() && self.tags.is_empty() {
+            return None;
+        }
+
+        let no_folder = Choice {
+            id: None,
+            label: t!("no_folder").to_string(),
+        };
+        let selected = element
+            .folder
+            .map(|id| Choice {
+                id: Some(id),
+                label: organize::folder_path(self.folders, &id),
+            })
+            .unwrap_or(no_folder.clone());
+        let mut choices = vec![no_folder];
+
+        choices.extend(organize::folder_choices(self.folders));
+
+        let folder_pick_list = pick_list(choices, Some(selected), Event::HandleSelectFolder)
+            .text_size(14)
+            .padding(4)
+            .style(zebra_ui::styles::pick_list::primary_field);
+        let tags_row =
+            self.tags
+                .iter()
+                .fold(Row::new().spacing(5).push(folder_pick_list), |row, tag| {
+                    let style = if element.tags.contains(&tag.id) {
+                        zebra_ui::styles::button::primary
+                    } else {
+                        zebra_ui::styles::button::outline_primary
+                    };
+                    let button = Button::new(Text::new(format!("#{}", tag.name)).size(TEXT_SIZE))
+                        .padding(4)
+                        .style(style)
+                        .on_press(Event::HandleToggleTag(tag.id));
+
+                    row.push(button)
+                });
+
+        Some(Container::new(tags_row.align_items(iced::Alignment::Center)).width(Length::Fill))
+    }
+
+    pub fn view_uris(&self) -> Option<Container<'a, Event, Theme, Renderer>> {
+        let element: &'a record::Element = self.element;
+
+        if !self.show_uris || (self.read_only && element.uris.is_empty()) {
+            return None;
+        }
+
+        let label = Text::new(t!("label_uris"))
+            .size(14)
+            .style(zebra_ui::styles::text::muted)
+            .width(Length::Fill);
+        let mut col = Column::new().spacing(ITEM_SPACING).push(label);
+
+        for (index, uri) in element.uris.iter().enumerate() {
+            let error = uri.validate().err();
+            let mut input = SmartInput::new()
+                .set_value(&uri.uri)
+                .padding(INPUT_PADDING)
+                .set_danger(error.is_some())
+                .on_input(move |v| Event::HandleUriInput(index, v))
+                .set_placeholder(t!("placeholder_uri"));
+
+            if !matches!(uri.mode, UriMatch::Regex | UriMatch::Never) {
+                if let Some(link) = ItemKind::Url.link(&uri.uri) {
+                    input = input.set_open(Event::HandleOpen(link));
+                }
+            }
+
+            if self.read_only {
+                col = col.push(input);
+                continue;
+            }
+
+            let mode_pick_list = pick_list(
+                UriMatch::ALL.map(UriMatchOption),
+                Some(UriMatchOption(uri.mode)),
+                move |mode| Event::HandleUriMode(index, mode),
+            )
+            .text_size(14)
+            .padding(4)
+            .style(zebra_ui::styles::pick_list::primary_field);
+            let trash_btn = Button::new(
+                zebra_ui::image::trash_icon()
+                    .style(zebra_ui::styles::svg::primary_hover)
+                    .height(30)
+                    .width(30),
+            )
+            .padding(0)
+            .on_press(Event::HandleRemoveUri(index))
+            .width(30)
+            .style(zebra_ui::styles::button::transparent);
+            let error = error.map(|e| {
+                Text::new(validation_error(&e))
+                    .size(13)
+                    .style(zebra_ui::styles::text::danger)
+            });
+            let row = Row::new()
+                .spacing(5)
+                .push(
+                    Column::new()
+                        .push(input)
+                        .push_maybe(error)
+                        .width(Length::Fill),
+                )
+                .push(mode_pick_list)
+                .push(trash_btn)
+                .align_items(iced::Alignment::Center);
+
+            col = col.push(row);
+        }
+
+        if !self.read_only {
+            let add_btn = Button::new(
+                zebra_ui::image::add_icon()
+                    .style(zebra_ui::styles::svg::primary_hover)
+                    .height(30)
+                    .width(30),
+            )
+            .padding(0)
+            .on_press(Event::HandleAddUri)
+            .style(zebra_ui::styles::button::transparent);
+
+            col = col.push(add_btn);
+        }
+
+        Some(Container::new(col).width(Length::Fill))
+    }
+
+    // files live outside the record, so they are managed on a saved one only.
+    pub fn view_attachments(&self) -> Option<Container<'a, Event, Theme, Renderer>> {
+        const TEXT_SIZE: u16 = 13;
+
+        let element: &'a record::Element = self.element;
+
+        if !self.read_only || (element.attachments.is_empty() && self.on_attach.is_none()) {
+            return None;
+        }
+
+        let label = Text::new(t!("label_attachments"))
+            .size(14)
+            .style(zebra_ui::styles::text::muted)
+            .width(Length::Fill);
+        let attach_btn = Button::new(Text::new(t!("attach_file")).size(TEXT_SIZE))
+            .padding(0)
+            .style(zebra_ui::styles::button::ref_primary)
+            .on_press_maybe(self.on_attach.as_ref().map(|_| Event::HandleAttach));
+        let head = Row::new()
+            .push(label)
+            .push(attach_btn)
+            .align_items(iced::Alignment::Center);
+        let col = element.attachments.iter().fold(
+            Column::new().spacing(ITEM_SPACING).push(head),
+            |col, attachment| {
+                let name = Text::new(attachment.name.clone())
+                    .size(14)
+                    .width(Length::Fill);
+                let size = Text::new(format_size(attachment.size))
+                    .size(TEXT_SIZE)
+                    .style(zebra_ui::styles::text::muted);
+                let preview_btn = Button::new(Text::new(t!("preview")).size(TEXT_SIZE))
+                    .padding(0)
+                    .style(zebra_ui::styles::button::ref_primary)
+                    .on_press_maybe(
+                        self.on_preview_attachment
+                            .as_ref()
+                            .map(|_| Event::HandlePreviewAttachment(attachment.id)),
+                    );
+                let save_btn = Button::new(Text::new(t!("save_as")).size(TEXT_SIZE))
+                    .padding(0)
+                    .style(zebra_ui::styles::button::ref_primary)
+                    .on_press_maybe(
+                        self.on_save_attachment
+                            .as_ref()
+                            .map(|_| Event::HandleSaveAttachment(attachment.id)),
+                    );
+                let remove_btn = Button::new(
+                    zebra_ui::image::trash_icon()
+                        .style(zebra_ui::styles::svg::primary_hover)
+                        .height(20)
+                        .width(20),
+                )
+                .padding(0)
+                .style(zebra_ui::styles::button::transparent)
+                .on_press_maybe(
+                    self.on_remove_attachment
+                        .as_ref()
+                        .map(|_| Event::HandleRemoveAttachment(attachment.id)),
+                );
+                let row = Row::new()
+                    .spacing(ITEM_SPACING)
+                    .align_items(iced::Alignment::Center)
+                    .push(name)
+                    .push(size)
+                    .push_maybe(attachment.is_image().then_some(preview_btn))
+                    .push(save_btn)
+                    .push(remove_btn);
+
+                col.push(row)
+            },
+        );
+
+        Some(Container::new(col).width(Length::Fill))
+    }
+
+    pub fn view_history(&self) -> Option<Container<'a, Event, Theme, Renderer>> {
+        const TEXT_SIZE: u16 = 13;
+
+        if !self.read_only {
+            return None;
+        }
+
+        let element: &'a record::Element = self.element;
+        let mut col = Column::new().width(Length::Fill).spacing(ITEM_SPACING);
+        let mut is_empty = true;
+
+        for (field_index, field) in element
+            .fields
+            .iter()
+            .chain(element.extra_fields.iter())
+            .enumerate()
+            .filter(|(_, field)| !field.history.is_empty())
+        {
+            let label = Text::new(format!("{} {}", t!("history_of"), field.title))
+                .size(14)
+                .style(zebra_ui::styles::text::muted);
+
+            col = col.push(label);
+            is_empty = false;
+
+            for (entry_index, entry) in field.history.iter().enumerate() {
+                let changed = match Local.timestamp_opt(entry.changed, 0) {
+                    MappedLocalTime::Single(t) => t.format(DATA_FORMAT).to_string(),
+                    _ => String::new(),
+                };
+                let value: SmartInput<'a, Event> = SmartInput::new()
+                    .set_value(&entry.value)
+                    // a hack event because wayland has issue with cusor
+                    .on_input(Event::HandleHistoryCopy)
+                    .set_copy(Event::HandleHistoryCopy(entry.value.clone()))
+                    .padding(5)
+                    .set_secure(field.hide);
+                let changed = Text::new(changed)
+                    .size(TEXT_SIZE)
+                    .style(zebra_ui::styles::text::muted);
+                let mut row = Row::new()
+                    .align_items(iced::Alignment::Center)
+                    .spacing(ITEM_SPACING)
+                    .push(Container::new(value).width(Length::Fill))
+                    .push(changed);
+
+                if self.on_restore_history.is_some() {
+                    let restore_btn = Button::new(Text::new(t!("restore")).size(TEXT_SIZE))
+                        .padding(0)
+                        .style(zebra_ui::styles::button::ref_primary)
+                        .on_press(Event::HandleHistoryRestore(field_index, entry_index));
+
+                    row = row.push(restore_btn);
+                }
+
+                col = col.push(row);
+            }
+        }
+
+        if is_empty {
+            return None;
+        }
+
+        Some(Container::new(col))
+    }
+
+    fn view_multiline_editor(
+        &self,
+        index: usize,
+        title: &str,
+    ) -> Option<iced::advanced::graphics::core::Element<'_, Event, Theme, Renderer>> {
+        let (_, content) = self.multiline.iter().find(|(i, _)| *i == index)?;
+        let label = Text::new(title.to_string())
+            .size(14)
+            .style(zebra_ui::styles::text::muted);
+        let editor = text_editor(content)
+            .height(120)
+            .padding(INPUT_PADDING)
+            .style(zebra_ui::styles::text_editor::primary)
+            .on_action(move |a| Event::HandleActionField(index, a));
+
+        Some(Column::new().spacing(4).push(label).push(editor).into())
+    }
+
+    // a live code instead of the secret, TOTP gets a countdown ring.
+    pub fn view_otp(
+        &self,
+        index: usize,
+        field: &'a record::Item,
+    ) -> Option<Container<'a, Event, Theme, Renderer>> {
+        if !self.read_only || field.kind != record::ItemKind::Totp {
+            return None;
+        }
+
+        let auth = OtpAuth::parse(&field.value).ok()?;
+        let now = Local::now().timestamp();
+        let label = Text::new(field.title.clone())
+            .size(14)
+            .style(zebra_ui::styles::text::muted);
+        let mut row = Row::new()
+            .spacing(ITEM_SPACING)
+            .align_items(iced::Alignment::Center);
+
+        match (auth.kind, auth.remaining(now)) {
+            (OtpKind::Totp { period }, Some(remaining)) => {
+                let code = auth.code(now).ok()?;
+                let ring = Circular::new()
+                    .size(24.0)
+                    .bar_height(3.0)
+                    .progress(remaining as f32 / period as f32);
+                let copy_btn = Button::new(
+                    zebra_ui::image::copy_icon()
+                        .style(zebra_ui::styles::svg::primary_hover)
+                        .height(25)
+                        .width(25),
+                )
+                .padding(0)
+                .style(zebra_ui::styles::button::transparent)
+                .on_press(Event::HandleCopyCode(code.clone()));
+
+                row = row
+                    .push(Text::new(format_code(&code)).size(24).width(Length::Fill))
+                    .push(Text::new(format!("{}s", remaining)).size(13))
+                    .push(ring)
+                    .push(copy_btn);
+            }
+            _ => {
+                let next_btn = Button::new(Text::new(t!("next_code")).size(14))
+                    .padding(0)
+                    .style(zebra_ui::styles::button::ref_primary)
+                    .on_press_maybe(
+                        self.on_next_hotp
+                            .as_ref()
+                            .map(|_| Event::HandleNextHotp(index)),
+                    );
+
+                row = row
+                    .push(Text::new(t!("hotp_hint")).size(14).width(Length::Fill))
+                    .push(next_btn);
+            }
+        }
+
+        let col = Column::new().spacing(4).push(label).push(row);
+
+        Some(
+            Container::new(col)
+                .padding(INPUT_PADDING)
+                .width(Length::Fill)
+                .style(zebra_ui::styles::container::primary_bordered_disabled),
+        )
+    }
+
+    pub fn view_reprompt(&self) -> Option<Checkbox<'a, Event, Theme, Renderer>> {
+        if self.read_only {
+            return None;
+        }
+
+        Some(
+            Checkbox::new(t!("reprompt_check_box"), self.element.reprompt)
+                .on_toggle(Event::HandleToggleReprompt)
+                .text_size(14)
+                .width(Length::Fill),
+        )
+    }
+
     pub fn view_remove_button(&self) -> Option<Container<'a, Event, Theme, Renderer>> {
         if self.read_only || self.on_remove.is_none() {
             return None;
@@ -380,6 +966,20 @@ where
                     None
                 }
             }
+            Event::HandleActionField(index, a) => {
+                let (_, content) = self.multiline.iter_mut().find(|(i, _)| *i == index)?;
+
+                content.perform(a);
+
+                let value = content.text();
+                let on_submit = self.on_input.as_ref()?;
+                let mut new_element = self.element.clone();
+
+                new_element.updated = Local::now().timestamp();
+                new_element.fields.get_mut(index)?.value = value;
+
+                Some(on_submit(new_element))
+            }
             Event::HandleActionNote(a) => {
                 self.content.perform(a);
 
@@ -445,6 +1045,98 @@ where
                 self.on_copy.as_ref().map(|on_copy| on_copy(value.clone()))
             }
             Event::HandleEdit => self.on_edit.clone(),
+            Event::HandleCopyId => self
+                .on_copy
+                .as_ref()
+                .map(|on_copy| on_copy(self.element.id.to_string())),
+            Event::HandleHistoryCopy(value) => {
+                self.on_copy.as_ref().map(|on_copy| on_copy(value.clone()))
+            }
+            Event::HandleSelectFolder(choice) => {
+                let mut new_element = self.element.clone();
+
+                new_element.folder = choice.id;
+
+                self.on_input.as_ref().map(|on_input| on_input(new_element))
+            }
+            Event::HandleToggleReprompt(reprompt) => {
+                let mut new_element = self.element.clone();
+
+                new_element.reprompt = reprompt;
+
+                self.on_input.as_ref().map(|on_input| on_input(new_element))
+            }
+            Event::HandleToggleTag(id) => {
+                let mut new_element = self.element.clone();
+
+                match new_element.tags.iter().position(|tag| *tag == id) {
+                    Some(index) => {
+                        new_element.tags.remove(index);
+                    }
+                    None => new_element.tags.push(id),
+                }
+
+                self.on_input.as_ref().map(|on_input| on_input(new_element))
+            }
+            Event::HandleAddUri => {
+                let mut new_element = self.element.clone();
+
+                new_element.uris.push(LoginUri::default());
+
+                self.on_input.as_ref().map(|on_input| on_input(new_element))
+            }
+            Event::HandleRemoveUri(index) => {
+                let mut new_element = self.element.clone();
+
+                if index < new_element.uris.len() {
+                    new_element.uris.remove(index);
+                }
+
+                self.on_input.as_ref().map(|on_input| on_input(new_element))
+            }
+            Event::HandleUriInput(index, value) => {
+                let mut new_element = self.element.clone();
+
+                new_element.uris.get_mut(index)?.uri = value;
+
+                self.on_input.as_ref().map(|on_input| on_input(new_element))
+            }
+            Event::HandleUriMode(index, UriMatchOption(mode)) => {
+                let mut new_element = self.element.clone();
+
+                new_element.uris.get_mut(index)?.mode = mode;
+
+                self.on_input.as_ref().map(|on_input| on_input(new_element))
+            }
+            Event::HandleCopyCode(code) => self.on_copy.as_ref().map(|on_copy| on_copy(code)),
+            Event::HandleNextHotp(index) => self
+                .on_next_hotp
+                .as_ref()
+                .map(|on_next_hotp| on_next_hotp(index)),
+            Event::HandleOpen(link) => {
+                let _ = open::that_detached(link);
+
+                None
+            }
+            Event::HandleHistoryRestore(field, entry) => self
+                .on_restore_history
+                .as_ref()
+                .map(|on_restore| on_restore(field, entry)),
+            Event::HandleAttach => self.on_attach.clone(),
+            Event::HandlePreviewAttachment(id) => self
+                .on_preview_attachment
+                .as_ref()
+                .map(|on_preview| on_preview(id)),
+            Event::HandleSaveAttachment(id) => {
+                self.on_save_attachment.as_ref().map(|on_save| on_save(id))
+            }
+            Event::HandleOpenReference(id) => {
+                self.on_open_reference.as_ref().map(|on_open| on_open(id))
+            }
+            Event::HandleRemoveAttachment(id) => self
+                .on_remove_attachment
+                .as_ref()
+                .map(|on_remove| on_remove(id)),
         }
     }
 
@@ -465,15 +1157,17 @@ where
             .size(16),
         )
         .style(zebra_ui::styles::button::outline_primary)
-        .on_press_maybe(if !self.element.name.is_empty() {
-            Some(if self.read_only {
-                Event::HandleEdit
+        .on_press_maybe(
+            if !self.element.name.is_empty() && self.element.validate().is_ok() {
+                Some(if self.read_only {
+                    Event::HandleEdit
+                } else {
+                    Event::HandleSave
+                })
             } else {
-                Event::HandleSave
-            })
-        } else {
-            None
-        });
+                None
+            },
+        );
         let head_row = Row::new()
             .push(Space::new(INDENT_HEAD, 0))
             .push(title)
@@ -497,13 +1191,15 @@ where
             .element
             .fields
             .iter()
-            .filter(|&x| !self.read_only || !x.value.is_empty())
             .enumerate()
+            .filter(|(_, x)| !self.read_only || !x.value.is_empty())
             .map(|(index, field)| {
+                let error = field.validate().err();
                 let mut input = SmartInput::new()
                     .set_value(&field.value)
                     .padding(INPUT_PADDING)
                     .set_secure(field.hide)
+                    .set_danger(error.is_some())
                     .on_input(move |v| Event::HandleInputFieldValue(index, v))
                     .set_placeholder(field.title.clone().into());
 
@@ -515,12 +1211,46 @@ where
                     input = input.set_copy(Event::HandleInputFieldCopy(index));
                 }
 
-                input.into()
+                if let Some(link) = field.kind.link(&field.value) {
+                    input = input.set_open(Event::HandleOpen(link));
+                }
+
+                // a hidden one stays masked until the record is edited.
+                if field.kind == ItemKind::Multiline && !self.read_only {
+                    if let Some(editor) = self.view_multiline_editor(index, &field.title) {
+                        return editor;
+                    }
+                } else if field.kind == ItemKind::Multiline && !field.hide {
+                    return view_multiline(&field.title, &field.value);
+                }
+
+                if let Some(otp) = self.view_otp(index, field) {
+                    return otp.into();
+                }
+
+                match error {
+                    Some(e) => {
+                        let error = Text::new(validation_error(&e))
+                            .size(13)
+                            .style(zebra_ui::styles::text::danger)
+                            .width(Length::Fill);
+
+                        Column::new().spacing(4).push(input).push(error).into()
+                    }
+                    None => match self.view_reference(index) {
+                        Some(link) => Column::new().spacing(4).push(input).push(link).into(),
+                        None => input.into(),
+                    },
+                }
             })
             .collect();
 
         fields.insert(0, name_field.into());
 
+        if let Some(organize) = self.view_organize() {
+            fields.insert(1, organize.into());
+        }
+
         let note_label = Text::new(t!("label_notes"))
             .size(14)
             .style(zebra_ui::styles::text::muted)
@@ -539,7 +1269,16 @@ where
                 .element
                 .extra_fields
                 .iter()
-                .map(|field| {
+                .enumerate()
+                .map(|(index, field)| {
+                    if field.kind == ItemKind::Multiline && !field.hide {
+                        return view_multiline(&field.title, &field.value);
+                    }
+
+                    if let Some(otp) = self.view_otp(self.element.fields.len() + index, field) {
+                        return otp.into();
+                    }
+
                     let mut new_field: SmartInput<'_, Event> = SmartInput::new()
                         .set_value(&field.value)
                         .set_label(&field.title)
@@ -553,7 +1292,14 @@ where
                             .set_copy(Event::HandleInputExtraFieldCopy(field.value.clone()));
                     }
 
-                    new_field.into()
+                    if let Some(link) = field.kind.link(&field.value) {
+                        new_field = new_field.set_open(Event::HandleOpen(link));
+                    }
+
+                    match self.view_reference(self.element.fields.len() + index) {
+                        Some(link) => Column::new().spacing(4).push(new_field).push(link).into(),
+                        None => new_field.into(),
+                    }
                 })
                 .collect();
 
@@ -577,8 +1323,11 @@ where
             .width(Length::Fill)
             .align_items(iced::Alignment::Center)
             .push(Space::new(0, INDENT_HEAD))
+            .push_maybe(self.view_uris())
             .push(custom_fields)
             .push(Space::new(0, INDENT_HEAD))
+            .push_maybe(self.view_attachments())
+            .push_maybe(self.view_history())
             .push_maybe(if self.read_only && self.element.note.is_empty() {
                 None
             } else {
@@ -589,6 +1338,7 @@ where
             } else {
                 Some(notes)
             })
+            .push_maybe(self.view_reprompt())
             .push_maybe(self.view_timestamp())
             .push_maybe(self.view_remove_button())
             .push(Space::new(0, INDENT_HEAD));
@@ -617,6 +1367,67 @@ where
     }
 }
 
+// 1536 -> "1.5 KiB".
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = size as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", size, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+// "123456" -> "123 456".
+fn format_code(code: &str) -> String {
+    let (head, tail) = code.split_at(code.len() / 2);
+
+    format!("{} {}", head, tail)
+}
+
+fn view_multiline<'a>(
+    title: &str,
+    value: &'a str,
+) -> iced::advanced::graphics::core::Element<'a, Event, Theme, Renderer> {
+    let label = Text::new(title.to_string())
+        .size(14)
+        .style(zebra_ui::styles::text::muted);
+    let value = Text::new(value).size(16).width(Length::Fill);
+
+    Container::new(Column::new().spacing(4).push(label).push(value))
+        .padding(INPUT_PADDING)
+        .width(Length::Fill)
+        .style(zebra_ui::styles::container::primary_bordered_disabled)
+        .into()
+}
+
+pub fn validation_error(error: &ZebraErrors) -> String {
+    match error {
+        ZebraErrors::CoreInvalidEmail => t!("invalid_email"),
+        ZebraErrors::CoreInvalidCardNumber => t!("invalid_card_number"),
+        ZebraErrors::CoreInvalidExpiry => t!("invalid_card_expiry"),
+        ZebraErrors::CoreInvalidCvv => t!("invalid_cvv"),
+        ZebraErrors::CoreInvalidIban => t!("invalid_iban"),
+        ZebraErrors::CoreInvalidBic => t!("invalid_bic"),
+        ZebraErrors::CoreInvalidDate => t!("invalid_date"),
+        ZebraErrors::CoreInvalidUrl => t!("invalid_url"),
+        ZebraErrors::CoreInvalidPhone => t!("invalid_phone"),
+        ZebraErrors::CoreInvalidNumber => t!("invalid_number"),
+        ZebraErrors::CoreInvalidTotp => t!("invalid_totp"),
+        ZebraErrors::CoreInvalidUriRegex => t!("invalid_uri_regex"),
+        e => return e.to_string(),
+    }
+    .to_string()
+}
+
 impl<'a, Message> From<AddRecordForm<'a, Message>> for Element<'a, Message>
 where
     Message: 'a + Clone,
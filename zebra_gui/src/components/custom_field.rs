@@ -1,12 +1,13 @@
 //! -- Copyright (c) 2024 Rina Khasanshin
 //! -- Email: hicarus@yandex.ru
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
-use iced::widget::{component, Button, Checkbox, Column, Component, Container, Row, Space, Text};
+use iced::widget::{component, pick_list, Button, Column, Component, Container, Row, Space, Text};
 use iced::{Element, Length, Padding, Renderer, Theme};
 use rust_i18n::t;
 
+use super::add_record_from::validation_error;
 use super::smart_input::SmartInput;
-use zebra_lib::core::record::Item;
+use zebra_lib::core::record::{Item, ItemKind};
 
 pub struct CustomFields<'a, Message>
 where
@@ -14,7 +15,7 @@ where
 {
     on_input: Option<Box<dyn Fn(Vec<Item>) -> Message + 'a>>,
     on_copy: Option<Box<dyn Fn(String) -> Message + 'a>>,
-    check_box_secure: bool,
+    kind: ItemKind,
     input_padding: u16,
     container_padding: Padding,
     list: &'a [Item],
@@ -27,10 +28,22 @@ pub enum Event {
     InputCopy(usize),
     InputCustomField((usize, String)),
     RemoveCustomField(usize),
-    CheckedSecure(bool),
+    SelectKind(KindOption),
+    Open(String),
     InputLabel(String),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KindOption(pub ItemKind);
+
+impl std::fmt::Display for KindOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let key = format!("kind_{:?}", self.0).to_lowercase();
+
+        write!(f, "{}", t!(&key))
+    }
+}
+
 impl<'a, Message> Default for CustomFields<'a, Message>
 where
     Message: Clone,
@@ -51,7 +64,7 @@ where
             on_input: None,
             on_copy: None,
             list: &[],
-            check_box_secure: false,
+            kind: ItemKind::Text,
             label: String::new(),
         }
     }
@@ -106,9 +119,11 @@ where
                     new_list.push(Item {
                         title: self.label.to_string(),
                         value: String::new(),
-                        hide: self.check_box_secure,
+                        kind: self.kind,
+                        hide: self.kind.is_secret(),
                         copy: true,
                         reload: false,
+                        ..Default::default()
                     });
 
                     Some(cb(new_list))
@@ -132,8 +147,13 @@ where
                     None
                 }
             }
-            Event::CheckedSecure(v) => {
-                self.check_box_secure = v;
+            Event::SelectKind(option) => {
+                self.kind = option.0;
+
+                None
+            }
+            Event::Open(link) => {
+                let _ = open::that_detached(link);
 
                 None
             }
@@ -177,18 +197,30 @@ where
                 .on_press(Event::RemoveCustomField(index))
                 .width(30)
                 .style(zebra_ui::styles::button::transparent);
+                let error = field.validate().err();
                 let mut new_field: SmartInput<'_, Event> = SmartInput::new()
                     .set_value(&field.value)
                     .set_label(&field.title)
                     .padding(self.input_padding)
                     .on_input(move |v| Event::InputCustomField((index, v)))
+                    .set_danger(error.is_some())
                     .set_secure(field.hide);
 
                 if !field.value.is_empty() {
                     new_field = new_field.set_copy(Event::InputCopy(index));
                 }
 
-                let field = Container::new(new_field).width(Length::FillPortion(2));
+                if let Some(link) = field.kind.link(&field.value) {
+                    new_field = new_field.set_open(Event::Open(link));
+                }
+
+                let error = error.map(|e| {
+                    Text::new(validation_error(&e))
+                        .size(13)
+                        .style(zebra_ui::styles::text::danger)
+                });
+                let field = Container::new(Column::new().push(new_field).push_maybe(error))
+                    .width(Length::FillPortion(2));
                 let field_row = Row::new()
                     .push(field)
                     .push(Space::new(5, 0))
@@ -223,13 +255,18 @@ where
             .on_submit(Event::AddNewField)
             .on_input(Event::InputLabel);
         let label_field = Container::new(label_field).width(200);
-        let secure_checkbox = Checkbox::new(t!("placeholder_password"), self.check_box_secure)
-            .on_toggle(Event::CheckedSecure)
-            .text_size(14);
+        let kind_pick_list = pick_list(
+            ItemKind::ALL.map(KindOption),
+            Some(KindOption(self.kind)),
+            Event::SelectKind,
+        )
+        .text_size(14)
+        .padding(4)
+        .style(zebra_ui::styles::pick_list::primary_field);
         let row_new_item = Row::new()
             .push(add_btn)
             .push(Space::new(8, 0))
-            .push(secure_checkbox)
+            .push(kind_pick_list)
             .push(Space::new(8, 0))
             .push(label_field)
             .align_items(iced::Alignment::Center);
@@ -11,6 +11,7 @@ pub enum NavRoute {
     Home,
     Gen,
     Settings,
+    Security,
     None,
 }
 
@@ -18,6 +19,7 @@ pub struct NavBar<'a, Message> {
     on_home: Option<&'a Message>,
     on_gen: Option<&'a Message>,
     on_settings: Option<&'a Message>,
+    on_security: Option<&'a Message>,
     on_add: Option<&'a Message>,
     route: NavRoute,
 }
@@ -29,6 +31,7 @@ impl<'a, Message: Clone> NavBar<'a, Message> {
             on_home: None,
             on_gen: None,
             on_settings: None,
+            on_security: None,
             on_add: None,
         }
     }
@@ -63,6 +66,12 @@ impl<'a, Message: Clone> NavBar<'a, Message> {
         self
     }
 
+    pub fn on_security(mut self, msg: &'a Message) -> Self {
+        self.on_security = Some(msg);
+
+        self
+    }
+
     pub fn view(&self, content: Container<'a, Message>) -> Container<'a, Message> {
         let header = self.view_header();
         let vline = zebra_ui::components::line::Linear::new()
@@ -118,6 +127,7 @@ impl<'a, Message: Clone> NavBar<'a, Message> {
             .push(Space::new(Length::Fill, 60))
             .push(self.vew_home_btn())
             .push(self.vew_gen_btn())
+            .push(self.vew_security_btn())
             .push(self.vew_settings_btn());
 
         Container::new(content_col).width(60).height(Length::Fill)
@@ -203,4 +213,31 @@ impl<'a, Message: Clone> NavBar<'a, Message> {
             .push(Space::new(10.0, 0))
             .push(lock_btn)
     }
+
+    fn vew_security_btn(&self) -> Row<'a, Message> {
+        let vline = zebra_ui::components::line::Linear::new()
+            .width(Length::Fixed(3.0))
+            .style(if self.route == NavRoute::Security {
+                zebra_ui::styles::line::line_inverse
+            } else {
+                zebra_ui::styles::line::line_transparent
+            })
+            .height(Length::Fixed(40.0));
+        let security_btn = Button::new(zebra_ui::image::shield_icon().height(25).width(25).style(
+            if self.route == NavRoute::Security {
+                zebra_ui::styles::svg::bg_inverse
+            } else {
+                zebra_ui::styles::svg::primary_hover
+            },
+        ))
+        .padding(0)
+        .style(zebra_ui::styles::button::transparent)
+        .on_press_maybe(self.on_security.cloned());
+
+        Row::new()
+            .align_items(iced::Alignment::Center)
+            .push(vline)
+            .push(Space::new(10.0, 0))
+            .push(security_btn)
+    }
 }
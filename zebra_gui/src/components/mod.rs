@@ -6,6 +6,7 @@ pub mod custom_field;
 pub mod general_settings;
 pub mod home_nav_bar;
 pub mod modal;
+pub mod organize;
 pub mod passgen;
 pub mod phrasegen;
 pub mod profile_view;
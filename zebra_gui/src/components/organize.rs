@@ -0,0 +1,59 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::fmt;
+
+use zebra_lib::core::organize::{Folder, Tag};
+use zebra_lib::core::record::Uuid;
+
+// An option of pick lists with folders or tags, `None` stands for "no folder".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub id: Option<Uuid>,
+    pub label: String,
+}
+
+impl fmt::Display for Choice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+pub fn folder_path(folders: &[Folder], id: &Uuid) -> String {
+    let mut path = Vec::new();
+    let mut current = Some(*id);
+
+    while let Some(folder) = current.and_then(|id| folders.iter().find(|f| f.id == id)) {
+        if path.len() > folders.len() {
+            break;
+        }
+
+        path.insert(0, folder.name.as_str());
+        current = folder.parent;
+    }
+
+    path.join(" / ")
+}
+
+pub fn folder_choices(folders: &[Folder]) -> Vec<Choice> {
+    let mut choices: Vec<Choice> = folders
+        .iter()
+        .map(|folder| Choice {
+            id: Some(folder.id),
+            label: folder_path(folders, &folder.id),
+        })
+        .collect();
+
+    choices.sort_by(|a, b| a.label.cmp(&b.label));
+
+    choices
+}
+
+pub fn tag_choices(tags: &[Tag]) -> Vec<Choice> {
+    tags.iter()
+        .map(|tag| Choice {
+            id: Some(tag.id),
+            label: format!("#{}", tag.name),
+        })
+        .collect()
+}
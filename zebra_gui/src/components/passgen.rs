@@ -4,7 +4,7 @@
 
 use crate::rust_i18n::t;
 use iced::widget::{
-    component, slider, text_input, Button, Checkbox, Column, Component, Container, Row,
+    component, slider, text_input, Button, Checkbox, Column, Component, Container, Row, Text,
 };
 use iced::Renderer;
 use iced::Theme;
@@ -17,6 +17,8 @@ use zebra_lib::errors::ZebraErrors;
 pub struct PassGenState {
     pub value: String,
     pub length: u8,
+    // breach count of `value`, `None` until it was looked up.
+    pub breached: Option<Option<u32>>,
 }
 
 pub struct PassGenForm<Message>
@@ -26,6 +28,8 @@ where
     state: Arc<Mutex<PassGenState>>,
     generator: PassGen,
     copy_msg: Option<Message>,
+    // times a password was seen in breaches, `None` when unknown.
+    breach_check: Option<Box<dyn Fn(&str) -> Option<u32>>>,
     height: Length,
 }
 
@@ -62,6 +66,7 @@ where
             state,
             generator,
             copy_msg: None,
+            breach_check: None,
             height: Length::Fill,
         })
     }
@@ -77,12 +82,33 @@ where
         self
     }
 
+    // the check runs once per generated password, not on every redraw.
+    pub fn on_breach_check<F>(mut self, check: F) -> Self
+    where
+        F: Fn(&str) -> Option<u32> + 'static,
+    {
+        let mut state = self.state.lock().unwrap(); // TODO: remove unwrap..
+
+        if state.breached.is_none() {
+            state.breached = Some(check(&state.value));
+        }
+
+        drop(state);
+        self.breach_check = Some(Box::new(check));
+
+        self
+    }
+
     pub fn regenerate(&self) {
         let mut rng = rand::thread_rng(); // TODO: change to ChaCha
         let mut locked_state = self.state.lock().unwrap(); // TODO: remove unwrap..
 
         if let Ok(bytes) = self.generator.gen(locked_state.length as usize, &mut rng) {
             locked_state.value = String::from_utf8_lossy(&bytes).to_string();
+            locked_state.breached = self
+                .breach_check
+                .as_ref()
+                .map(|check| check(&locked_state.value));
         }
     }
 
@@ -160,7 +186,23 @@ where
         let border_box = Container::new(box_row)
             .style(zebra_ui::styles::container::primary_bordered)
             .padding(16);
-        let col = Column::new().push(border_box);
+        let breached = self
+            .state
+            .lock()
+            .unwrap() // TODO: remove unwrap..
+            .breached
+            .flatten()
+            .filter(|count| *count > 0)
+            .map(|count| {
+                Text::new(t!("password_breached", count => count))
+                    .size(14)
+                    .style(zebra_ui::styles::text::danger)
+            });
+        let col = Column::new()
+            .spacing(8)
+            .align_items(iced::Alignment::Center)
+            .push(border_box)
+            .push_maybe(breached);
 
         Container::new(col)
     }
@@ -11,6 +11,7 @@ use iced::{Element, Length, Renderer, Theme};
 use rust_i18n::t;
 use zebra_ui::components::line::Linear;
 
+use super::add_record_from::format_size;
 use super::modal::Modal;
 use crate::components::{home_nav_bar::LINE_ALFA_CHANNEL, smart_field::SmartFields};
 
@@ -23,6 +24,7 @@ where
     data_dir_path: Cow<'a, str>,
     storage_version: String,
     data_size: String,
+    attachments_size: String,
     records_len: String,
     main_padding: f32,
     item_padding: f32,
@@ -32,6 +34,7 @@ where
     on_edit_email: Option<Box<dyn Fn(String) -> Message + 'a>>,
     on_export_database: Option<Message>,
     on_export_records: Option<Message>,
+    on_move_vault: Option<Message>,
 }
 
 #[derive(Debug, Clone)]
@@ -42,6 +45,7 @@ pub enum Event {
     ExportRecords,
     EditEmail,
     ExportDatabase,
+    MoveVault,
     InputEmail(String),
 }
 
@@ -65,6 +69,7 @@ where
             data_dir_path: Cow::default(),
             storage_version: String::new(),
             data_size: String::new(),
+            attachments_size: String::new(),
             records_len: String::new(),
             item_padding: Default::default(),
             main_padding: Default::default(),
@@ -74,6 +79,7 @@ where
             on_edit_email: None,
             on_export_records: None,
             on_export_database: None,
+            on_move_vault: None,
         }
     }
 
@@ -97,6 +103,11 @@ where
         self
     }
 
+    pub fn set_attachments_size(mut self, value: u64) -> Self {
+        self.attachments_size = format_size(value);
+        self
+    }
+
     pub fn set_storage_version(mut self, value: u16) -> Self {
         self.storage_version = format!("V{}", value);
         self
@@ -136,6 +147,11 @@ where
         self
     }
 
+    pub fn on_move_vault(mut self, msg: Message) -> Self {
+        self.on_move_vault = Some(msg);
+        self
+    }
+
     pub fn on_copy<F>(mut self, callback: F) -> Self
     where
         F: 'a + Fn(String) -> Message,
@@ -269,6 +285,7 @@ where
                 .as_ref()
                 .map(|cb| cb(self.email.to_string())),
             Event::ExportDatabase => self.on_export_database.clone(),
+            Event::MoveVault => self.on_move_vault.clone(),
             Event::InputEmail(value) => {
                 self.email = value.into();
 
@@ -314,12 +331,17 @@ where
             .set_value(&self.records_len);
         let records = Container::new(records);
 
-        let data_dir = SmartFields::new()
+        let mut data_dir = SmartFields::new()
             .set_label(t!("database_path"))
             .set_padding(self.item_padding)
             .on_copy(Event::CopyValue)
             .on_export(Event::ExportDatabase)
             .set_value(&self.data_dir_path);
+
+        if self.on_move_vault.is_some() {
+            data_dir = data_dir.on_edit(Event::MoveVault);
+        }
+
         let data_dir = Container::new(data_dir);
 
         let data_size = SmartFields::new()
@@ -328,6 +350,12 @@ where
             .set_value(&self.data_size);
         let data_size = Container::new(data_size);
 
+        let attachments_size = SmartFields::new()
+            .set_label(t!("attachments_size"))
+            .set_padding(self.item_padding)
+            .set_value(&self.attachments_size);
+        let attachments_size = Container::new(attachments_size);
+
         let storage_version = SmartFields::new()
             .set_label(t!("storage_version"))
             .set_padding(self.item_padding)
@@ -345,6 +373,8 @@ where
             .push(self.view_hline())
             .push(data_size)
             .push(self.view_hline())
+            .push(attachments_size)
+            .push(self.view_hline())
             .push(storage_version);
         let main_content = Container::new(border_col)
             .padding(Padding {
@@ -12,6 +12,7 @@ where
     showed_secure_flag: bool,
     on_reload: Option<Message>,
     on_copy: Option<Message>,
+    on_open: Option<Message>,
     on_input: Option<Box<dyn Fn(String) -> Message + 'a>>,
     on_submit: Option<Message>,
     padding: u16,
@@ -27,6 +28,7 @@ where
 #[derive(Debug, Clone)]
 pub enum Event {
     Copy,
+    Open,
     Reload,
     ShowHideSecure,
     HandleSubmit,
@@ -68,6 +70,7 @@ where
             showed_secure_flag: false,
             on_reload: None,
             on_copy: None,
+            on_open: None,
             on_input: None,
             on_submit: None,
         }
@@ -103,6 +106,13 @@ where
         self
     }
 
+    // an "open" button for links, files and the like.
+    pub fn set_open(mut self, open_msg: Message) -> Self {
+        self.on_open = Some(open_msg);
+
+        self
+    }
+
     pub fn on_input<F>(mut self, callback: F) -> Self
     where
         F: 'a + Fn(String) -> Message,
@@ -158,6 +168,7 @@ where
     fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
         match event {
             Event::Copy => self.on_copy.clone(),
+            Event::Open => self.on_open.clone(),
             Event::Reload => self.on_reload.clone(),
             Event::ShowHideSecure => {
                 self.showed_secure_flag = !self.showed_secure_flag;
@@ -252,6 +263,19 @@ where
             row = row.push(copy_btn);
         }
 
+        if self.on_open.is_some() {
+            let open_btn = Button::new(
+                zebra_ui::image::forward_icon()
+                    .style(zebra_ui::styles::svg::primary_hover)
+                    .height(25)
+                    .width(25),
+            )
+            .padding(0)
+            .style(zebra_ui::styles::button::transparent)
+            .on_press(Event::Open);
+            row = row.push(open_btn);
+        }
+
         if self.on_reload.is_some() {
             let reload_btn = Button::new(
                 zebra_ui::image::reload_icon()
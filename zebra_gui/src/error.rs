@@ -1,23 +1,39 @@
 //! -- Copyright (c) 2023 Rina Khasanshin
 //! -- Email: hicarus@yandex.ru
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use crate::components::smart_input::SmartInput;
+use crate::pages::settings::view_report;
 use crate::rust_i18n::t;
 use iced::advanced::Application;
-use iced::widget::{Column, Row, Text};
-use iced::{executor, Command, Element};
+use iced::widget::{Button, Column, Container, Row, Space, Text};
+use iced::{executor, window, Command, Element};
 use iced::{Alignment, Length, Renderer, Theme};
+use rfd::FileDialog;
+use zebra_lib::core::{verify::VerifyReport, Core};
+use zebra_lib::errors::ZebraErrors;
 
 pub struct GUIError {
-    error: String,
+    error: ZebraErrors,
+    // kept when the vault opened but its state did not pass the checks.
+    core: Option<Core>,
+    password: String,
+    repair_error: String,
+    report: Option<VerifyReport>,
 }
 
-#[derive(Debug)]
-pub enum ErrorMessage {}
+#[derive(Debug, Clone)]
+pub enum ErrorMessage {
+    Exit,
+    PasswordInput(String),
+    Verify,
+    Repair,
+    RepairFromBackup,
+}
 
 impl Application for GUIError {
     type Executor = executor::Default;
     type Message = ErrorMessage;
-    type Flags = String;
+    type Flags = (ZebraErrors, Option<Core>);
     type Theme = Theme;
     type Renderer = Renderer;
 
@@ -25,21 +41,83 @@ impl Application for GUIError {
         "ZebraPass Error".into()
     }
 
-    fn new(error: Self::Flags) -> (Self, Command<Self::Message>) {
-        (Self { error }, Command::none())
+    fn new((error, core): Self::Flags) -> (Self, Command<Self::Message>) {
+        let core = match error {
+            ZebraErrors::StorageHashsumError | ZebraErrors::StorageDataBroken => core,
+            _ => None,
+        };
+
+        (
+            Self {
+                error,
+                core,
+                password: String::new(),
+                repair_error: String::new(),
+                report: None,
+            },
+            Command::none(),
+        )
     }
 
-    fn update(&mut self, _message: Self::Message) -> Command<Self::Message> {
-        Command::none()
+    fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+        match message {
+            ErrorMessage::Exit => window::close(window::Id::MAIN),
+            ErrorMessage::PasswordInput(value) => {
+                self.password = value;
+                self.repair_error = String::new();
+
+                Command::none()
+            }
+            ErrorMessage::Verify => {
+                if let Some(core) = &self.core {
+                    let result = core.verify(&self.password);
+
+                    self.handle_report(result);
+                }
+
+                Command::none()
+            }
+            ErrorMessage::Repair => {
+                if let Some(core) = &mut self.core {
+                    let result = core.repair(&self.password, None);
+
+                    self.handle_report(result);
+                }
+
+                Command::none()
+            }
+            ErrorMessage::RepairFromBackup => {
+                let file = FileDialog::new()
+                    .add_filter("zebra", &["zebra"])
+                    .set_directory(dirs::home_dir().unwrap_or_default())
+                    .pick_file();
+
+                if let (Some(core), Some(path)) = (&mut self.core, file) {
+                    let result = core.repair(&self.password, Some(&path));
+
+                    self.handle_report(result);
+                }
+
+                Command::none()
+            }
+        }
     }
 
     fn view(&self) -> Element<'_, Self::Message, Theme> {
+        if let ZebraErrors::StorageLocked = self.error {
+            return self.view_already_running();
+        }
+
+        if self.core.is_some() {
+            return self.view_repair();
+        }
+
         // TODO: make colors
         // let palette = match self.theme() {
         //     Theme::Dark(p) => p,
         //     Theme::Light(p) => p,
         // };
-        let message = Text::new(t!("error.message", error = &self.error))
+        let message = Text::new(t!("error.message", error = self.error.to_string()))
             .size(20)
             // .style(style::text::Text::Color(palette.danger))
             .horizontal_alignment(iced::alignment::Horizontal::Center);
@@ -68,3 +146,137 @@ impl Application for GUIError {
         }
     }
 }
+
+impl GUIError {
+    fn view_already_running(&self) -> Element<'_, ErrorMessage, Theme> {
+        let title = Text::new(t!("already_running_title"))
+            .size(20)
+            .horizontal_alignment(iced::alignment::Horizontal::Center);
+        let description = Text::new(t!("already_running_description"))
+            .size(14)
+            .horizontal_alignment(iced::alignment::Horizontal::Center);
+        let exit_btn = Button::new(
+            Text::new(t!("exit"))
+                .size(14)
+                .horizontal_alignment(iced::alignment::Horizontal::Center),
+        )
+        .padding(8)
+        .width(120)
+        .on_press(ErrorMessage::Exit);
+        let col = Column::new()
+            .width(400)
+            .align_items(Alignment::Center)
+            .push(zebra_ui::image::bad_zebra_view().width(160).height(120))
+            .push(title)
+            .push(Space::new(0, 8))
+            .push(description)
+            .push(Space::new(0, 16))
+            .push(exit_btn);
+        let row = Row::new()
+            .height(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(col);
+
+        Column::new()
+            .width(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(row)
+            .into()
+    }
+
+    // the state on disk is damaged, it can be restored without starting the vault.
+    fn view_repair(&self) -> Element<'_, ErrorMessage, Theme> {
+        let title = Text::new(t!("error.message", error = self.error.to_string()))
+            .size(20)
+            .horizontal_alignment(iced::alignment::Horizontal::Center);
+        let description = Text::new(t!("verify_vault_description"))
+            .size(14)
+            .horizontal_alignment(iced::alignment::Horizontal::Center);
+        let password_input = SmartInput::new()
+            .set_value(&self.password)
+            .padding(8)
+            .set_danger(!self.repair_error.is_empty())
+            .set_font_size(14)
+            .set_secure(true)
+            .set_placeholder(t!("placeholder_password"))
+            .on_input(ErrorMessage::PasswordInput)
+            .on_submit(ErrorMessage::Verify);
+        let password_input = Container::new(password_input).width(300);
+        let ready = !self.password.is_empty();
+        let verify_btn = Button::new(Text::new(t!("verify_vault")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(8)
+            .on_press_maybe(ready.then_some(ErrorMessage::Verify));
+        let repair_btn = Button::new(Text::new(t!("repair_vault")).size(14))
+            .style(zebra_ui::styles::button::outline_danger)
+            .padding(8)
+            .on_press_maybe(ready.then_some(ErrorMessage::Repair));
+        let backup_btn = Button::new(Text::new(t!("repair_from_backup")).size(14))
+            .style(zebra_ui::styles::button::outline_danger)
+            .padding(8)
+            .on_press_maybe(ready.then_some(ErrorMessage::RepairFromBackup));
+        let exit_btn = Button::new(Text::new(t!("exit")).size(14))
+            .padding(8)
+            .on_press(ErrorMessage::Exit);
+        let buttons_row = Row::new()
+            .spacing(8)
+            .push(verify_btn)
+            .push(repair_btn)
+            .push(backup_btn)
+            .push(exit_btn);
+        let mut col = Column::new()
+            .width(500)
+            .align_items(Alignment::Center)
+            .push(zebra_ui::image::bad_zebra_view().width(160).height(120))
+            .push(title)
+            .push(Space::new(0, 8))
+            .push(description)
+            .push(Space::new(0, 16))
+            .push(password_input)
+            .push(Space::new(0, 8))
+            .push(buttons_row);
+
+        if !self.repair_error.is_empty() {
+            let error = Text::new(&self.repair_error)
+                .size(14)
+                .style(zebra_ui::styles::text::danger);
+
+            col = col.push(Space::new(0, 8)).push(error);
+        }
+
+        if let Some(report) = &self.report {
+            col = col.push(Space::new(0, 16)).push(view_report(report));
+
+            if report.is_ok() {
+                let restart = Text::new(t!("repair_restart"))
+                    .size(14)
+                    .horizontal_alignment(iced::alignment::Horizontal::Center);
+
+                col = col.push(Space::new(0, 8)).push(restart);
+            }
+        }
+
+        let row = Row::new()
+            .height(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(col);
+
+        Column::new()
+            .width(Length::Fill)
+            .align_items(Alignment::Center)
+            .push(row)
+            .into()
+    }
+
+    fn handle_report(&mut self, result: Result<VerifyReport, ZebraErrors>) {
+        match result {
+            Ok(report) => {
+                self.report = Some(report);
+                self.repair_error = String::new();
+            }
+            Err(e) => {
+                self.repair_error = e.to_string();
+            }
+        }
+    }
+}
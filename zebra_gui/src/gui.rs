@@ -1,13 +1,25 @@
 //! -- Copyright (c) 2023 Rina Khasanshin
 //! -- Email: hicarus@yandex.ru
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+#[cfg(unix)]
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
+#[cfg(unix)]
+use std::time::Duration;
 
 use crate::pages::Page;
+#[cfg(unix)]
+use crate::rust_i18n::t;
 
 use super::pages;
 use iced::advanced::Application;
+#[cfg(unix)]
+use iced::widget::{Button, Column, Container, Row, Space, Text};
+#[cfg(unix)]
+use iced::Length;
 use iced::{executor, Command, Element, Renderer, Theme};
+#[cfg(unix)]
+use zebra_lib::agent::{self, Agent, ConfirmRequest};
 use zebra_lib::{core::Core, settings::appearance::Themes};
 
 #[derive(Debug)]
@@ -22,6 +34,7 @@ pub enum Routers {
     Home(pages::home::Home),
     Generator(pages::gen::Generator),
     Settings(pages::settings::Settings),
+    Security(pages::security::Security),
     Lock(pages::lock::Lock),
     AddRecord(pages::add_record::AddRecordPage),
     ErrorPage(pages::error::ErrorPage),
@@ -30,6 +43,14 @@ pub enum Routers {
 pub struct Gui {
     core: Arc<Mutex<Core>>,
     route: Routers,
+    #[cfg(unix)]
+    agent: Option<Agent>,
+    #[cfg(unix)]
+    confirm_tx: Sender<ConfirmRequest>,
+    #[cfg(unix)]
+    confirm_rx: Receiver<ConfirmRequest>,
+    #[cfg(unix)]
+    pending: Option<ConfirmRequest>,
 }
 
 #[derive(Debug)]
@@ -44,10 +65,13 @@ pub enum GlobalMessage {
     HomeMessage(pages::home::HomeMessage),
     GeneratorMessage(pages::gen::GeneratorMessage),
     SettingsMessage(pages::settings::SettingsMessage),
+    SecurityMessage(pages::security::SecurityMessage),
     LockMessage(pages::lock::LockMessage),
     ErrorPageMessage(pages::error::ErrorPageMessage),
     AddRecordPageMessage(pages::add_record::AddRecordPageMessage),
     Route(Routers),
+    AgentTick,
+    AgentConfirm(bool),
 }
 
 async fn load(_core: Arc<Mutex<Core>>) -> Result<(), ()> {
@@ -75,9 +99,22 @@ impl Application for Gui {
         // let loader = pages::loader::Loader::new(Arc::clone(&core)).unwrap(); // TODO: Remove unwrap
         // let route = Routers::Loading(loader);
         let core_ref = Arc::clone(&core);
+        #[cfg(unix)]
+        let (confirm_tx, confirm_rx) = mpsc::channel();
 
         (
-            Self { core, route },
+            Self {
+                core,
+                route,
+                #[cfg(unix)]
+                agent: None,
+                #[cfg(unix)]
+                confirm_tx,
+                #[cfg(unix)]
+                confirm_rx,
+                #[cfg(unix)]
+                pending: None,
+            },
             Command::perform(load(core_ref), |_| {
                 GlobalMessage::LoadMessage(pages::loader::LoadMessage::Synced)
             }),
@@ -85,7 +122,7 @@ impl Application for Gui {
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
-        match message {
+        let command = match message {
             GlobalMessage::LoadMessage(msg) => match &mut self.route {
                 Routers::Loading(view) => view.update(msg),
                 _ => Command::none(),
@@ -134,6 +171,10 @@ impl Application for Gui {
                 Routers::Settings(view) => view.update(msg),
                 _ => Command::none(),
             },
+            GlobalMessage::SecurityMessage(msg) => match &mut self.route {
+                Routers::Security(view) => view.update(msg),
+                _ => Command::none(),
+            },
             GlobalMessage::ErrorPageMessage(msg) => match &mut self.route {
                 Routers::ErrorPage(view) => view.update(msg),
                 _ => Command::none(),
@@ -142,11 +183,32 @@ impl Application for Gui {
                 self.route = route;
                 Command::none()
             }
-        }
+            GlobalMessage::AgentTick => {
+                #[cfg(unix)]
+                if self.pending.is_none() {
+                    self.pending = self.confirm_rx.try_recv().ok();
+                }
+
+                Command::none()
+            }
+            GlobalMessage::AgentConfirm(allow) => {
+                #[cfg(unix)]
+                if let Some(request) = self.pending.take() {
+                    let _ = request.reply.send(allow);
+                }
+
+                Command::none()
+            }
+        };
+
+        #[cfg(unix)]
+        self.sync_agent();
+
+        command
     }
 
     fn subscription(&self) -> iced::Subscription<Self::Message> {
-        iced::Subscription::batch([match &self.route {
+        let page = match &self.route {
             Routers::Loading(v) => v.subscription().map(GlobalMessage::LoadMessage),
             Routers::ErrorPage(v) => v.subscription().map(GlobalMessage::ErrorPageMessage),
             Routers::Interview(v) => v.subscription().map(GlobalMessage::InterviewMessage),
@@ -157,13 +219,25 @@ impl Application for Gui {
             Routers::PasswordSetup(v) => v.subscription().map(GlobalMessage::PasswordSetupMessage),
             Routers::Home(v) => v.subscription().map(GlobalMessage::HomeMessage),
             Routers::Settings(v) => v.subscription().map(GlobalMessage::SettingsMessage),
+            Routers::Security(v) => v.subscription().map(GlobalMessage::SecurityMessage),
             Routers::Generator(v) => v.subscription().map(GlobalMessage::GeneratorMessage),
             Routers::Lock(v) => v.subscription().map(GlobalMessage::LockMessage),
             Routers::AddRecord(v) => v.subscription().map(GlobalMessage::AddRecordPageMessage),
-        }])
+        };
+
+        iced::Subscription::batch([page, self.agent_subscription()])
     }
 
     fn view(&self) -> Element<'_, Self::Message, Self::Theme> {
+        // the prompt takes the window until it is answered.
+        #[cfg(unix)]
+        if let Some(request) = &self.pending {
+            return Container::new(view_confirm(&request.name, &request.fingerprint))
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .into();
+        }
+
         match &self.route {
             Routers::Loading(l) => l.view().map(GlobalMessage::LoadMessage),
             Routers::Locale(l) => l.view().map(GlobalMessage::LocaleMessage),
@@ -176,6 +250,7 @@ impl Application for Gui {
             Routers::Lock(l) => l.view().map(GlobalMessage::LockMessage),
             Routers::Generator(l) => l.view().map(GlobalMessage::GeneratorMessage),
             Routers::Settings(l) => l.view().map(GlobalMessage::SettingsMessage),
+            Routers::Security(l) => l.view().map(GlobalMessage::SecurityMessage),
             Routers::AddRecord(l) => l.view().map(GlobalMessage::AddRecordPageMessage),
             Routers::ErrorPage(l) => l.view().map(GlobalMessage::ErrorPageMessage),
         }
@@ -204,3 +279,79 @@ impl Application for Gui {
         }
     }
 }
+
+impl Gui {
+    // signature prompts are polled while the agent runs.
+    fn agent_subscription(&self) -> iced::Subscription<GlobalMessage> {
+        #[cfg(unix)]
+        if self.agent.is_some() {
+            return iced::time::every(Duration::from_millis(250)).map(|_| GlobalMessage::AgentTick);
+        }
+
+        iced::Subscription::none()
+    }
+
+    // the agent runs while the vault is unlocked and the settings ask for it.
+    #[cfg(unix)]
+    fn sync_agent(&mut self) {
+        let enabled = self
+            .core
+            .lock()
+            .map(|core| core.is_unlock() && core.state.settings.agent.enabled)
+            .unwrap_or(false);
+
+        if !enabled {
+            self.agent = None;
+
+            if let Some(request) = self.pending.take() {
+                let _ = request.reply.send(false);
+            }
+
+            return;
+        }
+
+        if self.agent.is_some() {
+            return;
+        }
+
+        self.agent = agent::socket_path()
+            .and_then(|path| {
+                Agent::start(Arc::clone(&self.core), &path, Some(self.confirm_tx.clone()))
+            })
+            .ok();
+    }
+}
+
+#[cfg(unix)]
+fn view_confirm<'a>(name: &str, fingerprint: &str) -> Container<'a, GlobalMessage> {
+    let title = Text::new(t!("agent_confirm_title")).size(16);
+    let description = Text::new(t!("agent_confirm_description", name => name))
+        .size(14)
+        .horizontal_alignment(iced::alignment::Horizontal::Center);
+    let fingerprint = Text::new(fingerprint.to_string())
+        .size(12)
+        .style(zebra_ui::styles::text::muted);
+    let deny_btn = Button::new(Text::new(t!("agent_deny")).size(14))
+        .style(zebra_ui::styles::button::outline_danger)
+        .padding(8)
+        .on_press(GlobalMessage::AgentConfirm(false));
+    let allow_btn = Button::new(Text::new(t!("agent_allow")).size(14))
+        .style(zebra_ui::styles::button::outline_primary)
+        .padding(8)
+        .on_press(GlobalMessage::AgentConfirm(true));
+    let buttons_row = Row::new().spacing(8).push(deny_btn).push(allow_btn);
+    let main_modal_col = Column::new()
+        .push(title)
+        .push(Space::new(0, 8))
+        .push(description)
+        .push(Space::new(0, 8))
+        .push(fingerprint)
+        .push(Space::new(0, 16))
+        .push(buttons_row)
+        .padding(16)
+        .align_items(iced::Alignment::Center);
+
+    Container::new(main_modal_col)
+        .width(400)
+        .style(zebra_ui::styles::container::primary_bordered_modal)
+}
@@ -7,7 +7,7 @@ use error::GUIError;
 use gui::Gui;
 use iced::{advanced::Application, window, Settings, Size};
 use rust_i18n::i18n;
-use zebra_lib::core::Core;
+use zebra_lib::{config::location::VaultLocation, core::Core};
 use zebra_ui::config::{WIN_HEIGHT, WIN_WIDTH};
 
 mod components;
@@ -27,12 +27,13 @@ fn main() -> iced::Result {
         icon: Some(zebra_ui::image::zebra_app_icon()),
         ..Default::default()
     };
-    let mut core = match Core::new() {
+    let location = VaultLocation::from_args(std::env::args().skip(1));
+    let mut core = match Core::from_location(location) {
         Ok(core) => core,
         Err(e) => {
             return GUIError::run(Settings {
                 window,
-                flags: e.to_string(),
+                flags: (e, None),
                 ..Default::default()
             });
         }
@@ -41,9 +42,10 @@ fn main() -> iced::Result {
     match core.sync() {
         Ok(_) => {}
         Err(e) => {
+            // a damaged state keeps the core around so it can be repaired.
             return GUIError::run(Settings {
                 window,
-                flags: e.to_string(),
+                flags: (e, Some(core)),
                 ..Default::default()
             });
         }
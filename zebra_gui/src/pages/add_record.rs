@@ -5,20 +5,24 @@ use rust_i18n::t;
 use std::sync::{Arc, Mutex};
 
 use iced::keyboard::key::Named;
-use iced::widget::{Column, Container, Row, Text};
+use iced::widget::{Button, Column, Container, Row, Text};
 use iced::{Command, Element, Length, Subscription};
 use zebra_lib::{core::Core, errors::ZebraErrors};
 
 use crate::components::add_record_from::AddRecordForm;
 use crate::components::home_nav_bar::{NavBar, NavRoute, LINE_ALFA_CHANNEL};
 use crate::components::select_list;
+use crate::components::smart_input::SmartInput;
 use crate::gui::{GlobalMessage, Routers};
 use iced::keyboard;
+use rfd::FileDialog;
 use zebra_lib::core::record;
+use zebra_lib::core::ssh::SshKeyType;
 
 use super::error::ErrorPage;
 use super::gen::Generator;
 use super::home::Home;
+use super::security::Security;
 use super::settings::Settings;
 use super::Page;
 
@@ -27,10 +31,14 @@ pub struct AddRecordPage {
     core: Arc<Mutex<Core>>,
     categories: Vec<select_list::SelectListField<record::Categories>>,
     selected_index: usize,
+    // for importing a protected OpenSSH key.
+    ssh_passphrase: String,
+    ssh_error: String,
 }
 
 #[derive(Debug, Clone)]
 pub enum AddRecordPageMessage {
+    RouteSecurity,
     RouteGen,
     RouteHome,
     RouteSettings,
@@ -39,6 +47,9 @@ pub enum AddRecordPageMessage {
     TabPressed(bool),
     HanldeSelectCategories(usize),
     HanldeInput(record::Element),
+    GenerateSshKey(SshKeyType),
+    ImportSshKey,
+    SshPassphraseInput(String),
 }
 
 impl Page for AddRecordPage {
@@ -46,96 +57,35 @@ impl Page for AddRecordPage {
 
     fn new(core: Arc<Mutex<Core>>) -> Result<Self, ZebraErrors> {
         let selected_index = 0;
-        let categories = vec![
-            select_list::SelectListField {
-                text: t!(&format!(
-                    "item_{}",
-                    record::Categories::Login(Default::default())
-                ))
-                .to_string(),
-                value: record::Categories::Login(record::Element {
-                    fields: vec![
-                        record::Item {
-                            title: t!("placeholder_domain").to_string(),
-                            value: String::new(),
-                            hide: false,
-                            copy: true,
-                            reload: false,
-                        },
-                        record::Item {
-                            title: t!("placeholder_username").to_string(),
-                            value: String::new(),
-                            hide: false,
-                            copy: true,
-                            reload: false,
-                        },
-                        record::Item {
-                            title: t!("placeholder_email").to_string(),
-                            value: String::new(),
-                            hide: false,
-                            copy: true,
-                            reload: false,
-                        },
-                        record::Item {
-                            title: t!("placeholder_password").to_string(),
-                            value: String::new(),
-                            hide: true,
-                            copy: true,
-                            reload: true,
-                        },
-                    ],
-                    extra_fields: Vec::new(),
-                    ..Default::default()
-                }),
-            },
-            // select_list::SelectListField {
-            //     text: t!(&format!(
-            //         "item_{}",
-            //         Categories::CryptoWallet(Default::default())
-            //     )),
-            //     value: Categories::CryptoWallet,
-            // },
-            // select_list::SelectListField {
-            //     text: t!(&format!(
-            //         "item_{}",
-            //         Categories::CreditCard(Default::default())
-            //     )),
-            //     value: Categories::CreditCard,
-            // },
-            // select_list::SelectListField {
-            //     text: t!(&format!("item_{}", Categories::Identity)),
-            //     value: Categories::Identity,
-            // },
-            // select_list::SelectListField {
-            //     text: t!(&format!("item_{}", Categories::BankAccount)),
-            //     value: Categories::BankAccount,
-            // },
-            // select_list::SelectListField {
-            //     text: t!(&format!("item_{}", Categories::EmailAccount)),
-            //     value: Categories::EmailAccount,
-            // },
-            // select_list::SelectListField {
-            //     text: t!(&format!("item_{}", Categories::Passport)),
-            //     value: Categories::Passport,
-            // },
-            // select_list::SelectListField {
-            //     text: t!(&format!("item_{}", Categories::DriverLicense)),
-            //     value: Categories::DriverLicense,
-            // },
-            // select_list::SelectListField {
-            //     text: t!(&format!("item_{}", Categories::WifiPassword)),
-            //     value: Categories::WifiPassword,
-            // },
-            // select_list::SelectListField {
-            //     text: t!(&format!("item_{}", Categories::Other)),
-            //     value: Categories::Other,
-            // },
-        ];
+        let mut categories: Vec<_> = record::Categories::templates()
+            .into_iter()
+            .map(|mut category| {
+                translate_titles(&mut category);
+
+                select_list::SelectListField {
+                    text: t!(&format!("item_{}", category)).to_string(),
+                    value: category,
+                }
+            })
+            .collect();
+
+        if let Ok(core) = core.lock() {
+            categories.extend(core.get_custom_categories().iter().map(|category| {
+                select_list::SelectListField {
+                    text: format!("{} {}", category.icon, category.name)
+                        .trim()
+                        .to_string(),
+                    value: category.new_record(),
+                }
+            }));
+        }
 
         Ok(Self {
             selected_index,
             core,
             categories,
+            ssh_passphrase: String::new(),
+            ssh_error: String::new(),
         })
     }
 
@@ -210,6 +160,18 @@ impl Page for AddRecordPage {
                     Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
                 }
             },
+            AddRecordPageMessage::RouteSecurity => match Security::new(Arc::clone(&self.core)) {
+                Ok(security) => {
+                    let route = Routers::Security(security);
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+                Err(e) => {
+                    let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+            },
             AddRecordPageMessage::RouteSettings => match Settings::new(Arc::clone(&self.core)) {
                 Ok(settings) => {
                     let route = Routers::Settings(settings);
@@ -241,6 +203,37 @@ impl Page for AddRecordPage {
 
                 Command::none()
             }
+            AddRecordPageMessage::SshPassphraseInput(value) => {
+                self.ssh_passphrase = value;
+
+                Command::none()
+            }
+            AddRecordPageMessage::GenerateSshKey(kind) => {
+                let comment = self
+                    .categories
+                    .get(self.selected_index)
+                    .map(|selected| selected.value.get_value().name.clone())
+                    .unwrap_or_default();
+
+                self.set_ssh_key(record::Categories::generate_ssh_key(kind, &comment));
+
+                Command::none()
+            }
+            AddRecordPageMessage::ImportSshKey => {
+                let mut dialog = FileDialog::new();
+
+                if let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) {
+                    dialog = dialog.set_directory(ssh_dir);
+                }
+
+                if let Some(path) = dialog.pick_file() {
+                    let passphrase = std::mem::take(&mut self.ssh_passphrase);
+
+                    self.set_ssh_key(record::Categories::import_ssh_key_file(&path, &passphrase));
+                }
+
+                Command::none()
+            }
             AddRecordPageMessage::HanldeInput(new_element) => {
                 match self.categories.get_mut(self.selected_index) {
                     Some(element) => {
@@ -271,9 +264,15 @@ impl Page for AddRecordPage {
             .height(Length::Fill)
             .width(200)
             .push(categories);
+        let ssh = self
+            .categories
+            .get(self.selected_index)
+            .filter(|selected| matches!(selected.value, record::Categories::SshKey(_)))
+            .map(|_| self.view_ssh_key());
         let form = if let Some(selected) = self.categories.get(self.selected_index) {
             let f = AddRecordForm::from(selected.value.get_value())
                 .set_title(selected.text.clone())
+                .set_show_uris(matches!(selected.value, record::Categories::Login(_)))
                 .on_copy(AddRecordPageMessage::Copy)
                 .set_save(AddRecordPageMessage::SaveRecord)
                 .on_input(AddRecordPageMessage::HanldeInput);
@@ -287,7 +286,8 @@ impl Page for AddRecordPage {
             Container::new(error)
         };
 
-        let content_row = Row::new().push(left_col).push(vline).push(form);
+        let right_col = Column::new().push_maybe(ssh).push(form);
+        let content_row = Row::new().push(left_col).push(vline).push(right_col);
         let main_container = Container::new(content_row).width(Length::Fill);
 
         NavBar::<Self::Message>::new()
@@ -295,7 +295,86 @@ impl Page for AddRecordPage {
             .on_gen(&AddRecordPageMessage::RouteGen)
             .on_settings(&AddRecordPageMessage::RouteSettings)
             .on_home(&AddRecordPageMessage::RouteHome)
+            .on_security(&AddRecordPageMessage::RouteSecurity)
             .view(main_container)
             .into()
     }
 }
+
+impl AddRecordPage {
+    fn set_ssh_key(&mut self, result: Result<record::Categories, ZebraErrors>) {
+        let selected = match self.categories.get_mut(self.selected_index) {
+            Some(selected) => selected,
+            None => return,
+        };
+
+        match result {
+            Ok(mut key) => {
+                let name = &selected.value.get_value().name;
+
+                // the name typed before wins over the comment of the key.
+                if !name.is_empty() {
+                    key.get_value_mut().name.clone_from(name);
+                }
+
+                translate_titles(&mut key);
+                selected.value = key;
+                self.ssh_error = String::new();
+            }
+            Err(ZebraErrors::SshKeyBadPassphrase) => {
+                self.ssh_error = t!("ssh_bad_passphrase").to_string();
+            }
+            Err(_) => {
+                self.ssh_error = t!("ssh_invalid_key").to_string();
+            }
+        }
+    }
+
+    fn view_ssh_key(&self) -> Container<AddRecordPageMessage> {
+        let ed25519_btn = Button::new(Text::new(t!("ssh_generate_ed25519")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(8)
+            .on_press(AddRecordPageMessage::GenerateSshKey(SshKeyType::Ed25519));
+        let rsa_btn = Button::new(Text::new(t!("ssh_generate_rsa")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(8)
+            .on_press(AddRecordPageMessage::GenerateSshKey(SshKeyType::Rsa));
+        let passphrase = SmartInput::new()
+            .set_value(&self.ssh_passphrase)
+            .padding(8)
+            .set_font_size(14)
+            .set_secure(true)
+            .set_placeholder(t!("placeholder_ssh_passphrase"))
+            .on_input(AddRecordPageMessage::SshPassphraseInput)
+            .on_submit(AddRecordPageMessage::ImportSshKey);
+        let import_btn = Button::new(Text::new(t!("ssh_import")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(8)
+            .on_press(AddRecordPageMessage::ImportSshKey);
+        let row = Row::new()
+            .spacing(8)
+            .align_items(iced::Alignment::Center)
+            .push(ed25519_btn)
+            .push(rsa_btn)
+            .push(Container::new(passphrase).width(Length::Fill))
+            .push(import_btn);
+        let mut col = Column::new().spacing(8).push(row);
+
+        if !self.ssh_error.is_empty() {
+            let error = Text::new(&self.ssh_error)
+                .size(14)
+                .style(zebra_ui::styles::text::danger);
+
+            col = col.push(error);
+        }
+
+        Container::new(col).padding(16).width(Length::Fill)
+    }
+}
+
+// template titles are keys until they are shown.
+fn translate_titles(category: &mut record::Categories) {
+    for field in category.get_value_mut().fields.iter_mut() {
+        field.title = t!(&format!("placeholder_{}", field.key)).to_string();
+    }
+}
@@ -18,6 +18,7 @@ use crate::gui::{GlobalMessage, Routers};
 
 use super::add_record::AddRecordPage;
 use super::home::Home;
+use super::security::Security;
 use super::settings::Settings;
 use super::Page;
 
@@ -39,6 +40,7 @@ pub struct Generator {
 
 #[derive(Debug, Clone)]
 pub enum GeneratorMessage {
+    RouteSecurity,
     RouteHome,
     RouteSettings,
     AddRecord,
@@ -56,6 +58,7 @@ impl Page for Generator {
         let pass_gen_state = Arc::new(Mutex::new(PassGenState {
             value: String::new(),
             length: MAX_CHARS_SHOWN,
+            breached: None,
         }));
         let phrase_state = Arc::new(Mutex::new(PhraseGenState::default()));
 
@@ -85,6 +88,18 @@ impl Page for Generator {
                     Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
                 }
             },
+            GeneratorMessage::RouteSecurity => match Security::new(Arc::clone(&self.core)) {
+                Ok(security) => {
+                    let route = Routers::Security(security);
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+                Err(e) => {
+                    let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+            },
             GeneratorMessage::RouteSettings => match Settings::new(Arc::clone(&self.core)) {
                 Ok(settings) => {
                     let route = Routers::Settings(settings);
@@ -186,6 +201,7 @@ impl Page for Generator {
             .on_home(&GeneratorMessage::RouteHome)
             .on_settings(&GeneratorMessage::RouteSettings)
             .on_add(&GeneratorMessage::AddRecord)
+            .on_security(&GeneratorMessage::RouteSecurity)
             .view(container)
             .into()
     }
@@ -203,10 +219,17 @@ impl Generator {
     }
 
     pub fn view_password_gen(&self) -> Container<GeneratorMessage> {
+        let core = Arc::clone(&self.core);
+
         match PassGenForm::new(Arc::clone(&self.pass_gen_state)) {
-            Ok(ctx) => Container::new(ctx.set_copy_message(GeneratorMessage::CopyPassword))
-                .width(Length::Fill)
-                .height(Length::Fill),
+            Ok(ctx) => Container::new(
+                ctx.set_copy_message(GeneratorMessage::CopyPassword)
+                    .on_breach_check(move |password| {
+                        core.lock().ok()?.breach_count(password).ok()?
+                    }),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill),
             Err(e) => self.view_error(e.to_string()),
         }
     }
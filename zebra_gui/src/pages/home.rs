@@ -3,21 +3,34 @@
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
 
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use crate::components::add_record_from::AddRecordForm;
+use chrono::{Local, MappedLocalTime, TimeZone};
+
+use crate::components::add_record_from::{format_size, AddRecordForm};
+use crate::components::modal::Modal;
+use crate::components::organize;
 use crate::components::select_list;
+use crate::components::smart_input::SmartInput;
 use crate::rust_i18n::t;
-use iced::widget::{Button, Column, Container, Row, Text};
+use iced::keyboard::{self, key::Named};
+use iced::widget::{image, pick_list, Button, Checkbox, Column, Container, Row, Space, Text};
 use iced::{alignment, Command, Element, Length, Subscription};
-use zebra_lib::core::record;
+use zebra_lib::core::expiry::{Expiration, ExpiryKind};
+use zebra_lib::core::organize::{Folder, Tag};
+use zebra_lib::core::payload::TrashEntry;
+use zebra_lib::core::record::{self, SortOrder, Uuid};
 use zebra_lib::{core::Core, errors::ZebraErrors};
 
+use rfd::FileDialog;
+
 use crate::components::home_nav_bar::{NavBar, NavRoute, LINE_ALFA_CHANNEL};
 use crate::gui::{GlobalMessage, Routers};
 
 use super::add_record::AddRecordPage;
 use super::error::ErrorPage;
 use super::gen::Generator;
+use super::security::Security;
 use super::settings::Settings;
 use super::Page;
 
@@ -26,11 +39,78 @@ pub struct Home {
     core: Arc<Mutex<Core>>,
     read_only: bool,
     selected_index: usize,
+    sort_order: SortOrder,
+    search: String,
+    // hidden values are matched too, records behind the master password never are.
+    search_hidden: bool,
+    filter: FilterOption,
+    filter_options: Vec<FilterOption>,
+    folders: Vec<Folder>,
+    tags: Vec<Tag>,
     categories_list: Vec<select_list::SelectListField<record::Categories>>,
+    show_trash: bool,
+    trash_index: usize,
+    trash_list: Vec<select_list::SelectListField<TrashEntry>>,
+    // upcoming expirations with the record names.
+    expirations: Vec<(Expiration, String)>,
+    hide_expirations: bool,
+    // name and pixels of the attachment opened in the preview.
+    preview: Option<(String, image::Handle)>,
+    // an action which deletes for good, waiting to be confirmed.
+    confirm: Option<HomeMessage>,
+    // the opened record with its references filled in and where they come from.
+    resolved: Option<record::Element>,
+    references: Vec<(usize, Uuid, String)>,
+    // protects an exported ssh key, empty keeps it unprotected.
+    ssh_passphrase: String,
+    // the opened record asks for the master password, `revealed` is the one it was given for.
+    guarded: bool,
+    revealed: Option<Uuid>,
+    reprompt_password: String,
+    reprompt_error: String,
+}
+
+const DATA_FORMAT: &str = "%d.%m.%Y %H:%M:%S";
+const EXPIRATIONS_SHOWN: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordFilter {
+    All,
+    Category(String),
+    Custom(Uuid),
+    Folder(Uuid),
+    Tag(Uuid),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterOption {
+    label: String,
+    filter: RecordFilter,
+}
+
+impl std::fmt::Display for FilterOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOption(SortOrder);
+
+impl std::fmt::Display for SortOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = match self.0 {
+            SortOrder::Added => t!("sort_added"),
+            SortOrder::RecentlyUsed => t!("sort_recently_used"),
+            SortOrder::RecentlyModified => t!("sort_recently_modified"),
+        };
+        write!(f, "{}", text)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum HomeMessage {
+    RouteSecurity,
     RouteGen,
     RouteSettings,
     AddRecord,
@@ -40,61 +120,577 @@ pub enum HomeMessage {
     HanldeSelectCategories(usize),
     HanldeInputForm(record::Element),
     HanldeSaveRecord,
+    HanldeSortOrder(SortOption),
+    HanldeFilter(FilterOption),
+    HanldeSearch(String),
+    ToggleSearchHidden(bool),
+    ClearSearch,
+    MoveSelection(bool),
+    NextHotp(usize),
+    Tick,
+    RestoreHistory(usize, usize),
+    ToggleTrash,
+    HanldeSelectTrash(usize),
+    RestoreTrash,
+    PurgeTrash,
+    EmptyTrash,
+    OpenExpiration(Uuid),
+    DismissExpirations,
+    AttachFile,
+    PreviewAttachment(Uuid),
+    SaveAttachment(Uuid),
+    RemoveAttachment(Uuid),
+    ClosePreview,
+    AskConfirm(Box<HomeMessage>),
+    CloseConfirm,
+    OpenReference(Uuid),
+    SshPassphraseInput(String),
+    ExportSshKey,
+    RepromptInput(String),
+    Reprompt,
 }
 
 impl Page for Home {
     type Message = HomeMessage;
 
     fn new(core: Arc<Mutex<Core>>) -> Result<Self, ZebraErrors> {
-        let records = match core.lock() {
-            // TODO: this is bad praticle! the big array copy in ram, need rework with pointers.
-            Ok(state) => state.data.clone(),
-            Err(_) => Vec::new(),
+        let sort_order = SortOrder::default();
+        let filter = FilterOption {
+            label: t!("filter_all").to_string(),
+            filter: RecordFilter::All,
         };
-        let categories_list: Vec<select_list::SelectListField<record::Categories>> = records
-            .iter()
-            .map(|element| select_list::SelectListField {
-                text: element.get_value().name.clone(),
-                value: element.clone(),
-            })
-            .collect();
+        let categories_list = Self::records_list(&core, sort_order, &filter.filter, "", false);
+        let trash_list = Self::trash_list(&core);
+        let (folders, tags) = match core.lock() {
+            Ok(core) => (core.get_folders().to_vec(), core.get_tags().to_vec()),
+            Err(_) => Default::default(),
+        };
+        let filter_options = Self::filter_options(&core);
+        let expirations = Self::expirations(&core);
 
-        Ok(Self {
+        let mut home = Self {
             core,
+            expirations,
+            hide_expirations: false,
+            preview: None,
+            confirm: None,
             categories_list,
+            trash_list,
+            filter,
+            filter_options,
+            folders,
+            tags,
+            show_trash: false,
+            trash_index: 0,
+            sort_order,
+            search: String::new(),
+            search_hidden: false,
             read_only: true,
             selected_index: 0,
-        })
+            resolved: None,
+            references: Vec::new(),
+            ssh_passphrase: String::new(),
+            guarded: false,
+            revealed: None,
+            reprompt_password: String::new(),
+            reprompt_error: String::new(),
+        };
+
+        home.resolve_selected();
+
+        Ok(home)
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        Subscription::none()
+        let keys = keyboard::on_key_press(|key_code, _modifiers| match key_code {
+            keyboard::Key::Named(Named::ArrowDown) => Some(HomeMessage::MoveSelection(true)),
+            keyboard::Key::Named(Named::ArrowUp) => Some(HomeMessage::MoveSelection(false)),
+            keyboard::Key::Named(Named::Escape) => Some(HomeMessage::ClearSearch),
+            _ => None,
+        });
+        // redraws the live one-time codes of the opened record.
+        let has_otp = self
+            .categories_list
+            .get(self.selected_index)
+            .is_some_and(|element| {
+                let value = element.value.get_value();
+
+                value
+                    .fields
+                    .iter()
+                    .chain(value.extra_fields.iter())
+                    .any(|field| field.kind == record::ItemKind::Totp)
+            });
+
+        if has_otp && self.read_only && !self.show_trash {
+            Subscription::batch([
+                keys,
+                iced::time::every(Duration::from_secs(1)).map(|_| HomeMessage::Tick),
+            ])
+        } else {
+            keys
+        }
     }
 
     fn update(&mut self, message: Self::Message) -> iced::Command<GlobalMessage> {
-        match message {
-            HomeMessage::RemoveElementForm => match self.core.lock() {
-                Ok(mut core) => {
-                    self.categories_list.remove(self.selected_index);
-                    core.data.remove(self.selected_index);
+        let command = match message {
+            HomeMessage::RemoveElementForm => {
+                let id = match self.selected_id() {
+                    Some(id) => id,
+                    None => return Command::none(),
+                };
+
+                match self.core.lock() {
+                    Ok(mut core) => match core.remove_element(&id) {
+                        Ok(_) => {
+                            drop(core);
+                            self.categories_list.remove(self.selected_index);
+                            self.selected_index = self
+                                .selected_index
+                                .min(self.categories_list.len().saturating_sub(1));
+                            self.trash_list = Self::trash_list(&self.core);
+
+                            Command::none()
+                        }
+                        Err(e) => {
+                            let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                            Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                        }
+                    },
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
+            HomeMessage::Copy(value) => {
+                if let Some(id) = self.selected_id() {
+                    let _ = self.core.lock().map(|mut core| core.touch_element(&id));
+                }
 
-                    match core.data_update() {
+                iced::clipboard::write::<GlobalMessage>(value)
+            }
+            HomeMessage::RestoreHistory(field, entry) => {
+                let id = match self.selected_id() {
+                    Some(id) => id,
+                    None => return Command::none(),
+                };
+
+                match self.core.lock() {
+                    Ok(mut core) => match core.restore_history(&id, field, entry) {
+                        Ok(_) => {
+                            if let (Some(element), Some(saved)) = (
+                                self.categories_list.get_mut(self.selected_index),
+                                core.get_element(&id),
+                            ) {
+                                element.value = saved.clone();
+                            }
+
+                            Command::none()
+                        }
+                        Err(e) => {
+                            let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                            Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                        }
+                    },
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
+            HomeMessage::ToggleTrash => {
+                self.show_trash = !self.show_trash;
+                self.trash_index = 0;
+                self.read_only = true;
+
+                Command::none()
+            }
+            HomeMessage::HanldeSelectTrash(index) => {
+                self.trash_index = index;
+
+                Command::none()
+            }
+            HomeMessage::RestoreTrash | HomeMessage::PurgeTrash | HomeMessage::EmptyTrash => {
+                self.confirm = None;
+
+                let id = self
+                    .trash_list
+                    .get(self.trash_index)
+                    .map(|entry| *entry.value.record.get_id());
+                let result = self
+                    .core
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut core| {
+                        match (&message, id) {
+                            (HomeMessage::RestoreTrash, Some(id)) => core.restore_element(&id),
+                            (HomeMessage::PurgeTrash, Some(id)) => core.purge_element(&id),
+                            (HomeMessage::EmptyTrash, _) => core.empty_trash(),
+                            _ => Ok(()),
+                        }
+                        .map_err(|e| e.to_string())
+                    });
+
+                match result {
+                    Ok(_) => {
+                        self.refresh();
+                        self.trash_index = self
+                            .trash_index
+                            .min(self.trash_list.len().saturating_sub(1));
+                        self.show_trash = !self.trash_list.is_empty();
+
+                        Command::none()
+                    }
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
+            HomeMessage::HanldeFilter(option) => {
+                self.filter = option;
+                self.read_only = true;
+                self.selected_index = 0;
+                self.refresh();
+
+                Command::none()
+            }
+            HomeMessage::HanldeSearch(query) => {
+                self.search = query;
+                self.read_only = true;
+                self.selected_index = 0;
+                self.refresh();
+
+                Command::none()
+            }
+            HomeMessage::ToggleSearchHidden(value) => {
+                self.search_hidden = value;
+                self.selected_index = 0;
+                self.refresh();
+
+                Command::none()
+            }
+            HomeMessage::OpenExpiration(id) | HomeMessage::OpenReference(id) => {
+                // the record may be hidden by the search or the filter.
+                if !self.categories_list.iter().any(|e| e.value.get_id() == &id) {
+                    self.search.clear();
+                    self.filter = FilterOption {
+                        label: t!("filter_all").to_string(),
+                        filter: RecordFilter::All,
+                    };
+                    self.refresh();
+                }
+
+                if let Some(index) = self
+                    .categories_list
+                    .iter()
+                    .position(|element| element.value.get_id() == &id)
+                {
+                    self.selected_index = index;
+                    self.read_only = true;
+                    self.show_trash = false;
+                }
+
+                Command::none()
+            }
+            HomeMessage::AttachFile => {
+                let id = match self.selected_id() {
+                    Some(id) => id,
+                    None => return Command::none(),
+                };
+                let path = match FileDialog::new().pick_file() {
+                    Some(path) => path,
+                    None => return Command::none(),
+                };
+
+                match self.core.lock() {
+                    Ok(mut core) => match core.attach_file(&id, &path) {
+                        Ok(_) => {
+                            if let (Some(element), Some(saved)) = (
+                                self.categories_list.get_mut(self.selected_index),
+                                core.get_element(&id),
+                            ) {
+                                element.value = saved.clone();
+                            }
+
+                            Command::none()
+                        }
+                        Err(ZebraErrors::AttachmentTooLarge) => {
+                            let limit = format_size(core.state.settings.records.attachment_limit);
+                            let route = Routers::ErrorPage(ErrorPage::from(
+                                t!("attachment_too_large", limit => limit).to_string(),
+                            ));
+                            Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                        }
+                        Err(e) => {
+                            let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                            Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                        }
+                    },
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
+            HomeMessage::PreviewAttachment(attachment) => {
+                let name = self
+                    .categories_list
+                    .get(self.selected_index)
+                    .and_then(|element| {
+                        element
+                            .value
+                            .get_value()
+                            .attachments
+                            .iter()
+                            .find(|a| a.id == attachment)
+                    })
+                    .map(|a| a.name.clone())
+                    .unwrap_or_default();
+
+                match self.core.lock() {
+                    Ok(core) => match core.get_attachment(&attachment) {
+                        Ok(bytes) => {
+                            self.preview = Some((name, image::Handle::from_memory(bytes)));
+
+                            Command::none()
+                        }
+                        Err(e) => {
+                            let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                            Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                        }
+                    },
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
+            HomeMessage::SshPassphraseInput(value) => {
+                self.ssh_passphrase = value;
+
+                Command::none()
+            }
+            HomeMessage::ExportSshKey => {
+                let id = match self.selected_id() {
+                    Some(id) if !self.guarded => id,
+                    _ => return Command::none(),
+                };
+                // the names ssh looks for by default.
+                let rsa = self
+                    .categories_list
+                    .get(self.selected_index)
+                    .and_then(|element| {
+                        element
+                            .value
+                            .get_value()
+                            .fields
+                            .iter()
+                            .find(|item| item.key == "public_key")
+                    })
+                    .is_some_and(|item| item.value.starts_with("ssh-rsa "));
+                let file_name = if rsa { "id_rsa" } else { "id_ed25519" };
+                let mut dialog = FileDialog::new().set_file_name(file_name);
+
+                if let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) {
+                    dialog = dialog.set_directory(ssh_dir);
+                }
+
+                let path = match dialog.save_file() {
+                    Some(path) => path,
+                    None => return Command::none(),
+                };
+                let passphrase = std::mem::take(&mut self.ssh_passphrase);
+
+                match self.core.lock() {
+                    Ok(core) => match core.save_ssh_key(&id, &path, &passphrase) {
                         Ok(_) => Command::none(),
                         Err(e) => {
                             let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
                             Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
                         }
+                    },
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
+            HomeMessage::SaveAttachment(attachment) => {
+                let name = self
+                    .categories_list
+                    .get(self.selected_index)
+                    .and_then(|element| {
+                        element
+                            .value
+                            .get_value()
+                            .attachments
+                            .iter()
+                            .find(|a| a.id == attachment)
+                    })
+                    .map(|a| a.name.clone())
+                    .unwrap_or_default();
+                let mut dialog = FileDialog::new().set_file_name(name);
+
+                if let Some(home_dir) = dirs::home_dir() {
+                    dialog = dialog.set_directory(home_dir);
+                }
+
+                let path = match dialog.save_file() {
+                    Some(path) => path,
+                    None => return Command::none(),
+                };
+
+                match self.core.lock() {
+                    Ok(core) => match core.save_attachment(&attachment, &path) {
+                        Ok(_) => Command::none(),
+                        Err(e) => {
+                            let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                            Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                        }
+                    },
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
+            HomeMessage::RemoveAttachment(attachment) => {
+                self.confirm = None;
+
+                let id = match self.selected_id() {
+                    Some(id) => id,
+                    None => return Command::none(),
+                };
+
+                match self.core.lock() {
+                    Ok(mut core) => match core.remove_attachment(&id, &attachment) {
+                        Ok(_) => {
+                            if let (Some(element), Some(saved)) = (
+                                self.categories_list.get_mut(self.selected_index),
+                                core.get_element(&id),
+                            ) {
+                                element.value = saved.clone();
+                            }
+
+                            Command::none()
+                        }
+                        Err(e) => {
+                            let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                            Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                        }
+                    },
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
                     }
                 }
+            }
+            HomeMessage::ClosePreview => {
+                self.preview = None;
+
+                Command::none()
+            }
+            HomeMessage::AskConfirm(action) => {
+                self.confirm = Some(*action);
+
+                Command::none()
+            }
+            HomeMessage::CloseConfirm => {
+                self.confirm = None;
+
+                Command::none()
+            }
+            HomeMessage::DismissExpirations => {
+                self.hide_expirations = true;
+
+                Command::none()
+            }
+            HomeMessage::ClearSearch => {
+                if !self.search.is_empty() && self.read_only {
+                    self.search.clear();
+                    self.selected_index = 0;
+                    self.refresh();
+                }
+
+                Command::none()
+            }
+            HomeMessage::MoveSelection(down) => {
+                // arrows belong to the form while a record is edited.
+                if !self.read_only || self.show_trash || self.categories_list.is_empty() {
+                    return Command::none();
+                }
+
+                let last = self.categories_list.len() - 1;
+
+                self.selected_index = if down {
+                    (self.selected_index + 1).min(last)
+                } else {
+                    self.selected_index.saturating_sub(1)
+                };
+
+                Command::none()
+            }
+            HomeMessage::Tick => Command::none(),
+            HomeMessage::NextHotp(field) => {
+                let id = match self.selected_id() {
+                    Some(id) => id,
+                    None => return Command::none(),
+                };
+
+                match self.core.lock() {
+                    Ok(mut core) => match core.next_hotp(&id, field) {
+                        Ok(code) => {
+                            if let (Some(element), Some(saved)) = (
+                                self.categories_list.get_mut(self.selected_index),
+                                core.get_element(&id),
+                            ) {
+                                element.value = saved.clone();
+                            }
+
+                            iced::clipboard::write::<GlobalMessage>(code)
+                        }
+                        Err(e) => {
+                            let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                            Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                        }
+                    },
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
+            HomeMessage::HanldeSortOrder(option) => {
+                let selected = self.selected_id();
+
+                self.sort_order = option.0;
+                self.read_only = true;
+                self.refresh();
+                self.selected_index = self
+                    .categories_list
+                    .iter()
+                    .position(|element| Some(element.value.get_id()) == selected.as_ref())
+                    .unwrap_or(0);
+
+                Command::none()
+            }
+            HomeMessage::RouteGen => match Generator::new(Arc::clone(&self.core)) {
+                Ok(gen) => {
+                    let route = Routers::Generator(gen);
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
                 Err(e) => {
                     let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
                     Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
                 }
             },
-            HomeMessage::Copy(value) => iced::clipboard::write::<GlobalMessage>(value),
-            HomeMessage::RouteGen => match Generator::new(Arc::clone(&self.core)) {
-                Ok(gen) => {
-                    let route = Routers::Generator(gen);
+            HomeMessage::RouteSecurity => match Security::new(Arc::clone(&self.core)) {
+                Ok(security) => {
+                    let route = Routers::Security(security);
 
                     Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
                 }
@@ -134,7 +730,33 @@ impl Page for Home {
                 Command::none()
             }
             HomeMessage::EditForm => {
-                self.read_only = false;
+                self.read_only = self.guarded;
+                Command::none()
+            }
+            HomeMessage::RepromptInput(value) => {
+                self.reprompt_password = value;
+                self.reprompt_error = String::new();
+
+                Command::none()
+            }
+            HomeMessage::Reprompt => {
+                let result = self
+                    .core
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|core| {
+                        core.check_password(&self.reprompt_password)
+                            .map_err(|e| e.to_string())
+                    });
+
+                match result {
+                    Ok(_) => {
+                        self.revealed = self.selected_id();
+                        self.reprompt_password = String::new();
+                    }
+                    Err(e) => self.reprompt_error = e,
+                }
+
                 Command::none()
             }
             HomeMessage::HanldeInputForm(new_element) => {
@@ -159,11 +781,18 @@ impl Page for Home {
                 match self.categories_list.get_mut(self.selected_index) {
                     Some(element) => match self.core.lock() {
                         Ok(mut core) => {
-                            core.data[self.selected_index] = core.data[self.selected_index]
-                                .update_element(element.value.get_value().clone());
+                            let value = element.value.get_value().clone();
+                            let id = value.id;
+
+                            match core.update_element(&id, value) {
+                                Ok(_) => {
+                                    // picks up the timestamps stamped by core.
+                                    if let Some(saved) = core.get_element(&id) {
+                                        element.value = saved.clone();
+                                    }
 
-                            match core.data_update() {
-                                Ok(_) => Command::none(),
+                                    Command::none()
+                                }
                                 Err(e) => {
                                     let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
                                     Command::perform(std::future::ready(1), |_| {
@@ -185,28 +814,276 @@ impl Page for Home {
                     }
                 }
             }
-        }
+        };
+
+        self.resolve_selected();
+
+        command
     }
 
     fn view(&self) -> Element<Self::Message> {
         let records = &self.core.lock().unwrap(); // TODO: remove unwrap..
-        let content = Container::new(if records.data.is_empty() {
+        let content = Container::new(if records.data.is_empty() && !self.show_trash {
             self.view_options()
         } else {
             self.view_records()
         });
+        let content = match (&self.confirm, &self.preview) {
+            (Some(action), _) => Container::new(
+                Modal::new(content, self.view_confirm(action)).on_blur(HomeMessage::CloseConfirm),
+            ),
+            (None, Some(preview)) => Container::new(
+                Modal::new(content, self.view_preview(preview)).on_blur(HomeMessage::ClosePreview),
+            ),
+            (None, None) => content,
+        };
 
         NavBar::<Self::Message>::new()
             .set_route(NavRoute::Home)
             .on_gen(&HomeMessage::RouteGen)
             .on_settings(&HomeMessage::RouteSettings)
             .on_add(&HomeMessage::AddRecord)
+            .on_security(&HomeMessage::RouteSecurity)
             .view(content)
             .into()
     }
 }
 
 impl Home {
+    // opens a record, when another page links to it.
+    pub fn select(mut self, id: &Uuid) -> Self {
+        if let Some(index) = self
+            .categories_list
+            .iter()
+            .position(|element| element.value.get_id() == id)
+        {
+            self.selected_index = index;
+        }
+
+        self.resolve_selected();
+
+        self
+    }
+
+    fn resolve_selected(&mut self) {
+        let core = match self.core.lock() {
+            Ok(core) => core,
+            Err(_) => return,
+        };
+        let element = match self.categories_list.get(self.selected_index) {
+            Some(selected) => selected.value.get_value(),
+            None => {
+                self.resolved = None;
+                self.references.clear();
+
+                return;
+            }
+        };
+
+        // the password is asked again for every other record.
+        if self.revealed != Some(element.id) {
+            self.revealed = None;
+        }
+
+        self.guarded = self.revealed.is_none() && core.needs_reprompt(element);
+        self.references = element
+            .fields
+            .iter()
+            .chain(element.extra_fields.iter())
+            .enumerate()
+            .filter_map(|(index, item)| {
+                let source = core.get_element(&item.reference()?.id)?;
+
+                Some((index, *source.get_id(), source.get_value().name.clone()))
+            })
+            .collect();
+
+        // a guarded record shows nothing it borrows either.
+        self.resolved = Some(if self.guarded {
+            element.masked()
+        } else {
+            core.resolve_element(element)
+        });
+    }
+
+    fn refresh(&mut self) {
+        self.categories_list = Self::records_list(
+            &self.core,
+            self.sort_order,
+            &self.filter.filter,
+            &self.search,
+            self.search_hidden,
+        );
+        self.trash_list = Self::trash_list(&self.core);
+        self.filter_options = Self::filter_options(&self.core);
+        self.expirations = Self::expirations(&self.core);
+
+        if let Ok(core) = self.core.lock() {
+            self.folders = core.get_folders().to_vec();
+            self.tags = core.get_tags().to_vec();
+        }
+    }
+
+    fn expirations(core: &Arc<Mutex<Core>>) -> Vec<(Expiration, String)> {
+        let core = match core.lock() {
+            Ok(core) => core,
+            Err(_) => return Vec::new(),
+        };
+
+        core.upcoming_expirations(core.state.settings.records.expiry_notice_days)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|expiration| {
+                core.get_element(&expiration.id)
+                    .map(|record| (expiration, record.get_value().name.clone()))
+            })
+            .collect()
+    }
+
+    fn records_list(
+        core: &Arc<Mutex<Core>>,
+        sort_order: SortOrder,
+        filter: &RecordFilter,
+        query: &str,
+        search_hidden: bool,
+    ) -> Vec<select_list::SelectListField<record::Categories>> {
+        let mut records = match core.lock() {
+            // TODO: this is bad praticle! the big array copy in ram, need rework with pointers.
+            Ok(state) => {
+                let folders = match filter {
+                    RecordFilter::Folder(id) => state.folder_descendants(id),
+                    _ => Vec::new(),
+                };
+
+                // search results keep their rank instead of the sort order.
+                let found: Vec<&record::Categories> = if query.trim().is_empty() {
+                    state.data.iter().collect()
+                } else {
+                    state
+                        .search(query, search_hidden)
+                        .iter()
+                        .filter_map(|hit| state.get_element(&hit.id))
+                        .collect()
+                };
+
+                found
+                    .into_iter()
+                    .filter(|record| {
+                        let value = record.get_value();
+
+                        match filter {
+                            RecordFilter::All => true,
+                            RecordFilter::Category(name) => {
+                                record.custom_id().is_none() && record.to_string() == *name
+                            }
+                            RecordFilter::Custom(id) => record.custom_id() == Some(id),
+                            RecordFilter::Folder(_) => {
+                                value.folder.is_some_and(|id| folders.contains(&id))
+                            }
+                            RecordFilter::Tag(id) => value.tags.contains(id),
+                        }
+                    })
+                    .cloned()
+                    .collect()
+            }
+            Err(_) => Vec::new(),
+        };
+
+        if query.trim().is_empty() {
+            sort_order.sort(&mut records);
+        }
+
+        records
+            .into_iter()
+            .map(|element| select_list::SelectListField {
+                text: element.get_value().name.clone(),
+                value: element,
+            })
+            .collect()
+    }
+
+    fn filter_options(core: &Arc<Mutex<Core>>) -> Vec<FilterOption> {
+        let mut options = vec![FilterOption {
+            label: t!("filter_all").to_string(),
+            filter: RecordFilter::All,
+        }];
+        let core = match core.lock() {
+            Ok(core) => core,
+            Err(_) => return options,
+        };
+
+        for record in core
+            .data
+            .iter()
+            .filter(|record| record.custom_id().is_none())
+        {
+            let name = record.to_string();
+            let filter = RecordFilter::Category(name.clone());
+
+            if !options.iter().any(|option| option.filter == filter) {
+                options.push(FilterOption {
+                    label: t!(&format!("item_{}", name)).to_string(),
+                    filter,
+                });
+            }
+        }
+
+        for category in core.get_custom_categories() {
+            if core
+                .data
+                .iter()
+                .any(|record| record.custom_id() == Some(&category.id))
+            {
+                options.push(FilterOption {
+                    label: format!("{} {}", category.icon, category.name)
+                        .trim()
+                        .to_string(),
+                    filter: RecordFilter::Custom(category.id),
+                });
+            }
+        }
+
+        for choice in organize::folder_choices(core.get_folders()) {
+            if let Some(id) = choice.id {
+                options.push(FilterOption {
+                    label: choice.label,
+                    filter: RecordFilter::Folder(id),
+                });
+            }
+        }
+
+        for choice in organize::tag_choices(core.get_tags()) {
+            if let Some(id) = choice.id {
+                options.push(FilterOption {
+                    label: choice.label,
+                    filter: RecordFilter::Tag(id),
+                });
+            }
+        }
+
+        options
+    }
+
+    fn trash_list(core: &Arc<Mutex<Core>>) -> Vec<select_list::SelectListField<TrashEntry>> {
+        match core.lock() {
+            Ok(core) => core
+                .get_trash()
+                .iter()
+                .map(|entry| select_list::SelectListField {
+                    text: entry.record.get_value().name.clone(),
+                    value: entry.clone(),
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn selected_id(&self) -> Option<Uuid> {
+        self.categories_list
+            .get(self.selected_index)
+            .map(|element| *element.value.get_id())
+    }
+
     pub fn view_options(&self) -> Container<HomeMessage> {
         let title = Text::new(t!("no_records_title")).size(21);
         let row = Row::new()
@@ -239,11 +1116,79 @@ impl Home {
             .height(Length::Fill)
             .align_items(iced::Alignment::Center)
             .push(row)
-            .push(options);
+            .push(options)
+            .push(Space::new(0, 16))
+            .push_maybe(self.view_trash_button());
 
         Container::new(col).width(Length::Fill).height(Length::Fill)
     }
 
+    pub fn view_trash_button(&self) -> Option<Button<HomeMessage>> {
+        if self.trash_list.is_empty() {
+            return None;
+        }
+
+        let text = if self.show_trash {
+            t!("back_to_records").to_string()
+        } else {
+            format!("{} ({})", t!("trash"), self.trash_list.len())
+        };
+        let button = Button::new(Text::new(text).size(14))
+            .padding(0)
+            .style(zebra_ui::styles::button::ref_primary)
+            .on_press(HomeMessage::ToggleTrash);
+
+        Some(button)
+    }
+
+    pub fn view_trash_entry(&self) -> Container<HomeMessage> {
+        let entry = match self.trash_list.get(self.trash_index) {
+            Some(entry) => &entry.value,
+            None => {
+                let error = Text::new(t!("not_found_item"))
+                    .style(zebra_ui::styles::text::danger)
+                    .size(24);
+
+                return Container::new(error);
+            }
+        };
+        let title = Text::new(&entry.record.get_value().name)
+            .size(24)
+            .width(Length::Fill);
+        let deleted = match Local.timestamp_opt(entry.deleted, 0) {
+            MappedLocalTime::Single(t) => t.format(DATA_FORMAT).to_string(),
+            _ => String::new(),
+        };
+        let deleted = Text::new(format!("{}: {}", t!("deleted_at"), deleted))
+            .size(13)
+            .style(zebra_ui::styles::text::muted);
+        let restore_btn = Button::new(Text::new(t!("restore")).size(16))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(8)
+            .on_press(HomeMessage::RestoreTrash);
+        let purge_btn = Button::new(Text::new(t!("delete_forever")).size(16))
+            .style(zebra_ui::styles::button::outline_danger)
+            .padding(8)
+            .on_press(HomeMessage::AskConfirm(Box::new(HomeMessage::PurgeTrash)));
+        let empty_btn = Button::new(Text::new(t!("empty_trash")).size(14))
+            .padding(0)
+            .style(zebra_ui::styles::button::ref_danger)
+            .on_press(HomeMessage::AskConfirm(Box::new(HomeMessage::EmptyTrash)));
+        let buttons = Row::new().spacing(8).push(restore_btn).push(purge_btn);
+        let col = Column::new()
+            .padding(16)
+            .spacing(8)
+            .width(Length::Fill)
+            .push(title)
+            .push(deleted)
+            .push(Space::new(0, 8))
+            .push(buttons)
+            .push(Space::new(0, 16))
+            .push(empty_btn);
+
+        Container::new(col)
+    }
+
     pub fn view_records(&self) -> Container<HomeMessage> {
         let categories = select_list::SelectList::from(&self.categories_list)
             .on_select(HomeMessage::HanldeSelectCategories)
@@ -251,25 +1196,100 @@ impl Home {
             .set_text_horizontal_alignmen(iced::alignment::Horizontal::Left)
             .set_line_gap(10)
             .set_field_padding(8);
-        let categories = Container::new(categories);
+        let sort_pick_list = pick_list(
+            SortOrder::ALL.map(SortOption),
+            Some(SortOption(self.sort_order)),
+            HomeMessage::HanldeSortOrder,
+        )
+        .text_size(14)
+        .padding(4)
+        .style(zebra_ui::styles::pick_list::primary_field)
+        .width(Length::Fill);
 
         let vline = zebra_ui::components::line::Linear::new()
             .width(Length::Fixed(1.0))
             .height(Length::Fill)
             .style(zebra_ui::styles::line::line_secondary)
             .alfa(LINE_ALFA_CHANNEL);
-        let left_search_col = Column::new()
-            .height(Length::Fill)
-            .width(200)
-            .push(categories);
+        let left_search_col = if self.show_trash {
+            let trash = select_list::SelectList::from(&self.trash_list)
+                .on_select(HomeMessage::HanldeSelectTrash)
+                .set_selected_index(self.trash_index)
+                .set_text_horizontal_alignmen(iced::alignment::Horizontal::Left)
+                .set_line_gap(10)
+                .set_field_padding(8);
+            let title = Text::new(t!("trash"))
+                .size(14)
+                .style(zebra_ui::styles::text::muted);
 
-        let form = if let Some(selected) = self.categories_list.get(self.selected_index) {
-            let mut f = AddRecordForm::from(selected.value.get_value())
+            Column::new()
+                .push(title)
+                .push(Space::new(0, 8))
+                .push(Container::new(trash).height(Length::Fill))
+        } else {
+            let filter_pick_list = pick_list(
+                self.filter_options.as_slice(),
+                Some(self.filter.clone()),
+                HomeMessage::HanldeFilter,
+            )
+            .text_size(14)
+            .padding(4)
+            .style(zebra_ui::styles::pick_list::primary_field)
+            .width(Length::Fill);
+
+            let search = SmartInput::new()
+                .set_value(&self.search)
+                .padding(4)
+                .set_font_size(14)
+                .set_placeholder(t!("placeholder_search"))
+                .on_input(HomeMessage::HanldeSearch);
+            let search_hidden = Checkbox::new(t!("search_hidden"), self.search_hidden)
+                .on_toggle(HomeMessage::ToggleSearchHidden)
+                .text_size(12);
+
+            Column::new()
+                .push(search)
+                .push(Space::new(0, 4))
+                .push(search_hidden)
+                .push(Space::new(0, 8))
+                .push(filter_pick_list)
+                .push(Space::new(0, 8))
+                .push(sort_pick_list)
+                .push(Space::new(0, 8))
+                .push(Container::new(categories).height(Length::Fill))
+        }
+        .height(Length::Fill)
+        .width(200)
+        .push_maybe(self.view_trash_button())
+        .push(Space::new(0, 8));
+
+        let form = if self.show_trash {
+            self.view_trash_entry()
+        } else if let Some(selected) = self.categories_list.get(self.selected_index) {
+            // edits go to the references themselves.
+            let element = match &self.resolved {
+                Some(resolved) if self.read_only => resolved,
+                _ => selected.value.get_value(),
+            };
+            let mut f = AddRecordForm::from(element)
+                .set_folders(&self.folders)
+                .set_references(&self.references)
+                .set_tags(&self.tags)
                 .set_read_only(self.read_only)
                 .set_edit(HomeMessage::EditForm)
                 .set_remove(HomeMessage::RemoveElementForm)
                 .set_title(selected.text.clone())
-                .on_copy(HomeMessage::Copy);
+                .set_show_uris(matches!(selected.value, record::Categories::Login(_)))
+                .on_copy(HomeMessage::Copy)
+                .on_restore_history(HomeMessage::RestoreHistory)
+                .on_next_hotp(HomeMessage::NextHotp)
+                .on_attach(HomeMessage::AttachFile)
+                .on_preview_attachment(HomeMessage::PreviewAttachment)
+                .on_save_attachment(HomeMessage::SaveAttachment)
+                .on_remove_attachment(|attachment| {
+                    HomeMessage::AskConfirm(Box::new(HomeMessage::RemoveAttachment(attachment)))
+                })
+                .on_open_reference(HomeMessage::OpenReference);
 
             if !self.read_only {
                 f = f
@@ -285,8 +1305,212 @@ impl Home {
 
             Container::new(error)
         };
-        let row = Row::new().push(left_search_col).push(vline).push(form);
+        let right_col = Column::new()
+            .push_maybe(self.view_expirations())
+            .push_maybe(self.view_reprompt())
+            .push_maybe(self.view_ssh_export())
+            .push(form);
+        let row = Row::new().push(left_search_col).push(vline).push(right_col);
 
         Container::new(row)
     }
+
+    fn view_preview<'a>(
+        &self,
+        (name, handle): &'a (String, image::Handle),
+    ) -> Container<'a, HomeMessage> {
+        let close_btn = Button::new(
+            zebra_ui::image::close_icon()
+                .style(zebra_ui::styles::svg::primary_hover)
+                .height(30)
+                .width(30),
+        )
+        .padding(0)
+        .style(zebra_ui::styles::button::transparent)
+        .on_press(HomeMessage::ClosePreview);
+        let head = Row::new()
+            .push(Text::new(name).size(16).width(Length::Fill))
+            .push(close_btn)
+            .align_items(iced::Alignment::Center);
+        let picture = image::Image::new(handle.clone())
+            .width(Length::Shrink)
+            .height(Length::Shrink);
+        let col = Column::new()
+            .padding(16)
+            .spacing(8)
+            .push(head)
+            .push(picture)
+            .align_items(iced::Alignment::Center);
+
+        Container::new(col)
+            .max_width(800)
+            .max_height(600)
+            .style(zebra_ui::styles::container::primary_bordered_modal)
+    }
+
+    fn view_confirm<'a>(&self, action: &HomeMessage) -> Container<'a, HomeMessage> {
+        let (description, label) = match action {
+            HomeMessage::PurgeTrash => (t!("confirm_delete_forever"), t!("delete_forever")),
+            HomeMessage::EmptyTrash => (t!("confirm_empty_trash"), t!("empty_trash")),
+            HomeMessage::RemoveAttachment(_) => (t!("confirm_remove_attachment"), t!("remove")),
+            _ => return Container::new(Space::new(0, 0)),
+        };
+        let description = Text::new(description)
+            .size(14)
+            .horizontal_alignment(iced::alignment::Horizontal::Center)
+            .style(zebra_ui::styles::text::warn);
+        let cancel_btn = Button::new(Text::new(t!("cancel")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(8)
+            .on_press(HomeMessage::CloseConfirm);
+        let confirm_btn = Button::new(Text::new(label).size(14))
+            .style(zebra_ui::styles::button::outline_danger)
+            .padding(8)
+            .on_press(action.clone());
+        let buttons = Row::new().spacing(8).push(cancel_btn).push(confirm_btn);
+        let col = Column::new()
+            .padding(16)
+            .spacing(16)
+            .push(description)
+            .push(buttons)
+            .align_items(iced::Alignment::Center);
+
+        Container::new(col)
+            .width(400)
+            .style(zebra_ui::styles::container::primary_bordered_modal)
+    }
+
+    fn view_reprompt(&self) -> Option<Container<HomeMessage>> {
+        if !self.guarded || self.show_trash {
+            return None;
+        }
+
+        let description = Text::new(t!("reprompt_description"))
+            .size(14)
+            .style(zebra_ui::styles::text::muted);
+        let password = SmartInput::new()
+            .set_value(&self.reprompt_password)
+            .padding(8)
+            .set_danger(!self.reprompt_error.is_empty())
+            .set_font_size(14)
+            .set_secure(true)
+            .set_placeholder(t!("placeholder_password"))
+            .on_input(HomeMessage::RepromptInput)
+            .on_submit(HomeMessage::Reprompt);
+        let unlock_btn = Button::new(Text::new(t!("reprompt_unlock")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(8)
+            .on_press_maybe((!self.reprompt_password.is_empty()).then_some(HomeMessage::Reprompt));
+        let row = Row::new()
+            .spacing(8)
+            .align_items(iced::Alignment::Center)
+            .push(Container::new(password).width(Length::Fill))
+            .push(unlock_btn);
+        let mut col = Column::new().spacing(8).push(description).push(row);
+
+        if !self.reprompt_error.is_empty() {
+            col = col.push(
+                Text::new(&self.reprompt_error)
+                    .size(14)
+                    .style(zebra_ui::styles::text::danger),
+            );
+        }
+
+        Some(Container::new(col).padding(16).width(Length::Fill))
+    }
+
+    fn view_ssh_export(&self) -> Option<Container<HomeMessage>> {
+        let selected = self.categories_list.get(self.selected_index)?;
+
+        if !self.read_only || self.show_trash || self.guarded {
+            return None;
+        }
+
+        if !matches!(selected.value, record::Categories::SshKey(_)) {
+            return None;
+        }
+
+        let passphrase = SmartInput::new()
+            .set_value(&self.ssh_passphrase)
+            .padding(8)
+            .set_font_size(14)
+            .set_secure(true)
+            .set_placeholder(t!("placeholder_ssh_passphrase"))
+            .on_input(HomeMessage::SshPassphraseInput)
+            .on_submit(HomeMessage::ExportSshKey);
+        let export_btn = Button::new(Text::new(t!("ssh_export")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(8)
+            .on_press(HomeMessage::ExportSshKey);
+        let row = Row::new()
+            .spacing(8)
+            .align_items(iced::Alignment::Center)
+            .push(Container::new(passphrase).width(Length::Fill))
+            .push(export_btn);
+
+        Some(Container::new(row).padding(16).width(Length::Fill))
+    }
+
+    fn view_expirations(&self) -> Option<Container<HomeMessage>> {
+        if self.hide_expirations || self.expirations.is_empty() || self.show_trash {
+            return None;
+        }
+
+        let title = Text::new(t!("expiry_banner_title", count => self.expirations.len()))
+            .size(14)
+            .width(Length::Fill);
+        let dismiss_btn = Button::new(
+            zebra_ui::image::close_icon()
+                .height(12)
+                .width(12)
+                .style(zebra_ui::styles::svg::primary_hover),
+        )
+        .padding(0)
+        .style(zebra_ui::styles::button::transparent)
+        .on_press(HomeMessage::DismissExpirations);
+        let head = Row::new()
+            .push(title)
+            .push(dismiss_btn)
+            .align_items(iced::Alignment::Center);
+        let col = self.expirations.iter().take(EXPIRATIONS_SHOWN).fold(
+            Column::new().spacing(4).push(head),
+            |col, (expiration, name)| {
+                let detail = match (expiration.kind, expiration.days_left) {
+                    (ExpiryKind::Rotation, days) if days <= 0 => t!("rotation_overdue"),
+                    (ExpiryKind::Rotation, days) => t!("rotation_due", days => days),
+                    (ExpiryKind::Document, days) if days < 0 => {
+                        t!("expiry_overdue", date => expiration.date)
+                    }
+                    (ExpiryKind::Document, days) => t!("expiry_in_days", days => days),
+                };
+                let detail = Text::new(detail).size(13);
+                let detail = if expiration.days_left < 0 {
+                    detail.style(zebra_ui::styles::text::danger)
+                } else {
+                    detail.style(zebra_ui::styles::text::muted)
+                };
+                let open_btn = Button::new(Text::new(t!("expiry_open")).size(13))
+                    .style(zebra_ui::styles::button::outline_primary)
+                    .padding(4)
+                    .on_press(HomeMessage::OpenExpiration(expiration.id));
+                let row = Row::new()
+                    .spacing(8)
+                    .push(Text::new(name).size(14).width(Length::Fill))
+                    .push(detail)
+                    .push(open_btn)
+                    .align_items(iced::Alignment::Center);
+
+                col.push(row)
+            },
+        );
+        let overdue = self.expirations.iter().any(|(e, _)| e.days_left < 0);
+
+        let banner = Container::new(col).padding(8).width(Length::Fill);
+
+        Some(if overdue {
+            banner.style(zebra_ui::styles::container::danger_bordered_hover)
+        } else {
+            banner.style(zebra_ui::styles::container::primary_bordered)
+        })
+    }
 }
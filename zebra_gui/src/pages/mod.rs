@@ -23,6 +23,7 @@ pub mod lock;
 pub mod options;
 pub mod password_setup;
 pub mod restore;
+pub mod security;
 pub mod settings;
 
 pub trait Page {
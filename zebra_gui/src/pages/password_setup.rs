@@ -37,6 +37,8 @@ pub enum LastRoute {
 pub struct PasswordSetup {
     pub last_route: LastRoute,
     error_msg: String,
+    // times the password was seen in breaches, shown once as a warning.
+    breached: Option<u32>,
     salt: String,
     email: String,
     approved: bool,
@@ -101,6 +103,7 @@ impl Page for PasswordSetup {
 
         Ok(Self {
             email,
+            breached: None,
             loading,
             password,
             confirm_password,
@@ -203,6 +206,20 @@ impl Page for PasswordSetup {
                     return Command::none();
                 }
 
+                let breached = match self.core.lock() {
+                    Ok(core) => core.breach_count(&self.password).ok().flatten(),
+                    Err(_) => None,
+                };
+
+                // the next press goes on with the breached password.
+                if let Some(count) = breached.filter(|count| *count > 0) {
+                    if self.breached != Some(count) {
+                        self.breached = Some(count);
+
+                        return Command::none();
+                    }
+                }
+
                 self.loading = true;
 
                 Command::perform(
@@ -243,6 +260,7 @@ impl Page for PasswordSetup {
             }
             PasswordSetupMessage::OnPasswordInputed(v) => {
                 self.error_msg = String::new();
+                self.breached = None;
                 self.password = v;
                 Command::none()
             }
@@ -443,6 +461,11 @@ impl PasswordSetup {
         let error_msg = Text::new(&self.error_msg)
             .style(zebra_ui::styles::text::danger)
             .size(14);
+        let breached = self.breached.map(|count| {
+            Text::new(t!("password_breached", count => count))
+                .style(zebra_ui::styles::text::warn)
+                .size(14)
+        });
         let mut passowrd_input = SmartInput::new()
             .set_value(&self.password)
             .padding(10)
@@ -484,6 +507,7 @@ impl PasswordSetup {
             .push(info)
             .push(Space::new(0, 5))
             .push(error_msg)
+            .push_maybe(breached)
             .push(Space::new(0, 5))
             .push(in_col)
             .push(Space::new(0, 5))
@@ -0,0 +1,582 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+
+use std::sync::{Arc, Mutex};
+
+use iced::widget::scrollable::{Direction, Properties};
+use iced::widget::{Button, Column, Container, Row, Scrollable, Space, Text};
+use iced::{Command, Element, Length, Subscription};
+use zebra_lib::core::audit::{AuditReport, FieldRef};
+use zebra_lib::core::record::{self, Uuid};
+use zebra_lib::{core::Core, errors::ZebraErrors};
+
+use crate::components::home_nav_bar::{NavBar, NavRoute};
+use crate::components::modal::Modal;
+use crate::gui::{GlobalMessage, Routers};
+use crate::rust_i18n::t;
+
+use super::add_record::AddRecordPage;
+use super::error::ErrorPage;
+use super::gen::Generator;
+use super::home::Home;
+use super::settings::Settings;
+use super::Page;
+
+const MAIN_PADDING: f32 = 16.0;
+const ITEM_PADDING: f32 = 8.0;
+
+#[derive(Debug, Clone)]
+struct Finding {
+    id: Uuid,
+    name: String,
+    detail: String,
+}
+
+#[derive(Debug, Clone)]
+struct Section {
+    title: String,
+    findings: Vec<Finding>,
+}
+
+#[derive(Debug)]
+pub struct Security {
+    core: Arc<Mutex<Core>>,
+    checked: usize,
+    sections: Vec<Section>,
+    // records of every duplicate group, oldest first.
+    duplicates: Vec<Vec<record::Element>>,
+    // the group in the merge dialog and the record which stays.
+    merging: Option<(usize, usize)>,
+    error: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum SecurityMessage {
+    RouteHome,
+    RouteGen,
+    RouteSettings,
+    AddRecord,
+    Refresh,
+    OpenRecord(Uuid),
+    OpenMerge(usize),
+    SelectKeep(usize),
+    Merge,
+    CloseMerge,
+}
+
+impl Page for Security {
+    type Message = SecurityMessage;
+
+    fn new(core: Arc<Mutex<Core>>) -> Result<Self, ZebraErrors> {
+        let mut security = Self {
+            core,
+            checked: 0,
+            sections: Vec::new(),
+            duplicates: Vec::new(),
+            merging: None,
+            error: String::new(),
+        };
+
+        security.audit();
+
+        Ok(security)
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    fn update(&mut self, message: Self::Message) -> Command<GlobalMessage> {
+        match message {
+            SecurityMessage::RouteHome => match Home::new(Arc::clone(&self.core)) {
+                Ok(home) => {
+                    let route = Routers::Home(home);
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+                Err(e) => {
+                    let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+            },
+            SecurityMessage::RouteGen => match Generator::new(Arc::clone(&self.core)) {
+                Ok(gen) => {
+                    let route = Routers::Generator(gen);
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+                Err(e) => {
+                    let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+            },
+            SecurityMessage::RouteSettings => match Settings::new(Arc::clone(&self.core)) {
+                Ok(settings) => {
+                    let route = Routers::Settings(settings);
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+                Err(e) => {
+                    let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+            },
+            SecurityMessage::AddRecord => match AddRecordPage::new(Arc::clone(&self.core)) {
+                Ok(add_record) => {
+                    let route = Routers::AddRecord(add_record);
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+                Err(e) => {
+                    let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+            },
+            SecurityMessage::OpenRecord(id) => match Home::new(Arc::clone(&self.core)) {
+                Ok(home) => {
+                    let route = Routers::Home(home.select(&id));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+                Err(e) => {
+                    let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+            },
+            SecurityMessage::Refresh => {
+                self.audit();
+
+                Command::none()
+            }
+            SecurityMessage::OpenMerge(group) => {
+                self.merging = Some((group, 0));
+
+                Command::none()
+            }
+            SecurityMessage::SelectKeep(keep) => {
+                if let Some((group, _)) = self.merging {
+                    self.merging = Some((group, keep));
+                }
+
+                Command::none()
+            }
+            SecurityMessage::CloseMerge => {
+                self.merging = None;
+
+                Command::none()
+            }
+            SecurityMessage::Merge => {
+                let (group, keep) = match self.merging.take() {
+                    Some(merging) => merging,
+                    None => return Command::none(),
+                };
+                let ids: Vec<Uuid> = self.duplicates[group].iter().map(|e| e.id).collect();
+                let merged = match self.core.lock() {
+                    Ok(mut core) => core
+                        .merge_elements(&ids[keep], &ids)
+                        .map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+
+                match merged {
+                    Ok(()) => self.audit(),
+                    Err(e) => self.error = e,
+                }
+
+                Command::none()
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Self::Message> {
+        let title = Text::new(t!("security_title"))
+            .size(24)
+            .width(Length::Fill)
+            .horizontal_alignment(iced::alignment::Horizontal::Left);
+        let refresh_btn = Button::new(Text::new(t!("security_refresh")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(ITEM_PADDING)
+            .on_press(SecurityMessage::Refresh);
+        let head_row = Row::new()
+            .push(title)
+            .push(refresh_btn)
+            .align_items(iced::Alignment::Center);
+        let issues: usize = self.sections.iter().map(|s| s.findings.len()).sum();
+        let summary = Text::new(t!(
+            "security_summary",
+            checked => self.checked,
+            issues => issues
+        ))
+        .size(14)
+        .style(zebra_ui::styles::text::muted)
+        .width(Length::Fill);
+        let mut main_col = Column::new()
+            .padding(MAIN_PADDING)
+            .push(head_row)
+            .push(Space::new(0, ITEM_PADDING))
+            .push(summary);
+
+        if !self.error.is_empty() {
+            let error = Text::new(&self.error)
+                .size(14)
+                .style(zebra_ui::styles::text::danger);
+
+            main_col = main_col.push(Space::new(0, ITEM_PADDING)).push(error);
+        }
+
+        let issues = issues + self.duplicates.len();
+
+        if issues == 0 && self.error.is_empty() {
+            let clean = Text::new(t!("security_clean")).size(16);
+
+            main_col = main_col.push(Space::new(0, MAIN_PADDING)).push(clean);
+        }
+
+        for section in self.sections.iter().filter(|s| !s.findings.is_empty()) {
+            main_col = main_col
+                .push(Space::new(0, MAIN_PADDING))
+                .push(self.view_section(section));
+        }
+
+        if !self.duplicates.is_empty() {
+            main_col = main_col
+                .push(Space::new(0, MAIN_PADDING))
+                .push(self.view_duplicates());
+        }
+
+        let scrolling = Scrollable::new(main_col)
+            .height(Length::Fill)
+            .style(zebra_ui::styles::scrollable::scroll_transparent);
+        let content = match self.merging {
+            Some((group, keep)) => Container::new(
+                Modal::new(scrolling, self.view_merge_modal(group, keep))
+                    .on_blur(SecurityMessage::CloseMerge),
+            ),
+            None => Container::new(scrolling),
+        };
+
+        NavBar::<Self::Message>::new()
+            .set_route(NavRoute::Security)
+            .on_home(&SecurityMessage::RouteHome)
+            .on_gen(&SecurityMessage::RouteGen)
+            .on_settings(&SecurityMessage::RouteSettings)
+            .on_add(&SecurityMessage::AddRecord)
+            .view(content.width(Length::Fill))
+            .into()
+    }
+}
+
+impl Security {
+    fn audit(&mut self) {
+        let core = match self.core.lock() {
+            Ok(core) => core,
+            Err(e) => {
+                self.error = e.to_string();
+                return;
+            }
+        };
+
+        match core.audit() {
+            Ok(report) => {
+                self.checked = report.checked;
+                self.sections = Self::sections(&core, &report);
+                self.duplicates = core
+                    .find_duplicates()
+                    .iter()
+                    .map(|group| {
+                        group
+                            .iter()
+                            .filter_map(|id| core.get_element(id))
+                            .map(|record| record.get_value().clone())
+                            .collect()
+                    })
+                    .collect();
+                self.error = String::new();
+            }
+            Err(e) => {
+                self.error = e.to_string();
+            }
+        }
+    }
+
+    fn sections(core: &Core, report: &AuditReport) -> Vec<Section> {
+        let finding = |at: &FieldRef, detail: String| {
+            let (name, field) = match core.get_element(&at.id) {
+                Some(record) => {
+                    let elem = record.get_value();
+                    let field = elem
+                        .get_field(at.field)
+                        .map(|item| item.title.clone())
+                        .unwrap_or_default();
+
+                    (elem.name.clone(), field)
+                }
+                None => Default::default(),
+            };
+
+            Finding {
+                id: at.id,
+                name: format!("{} · {}", name, field),
+                detail,
+            }
+        };
+        let weak = report
+            .weak
+            .iter()
+            .map(|w| {
+                finding(
+                    &w.at,
+                    t!("security_strength", strength => w.strength).to_string(),
+                )
+            })
+            .collect();
+        let breached = report
+            .breached
+            .iter()
+            .map(|b| {
+                finding(
+                    &b.at,
+                    t!("security_breached_times", count => b.count).to_string(),
+                )
+            })
+            .collect();
+        let reused = report
+            .reused
+            .iter()
+            .flat_map(|group| {
+                group.iter().map(|at| {
+                    finding(
+                        at,
+                        t!("security_reused_times", count => group.len()).to_string(),
+                    )
+                })
+            })
+            .collect();
+        let similar = report
+            .similar
+            .iter()
+            .flat_map(|(a, b)| {
+                let first = finding(a, String::new());
+                let second = finding(b, String::new());
+
+                [
+                    Finding {
+                        detail: t!("security_similar_to", name => &second.name).to_string(),
+                        ..first.clone()
+                    },
+                    Finding {
+                        detail: t!("security_similar_to", name => &first.name).to_string(),
+                        ..second
+                    },
+                ]
+            })
+            .collect();
+        let stale = report
+            .stale
+            .iter()
+            .map(|s| finding(&s.at, t!("security_days_old", days => s.days).to_string()))
+            .collect();
+        let missing_2fa = report
+            .missing_2fa
+            .iter()
+            .map(|id| Finding {
+                id: *id,
+                name: core
+                    .get_element(id)
+                    .map(|record| record.get_value().name.clone())
+                    .unwrap_or_default(),
+                detail: String::new(),
+            })
+            .collect();
+
+        vec![
+            Section {
+                title: t!("security_weak").to_string(),
+                findings: weak,
+            },
+            Section {
+                title: t!("security_breached").to_string(),
+                findings: breached,
+            },
+            Section {
+                title: t!("security_reused").to_string(),
+                findings: reused,
+            },
+            Section {
+                title: t!("security_similar").to_string(),
+                findings: similar,
+            },
+            Section {
+                title: t!("security_stale").to_string(),
+                findings: stale,
+            },
+            Section {
+                title: t!("security_missing_2fa").to_string(),
+                findings: missing_2fa,
+            },
+        ]
+    }
+
+    fn view_section<'a>(&self, section: &'a Section) -> Column<'a, SecurityMessage> {
+        let title = Text::new(format!("{} ({})", section.title, section.findings.len()))
+            .size(18)
+            .width(Length::Fill);
+
+        section.findings.iter().fold(
+            Column::new().spacing(ITEM_PADDING).push(title),
+            |col, finding| {
+                let name = Text::new(&finding.name).size(14).width(Length::Fill);
+                let detail = Text::new(&finding.detail)
+                    .size(13)
+                    .style(zebra_ui::styles::text::muted);
+                let open_btn = Button::new(Text::new(t!("security_open")).size(13))
+                    .style(zebra_ui::styles::button::outline_primary)
+                    .padding(4)
+                    .on_press(SecurityMessage::OpenRecord(finding.id));
+                let row = Row::new()
+                    .spacing(ITEM_PADDING)
+                    .push(name)
+                    .push(detail)
+                    .push(open_btn)
+                    .align_items(iced::Alignment::Center);
+
+                col.push(row)
+            },
+        )
+    }
+
+    fn view_duplicates(&self) -> Column<SecurityMessage> {
+        let title = Text::new(format!(
+            "{} ({})",
+            t!("security_duplicates"),
+            self.duplicates.len()
+        ))
+        .size(18)
+        .width(Length::Fill);
+
+        self.duplicates.iter().enumerate().fold(
+            Column::new().spacing(ITEM_PADDING).push(title),
+            |col, (index, group)| {
+                let name = Text::new(&group[0].name).size(14).width(Length::Fill);
+                let detail = Text::new(t!("security_duplicates_count", count => group.len()))
+                    .size(13)
+                    .style(zebra_ui::styles::text::muted);
+                let merge_btn = Button::new(Text::new(t!("merge")).size(13))
+                    .style(zebra_ui::styles::button::outline_primary)
+                    .padding(4)
+                    .on_press(SecurityMessage::OpenMerge(index));
+                let row = Row::new()
+                    .spacing(ITEM_PADDING)
+                    .push(name)
+                    .push(detail)
+                    .push(merge_btn)
+                    .align_items(iced::Alignment::Center);
+
+                col.push(row)
+            },
+        )
+    }
+
+    // the records of a group next to each other, the chosen one absorbs the rest.
+    fn view_merge_modal(&self, group: usize, keep: usize) -> Container<SecurityMessage> {
+        let close_btn = Button::new(
+            zebra_ui::image::close_icon()
+                .style(zebra_ui::styles::svg::primary_hover)
+                .height(30)
+                .width(30),
+        )
+        .padding(0)
+        .style(zebra_ui::styles::button::transparent)
+        .on_press(SecurityMessage::CloseMerge);
+        let title = Text::new(t!("merge_title")).size(18).width(Length::Fill);
+        let head_row = Row::new()
+            .push(title)
+            .push(close_btn)
+            .align_items(iced::Alignment::Center);
+        let description = Text::new(t!("merge_description"))
+            .size(14)
+            .style(zebra_ui::styles::text::muted);
+        let columns = self.duplicates[group]
+            .iter()
+            .enumerate()
+            .fold(Row::new().spacing(MAIN_PADDING), |row, (index, elem)| {
+                row.push(Self::view_merge_column(elem, index, index == keep))
+            });
+        let merge_btn = Button::new(
+            Text::new(t!("merge"))
+                .size(MAIN_PADDING)
+                .horizontal_alignment(iced::alignment::Horizontal::Center),
+        )
+        .style(zebra_ui::styles::button::outline_primary)
+        .padding(ITEM_PADDING)
+        .width(Length::Fill)
+        .on_press(SecurityMessage::Merge);
+        let col = Column::new()
+            .padding(MAIN_PADDING)
+            .spacing(ITEM_PADDING)
+            .push(head_row)
+            .push(description)
+            .push(Scrollable::new(columns).direction(Direction::Horizontal(Properties::default())))
+            .push(merge_btn);
+
+        Container::new(col)
+            .max_width(900)
+            .style(zebra_ui::styles::container::primary_bordered_modal)
+    }
+
+    fn view_merge_column(
+        elem: &record::Element,
+        index: usize,
+        keep: bool,
+    ) -> Container<SecurityMessage> {
+        let keep_btn = Button::new(
+            Text::new(if keep {
+                t!("merge_kept")
+            } else {
+                t!("merge_keep")
+            })
+            .size(13),
+        )
+        .style(zebra_ui::styles::button::outline_primary)
+        .padding(4)
+        .on_press_maybe((!keep).then_some(SecurityMessage::SelectKeep(index)));
+        let name = Text::new(&elem.name).size(16).width(Length::Fill);
+        let col = elem
+            .fields
+            .iter()
+            .chain(elem.extra_fields.iter())
+            .filter(|item| !item.value.is_empty())
+            .fold(
+                Column::new()
+                    .spacing(4)
+                    .push(Row::new().push(name).push(keep_btn)),
+                |col, item| {
+                    let value = if item.hide {
+                        "••••••••".to_string()
+                    } else {
+                        item.value.clone()
+                    };
+
+                    col.push(
+                        Text::new(item.title.clone())
+                            .size(12)
+                            .style(zebra_ui::styles::text::muted),
+                    )
+                    .push(Text::new(value).size(14))
+                },
+            );
+        let column = Container::new(col).padding(ITEM_PADDING).width(260);
+
+        if keep {
+            column.style(zebra_ui::styles::container::primary_bordered)
+        } else {
+            column
+        }
+    }
+}
@@ -4,15 +4,27 @@
 
 use std::sync::{Arc, Mutex, MutexGuard};
 
-use iced::widget::{Button, Checkbox, Column, Container, Row, Space, Text};
+use iced::widget::{pick_list, Button, Checkbox, Column, Container, Row, Space, Text};
 use iced::{Command, Element, Length, Subscription};
 use iced::{Renderer, Theme};
-use zebra_lib::{core::Core, errors::ZebraErrors};
+use zebra_lib::{
+    config::{app::VAULT_DIR_NAME, location::VaultLocation},
+    core::{
+        record::{Item, Uuid},
+        verify::{Check, VerifyReport},
+        Core,
+    },
+    errors::ZebraErrors,
+};
 
 use dirs;
 use rfd::FileDialog;
 
+use crate::components::add_record_from::format_size;
+use crate::components::custom_field::CustomFields;
 use crate::components::modal::Modal;
+use crate::components::organize::{self, Choice};
+use crate::components::smart_input::SmartInput;
 use crate::components::{
     general_settings::GeneralSettings,
     home_nav_bar::{NavBar, NavRoute, LINE_ALFA_CHANNEL},
@@ -25,10 +37,23 @@ use super::add_record::AddRecordPage;
 use super::error::ErrorPage;
 use super::gen::Generator;
 use super::home::Home;
+use super::locale::Locale;
+use super::security::Security;
 use super::Page;
 
 const MAIN_PADDING: f32 = 16.0;
 const ITEM_PADDING: f32 = 8.0;
+const MIB: u64 = 1024 * 1024;
+const ATTACHMENT_LIMITS: [u64; 6] = [MIB, 5 * MIB, 10 * MIB, 25 * MIB, 50 * MIB, 100 * MIB];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitOption(u64);
+
+impl std::fmt::Display for LimitOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", format_size(self.0))
+    }
+}
 
 #[derive(Debug, Clone)]
 enum SettingsOptions {
@@ -36,21 +61,47 @@ enum SettingsOptions {
     General,
     Crypto,
     Advanced,
+    Organize,
     Network,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrganizeItem {
+    Tag(Uuid),
+    Folder(Uuid),
+    Category(Uuid),
+}
+
 #[derive(Debug)]
 pub struct Settings {
     core: Arc<Mutex<Core>>,
     remove_modal: bool,
     selected_index: usize,
-    local_remove: bool,
     selected_option: SettingsOptions,
     options_list: Vec<select_list::SelectListField<SettingsOptions>>,
+    remove_password: String,
+    remove_error: String,
+    advanced_password: String,
+    advanced_error: String,
+    verify_report: Option<VerifyReport>,
+    // where the vault went after the last move.
+    vault_moved: String,
+    new_tag: String,
+    new_folder: String,
+    new_folder_parent: Option<Choice>,
+    renaming: Option<OrganizeItem>,
+    rename_value: String,
+    new_category: String,
+    new_category_icon: String,
+    // the category whose template is open and its edited fields.
+    editing_template: Option<Uuid>,
+    template: Vec<Item>,
+    organize_error: String,
 }
 
 #[derive(Debug, Clone)]
 pub enum SettingsMessage {
+    RouteSecurity,
     RouteHome,
     RouteGen,
     AddRecord,
@@ -59,9 +110,36 @@ pub enum SettingsMessage {
     EditEmail(String),
     Remove,
     RemoveModal,
-    ToggleRemoveLocally(bool),
+    RemovePasswordInput(String),
     ExportRecords,
     ExportDatabase,
+    MoveVault,
+    AdvancedPasswordInput(String),
+    VerifyVault,
+    RepairVault,
+    RepairFromBackup,
+    PickBreachFile,
+    PickBreachFolder,
+    ClearBreachDb,
+    AttachmentLimit(LimitOption),
+    ToggleAgent(bool),
+    ToggleAgentConfirm(bool),
+    NewTagInput(String),
+    CreateTag,
+    NewFolderInput(String),
+    NewFolderParent(Choice),
+    CreateFolder,
+    StartRename(OrganizeItem, String),
+    RenameInput(String),
+    SaveRename,
+    Merge(OrganizeItem, Choice),
+    Delete(OrganizeItem),
+    NewCategoryInput(String),
+    NewCategoryIconInput(String),
+    CreateCategory,
+    EditTemplate(Uuid),
+    TemplateInput(Vec<Item>),
+    SaveTemplate,
 }
 
 impl Page for Settings {
@@ -81,6 +159,10 @@ impl Page for Settings {
                 text: t!("advanced").to_string(),
                 value: SettingsOptions::Advanced,
             },
+            select_list::SelectListField {
+                text: t!("tags_and_folders").to_string(),
+                value: SettingsOptions::Organize,
+            },
             select_list::SelectListField {
                 text: t!("crypto").to_string(),
                 value: SettingsOptions::Crypto,
@@ -96,8 +178,23 @@ impl Page for Settings {
             options_list,
             selected_index: 1,
             remove_modal: false,
-            local_remove: false,
             selected_option: SettingsOptions::General,
+            remove_password: String::new(),
+            remove_error: String::new(),
+            advanced_password: String::new(),
+            advanced_error: String::new(),
+            verify_report: None,
+            vault_moved: String::new(),
+            new_tag: String::new(),
+            new_folder: String::new(),
+            new_folder_parent: None,
+            renaming: None,
+            rename_value: String::new(),
+            new_category: String::new(),
+            new_category_icon: String::new(),
+            editing_template: None,
+            template: Vec::new(),
+            organize_error: String::new(),
         })
     }
 
@@ -107,11 +204,18 @@ impl Page for Settings {
 
     fn update(&mut self, message: Self::Message) -> iced::Command<GlobalMessage> {
         match message {
-            SettingsMessage::ToggleRemoveLocally(value) => {
-                self.local_remove = value;
+            SettingsMessage::RouteSecurity => match Security::new(Arc::clone(&self.core)) {
+                Ok(security) => {
+                    let route = Routers::Security(security);
 
-                Command::none()
-            }
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+                Err(e) => {
+                    let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                    Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                }
+            },
             SettingsMessage::RouteHome => match Home::new(Arc::clone(&self.core)) {
                 Ok(home) => {
                     let route = Routers::Home(home);
@@ -162,10 +266,41 @@ impl Page for Settings {
                 let _ = self.core.lock().map(|mut core| core.set_email(value));
                 Command::none()
             }
-            SettingsMessage::Remove => {
-                //
+            SettingsMessage::RemovePasswordInput(value) => {
+                self.remove_password = value;
+                self.remove_error = String::new();
+
                 Command::none()
             }
+            SettingsMessage::Remove => {
+                let result = self
+                    .core
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut core| {
+                        core.destroy_vault(&self.remove_password)
+                            .map_err(|e| e.to_string())
+                    });
+
+                if let Err(e) = result {
+                    self.remove_error = e;
+
+                    return Command::none();
+                }
+
+                match Locale::new(Arc::clone(&self.core)) {
+                    Ok(locale) => {
+                        let route = Routers::Locale(locale);
+
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                    Err(e) => {
+                        let route = Routers::ErrorPage(ErrorPage::from(e.to_string()));
+
+                        Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+                    }
+                }
+            }
             SettingsMessage::ExportRecords => {
                 let error_msg;
 
@@ -236,8 +371,315 @@ impl Page for Settings {
                 let route = Routers::ErrorPage(ErrorPage::from(error_msg));
                 Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
             }
+            SettingsMessage::MoveVault => {
+                let error_msg;
+
+                if let Some(home_dir) = dirs::home_dir() {
+                    let folder = FileDialog::new().set_directory(home_dir).pick_folder();
+
+                    if let Some(folder) = folder {
+                        match self.core.lock() {
+                            Ok(mut core) => {
+                                match core.move_vault(&folder.join(VAULT_DIR_NAME)) {
+                                    Ok(_) => {
+                                        let path = core
+                                            .get_data_dir()
+                                            .map(|path| path.display().to_string())
+                                            .unwrap_or_default();
+
+                                        self.vault_moved = match core.get_location() {
+                                            VaultLocation::Custom(_) => {
+                                                t!("vault_moved_custom", path => path)
+                                            }
+                                            _ => t!("vault_moved", path => path),
+                                        }
+                                        .to_string();
+
+                                        return Command::none();
+                                    }
+                                    Err(e) => {
+                                        error_msg = e.to_string();
+                                    }
+                                };
+                            }
+                            Err(e) => {
+                                error_msg = e.to_string();
+                            }
+                        }
+                    } else {
+                        return Command::none();
+                    }
+                } else {
+                    error_msg = t!("cannot_get_access_home_dir").to_string();
+                }
+
+                let route = Routers::ErrorPage(ErrorPage::from(error_msg));
+                Command::perform(std::future::ready(1), |_| GlobalMessage::Route(route))
+            }
+            SettingsMessage::AdvancedPasswordInput(value) => {
+                self.advanced_password = value;
+                self.advanced_error = String::new();
+
+                Command::none()
+            }
+            SettingsMessage::VerifyVault => {
+                let result = self
+                    .core
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|core| {
+                        core.verify(&self.advanced_password)
+                            .map_err(|e| e.to_string())
+                    });
+
+                self.handle_report(result);
+
+                Command::none()
+            }
+            SettingsMessage::RepairVault => {
+                let result = self
+                    .core
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut core| {
+                        core.repair(&self.advanced_password, None)
+                            .map_err(|e| e.to_string())
+                    });
+
+                self.handle_report(result);
+
+                Command::none()
+            }
+            SettingsMessage::RepairFromBackup => {
+                let home_dir = match dirs::home_dir() {
+                    Some(home_dir) => home_dir,
+                    None => {
+                        self.advanced_error = t!("cannot_get_access_home_dir").to_string();
+
+                        return Command::none();
+                    }
+                };
+                let file = FileDialog::new()
+                    .add_filter("zebra", &["zebra"])
+                    .set_directory(home_dir)
+                    .pick_file();
+
+                if let Some(path) = file {
+                    let result =
+                        self.core
+                            .lock()
+                            .map_err(|e| e.to_string())
+                            .and_then(|mut core| {
+                                core.repair(&self.advanced_password, Some(&path))
+                                    .map_err(|e| e.to_string())
+                            });
+
+                    self.handle_report(result);
+                }
+
+                Command::none()
+            }
+            SettingsMessage::PickBreachFile | SettingsMessage::PickBreachFolder => {
+                let dialog = FileDialog::new();
+                let path = if matches!(message, SettingsMessage::PickBreachFile) {
+                    dialog.pick_file()
+                } else {
+                    dialog.pick_folder()
+                };
+
+                if let Some(path) = path {
+                    self.set_breach_db(Some(path));
+                }
+
+                Command::none()
+            }
+            SettingsMessage::ClearBreachDb => {
+                self.set_breach_db(None);
+
+                Command::none()
+            }
+            SettingsMessage::AttachmentLimit(LimitOption(limit)) => {
+                let result = self
+                    .core
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut core| {
+                        core.set_attachment_limit(limit).map_err(|e| e.to_string())
+                    });
+
+                self.advanced_error = result.err().unwrap_or_default();
+
+                Command::none()
+            }
+            SettingsMessage::ToggleAgent(enabled) => {
+                let result = self
+                    .core
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut core| {
+                        let confirm = core.state.settings.agent.confirm;
+
+                        core.set_agent(enabled, confirm).map_err(|e| e.to_string())
+                    });
+
+                self.advanced_error = result.err().unwrap_or_default();
+
+                Command::none()
+            }
+            SettingsMessage::ToggleAgentConfirm(confirm) => {
+                let result = self
+                    .core
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut core| {
+                        let enabled = core.state.settings.agent.enabled;
+
+                        core.set_agent(enabled, confirm).map_err(|e| e.to_string())
+                    });
+
+                self.advanced_error = result.err().unwrap_or_default();
+
+                Command::none()
+            }
+            SettingsMessage::NewTagInput(value) => {
+                self.new_tag = value;
+
+                Command::none()
+            }
+            SettingsMessage::NewFolderInput(value) => {
+                self.new_folder = value;
+
+                Command::none()
+            }
+            SettingsMessage::NewFolderParent(choice) => {
+                self.new_folder_parent = Some(choice);
+
+                Command::none()
+            }
+            SettingsMessage::RenameInput(value) => {
+                self.rename_value = value;
+
+                Command::none()
+            }
+            SettingsMessage::StartRename(item, name) => {
+                self.renaming = Some(item);
+                self.rename_value = name;
+
+                Command::none()
+            }
+            SettingsMessage::CreateTag => {
+                let name = std::mem::take(&mut self.new_tag);
+
+                self.organize(|core| core.create_tag(&name).map(|_| ()))
+            }
+            SettingsMessage::CreateFolder => {
+                let name = std::mem::take(&mut self.new_folder);
+                let parent = self.new_folder_parent.take().and_then(|choice| choice.id);
+
+                self.organize(|core| core.create_folder(&name, parent).map(|_| ()))
+            }
+            SettingsMessage::SaveRename => {
+                let name = std::mem::take(&mut self.rename_value);
+
+                match self.renaming.take() {
+                    Some(OrganizeItem::Tag(id)) => {
+                        self.organize(|core| core.rename_tag(&id, &name))
+                    }
+                    Some(OrganizeItem::Folder(id)) => {
+                        self.organize(|core| core.rename_folder(&id, &name))
+                    }
+                    Some(OrganizeItem::Category(id)) => self.organize(|core| {
+                        let category = core
+                            .get_custom_category(&id)
+                            .cloned()
+                            .ok_or(ZebraErrors::CoreCategoryNotFound)?;
+
+                        core.update_custom_category(&id, &name, &category.icon, category.template)
+                    }),
+                    None => Command::none(),
+                }
+            }
+            SettingsMessage::Merge(item, choice) => match (item, choice.id) {
+                (OrganizeItem::Tag(from), Some(into)) => {
+                    self.organize(|core| core.merge_tags(&from, &into))
+                }
+                (OrganizeItem::Folder(from), Some(into)) => {
+                    self.organize(|core| core.merge_folders(&from, &into))
+                }
+                _ => Command::none(),
+            },
+            SettingsMessage::Delete(item) => match item {
+                OrganizeItem::Tag(id) => self.organize(|core| core.delete_tag(&id)),
+                OrganizeItem::Folder(id) => self.organize(|core| core.delete_folder(&id)),
+                OrganizeItem::Category(id) => {
+                    if self.editing_template == Some(id) {
+                        self.editing_template = None;
+                    }
+
+                    self.organize(|core| core.delete_custom_category(&id))
+                }
+            },
+            SettingsMessage::NewCategoryInput(value) => {
+                self.new_category = value;
+
+                Command::none()
+            }
+            SettingsMessage::NewCategoryIconInput(value) => {
+                self.new_category_icon = value;
+
+                Command::none()
+            }
+            SettingsMessage::CreateCategory => {
+                let name = std::mem::take(&mut self.new_category);
+                let icon = std::mem::take(&mut self.new_category_icon);
+
+                self.organize(|core| {
+                    core.create_custom_category(&name, &icon, Vec::new())
+                        .map(|_| ())
+                })
+            }
+            SettingsMessage::EditTemplate(id) => {
+                if self.editing_template == Some(id) {
+                    self.editing_template = None;
+
+                    return Command::none();
+                }
+
+                self.template = match self.core.lock() {
+                    Ok(core) => core
+                        .get_custom_category(&id)
+                        .map(|category| category.template.clone())
+                        .unwrap_or_default(),
+                    Err(_) => Vec::new(),
+                };
+                self.editing_template = Some(id);
+
+                Command::none()
+            }
+            SettingsMessage::TemplateInput(template) => {
+                self.template = template;
+
+                Command::none()
+            }
+            SettingsMessage::SaveTemplate => match self.editing_template.take() {
+                Some(id) => {
+                    let template = std::mem::take(&mut self.template);
+
+                    self.organize(|core| {
+                        let category = core
+                            .get_custom_category(&id)
+                            .cloned()
+                            .ok_or(ZebraErrors::CoreCategoryNotFound)?;
+
+                        core.update_custom_category(&id, &category.name, &category.icon, template)
+                    })
+                }
+                None => Command::none(),
+            },
             SettingsMessage::RemoveModal => {
                 self.remove_modal = !self.remove_modal;
+                self.remove_password = String::new();
+                self.remove_error = String::new();
                 Command::none()
             }
         }
@@ -266,7 +708,8 @@ impl Page for Settings {
                 SettingsOptions::Profile => self.view_profile(core),
                 SettingsOptions::General => self.view_general(),
                 SettingsOptions::Network => self.view_network(),
-                SettingsOptions::Advanced => self.view_advanced(),
+                SettingsOptions::Advanced => self.view_advanced(core),
+                SettingsOptions::Organize => self.view_organize(core),
                 SettingsOptions::Crypto => self.view_crypto(),
             }
         } else {
@@ -287,13 +730,14 @@ impl Page for Settings {
             .on_home(&SettingsMessage::RouteHome)
             .on_gen(&SettingsMessage::RouteGen)
             .on_add(&SettingsMessage::AddRecord)
+            .on_security(&SettingsMessage::RouteSecurity)
             .view(content)
             .into()
     }
 }
 
 impl Settings {
-    pub fn view_remove_modal<'a>(&self) -> Container<'a, SettingsMessage, Theme, Renderer> {
+    pub fn view_remove_modal(&self) -> Container<SettingsMessage, Theme, Renderer> {
         let close_btn = Button::new(
             zebra_ui::image::close_icon()
                 .style(zebra_ui::styles::svg::primary_hover)
@@ -308,17 +752,31 @@ impl Settings {
             .width(Length::Fill)
             .align_items(iced::Alignment::End);
 
-        let enable_local_remove = Checkbox::new(t!("server_sync_check_box"), self.local_remove)
-            .on_toggle(SettingsMessage::ToggleRemoveLocally)
-            .text_size(14);
         let description = Text::new(t!("remove_account_description"))
             .size(14)
             .horizontal_alignment(iced::alignment::Horizontal::Center)
             .style(zebra_ui::styles::text::warn);
-        let col = Column::new()
+        let password_input = SmartInput::new()
+            .set_value(&self.remove_password)
+            .padding(8)
+            .set_danger(!self.remove_error.is_empty())
+            .set_font_size(14)
+            .set_secure(true)
+            .set_placeholder(t!("placeholder_password"))
+            .on_input(SettingsMessage::RemovePasswordInput)
+            .on_submit(SettingsMessage::Remove);
+        let mut col = Column::new()
             .push(description)
             .push(Space::new(0, ITEM_PADDING))
-            .push(enable_local_remove);
+            .push(password_input);
+
+        if !self.remove_error.is_empty() {
+            let error = Text::new(&self.remove_error)
+                .size(14)
+                .style(zebra_ui::styles::text::danger);
+
+            col = col.push(Space::new(0, ITEM_PADDING)).push(error);
+        }
 
         let row_header = Row::new().padding(8).push(close_btn).width(Length::Fill);
 
@@ -329,7 +787,7 @@ impl Settings {
         )
         .style(zebra_ui::styles::button::outline_danger)
         .padding(ITEM_PADDING)
-        .on_press(SettingsMessage::Remove);
+        .on_press_maybe((!self.remove_password.is_empty()).then_some(SettingsMessage::Remove));
 
         let main_modal_col = Column::new()
             .push(row_header)
@@ -353,18 +811,36 @@ impl Settings {
             .width(Length::Fill);
         let profile_view = ProfileViewForm::new()
             .set_data_size(core.get_data_size())
+            .set_attachments_size(core.get_attachments_size())
             .set_records_len(core.data.len())
             .set_storage_version(core.get_storage_version())
             .set_email(core.state.email.clone().unwrap_or_default())
             .set_address(core.state.address.clone())
-            .set_data_dir_path(core.get_data_dir().to_string_lossy().to_string().into())
+            .set_data_dir_path(
+                core.get_data_dir()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_default()
+                    .into(),
+            )
             .on_copy(SettingsMessage::CopyValue)
             .on_edit_email(SettingsMessage::EditEmail)
             .on_export_database(SettingsMessage::ExportDatabase)
             .on_export_records(SettingsMessage::ExportRecords)
             .set_main_padding(MAIN_PADDING)
             .set_item_padding(ITEM_PADDING);
+        // a portable vault is found next to the executable, it can't be moved.
+        let portable = *core.get_location() == VaultLocation::Portable;
+        let profile_view = if portable {
+            profile_view
+        } else {
+            profile_view.on_move_vault(SettingsMessage::MoveVault)
+        };
         let profile_view = Container::new(profile_view);
+        let vault_note = if portable {
+            t!("vault_move_portable").to_string()
+        } else {
+            self.vault_moved.clone()
+        };
 
         let remove_button = Button::new(Text::new(t!("remove")).size(14))
             .padding(0)
@@ -378,8 +854,20 @@ impl Settings {
             .push(title)
             .push(Space::new(0, MAIN_PADDING))
             .push(profile_view)
-            .push(Space::new(0, MAIN_PADDING))
-            .push(remove_button_row);
+            .push(Space::new(0, MAIN_PADDING));
+        let main_col = if vault_note.is_empty() {
+            main_col
+        } else {
+            main_col
+                .push(
+                    Text::new(vault_note)
+                        .size(14)
+                        .style(zebra_ui::styles::text::muted)
+                        .width(Length::Fill),
+                )
+                .push(Space::new(0, MAIN_PADDING))
+        };
+        let main_col = main_col.push(remove_button_row);
 
         Container::new(main_col)
     }
@@ -402,12 +890,468 @@ impl Settings {
         Container::new(main_col)
     }
 
-    pub fn view_advanced(&self) -> Container<SettingsMessage> {
-        let main_col = Column::new();
+    pub fn view_advanced(&self, core: MutexGuard<Core>) -> Container<SettingsMessage> {
+        let title = Text::new(&self.options_list[self.selected_index].text)
+            .size(24)
+            .horizontal_alignment(iced::alignment::Horizontal::Left)
+            .width(Length::Fill);
+        let description = Text::new(t!("verify_vault_description"))
+            .size(14)
+            .style(zebra_ui::styles::text::muted)
+            .width(Length::Fill);
+        let password_input = SmartInput::new()
+            .set_value(&self.advanced_password)
+            .padding(8)
+            .set_danger(!self.advanced_error.is_empty())
+            .set_font_size(14)
+            .set_secure(true)
+            .set_placeholder(t!("placeholder_password"))
+            .on_input(SettingsMessage::AdvancedPasswordInput)
+            .on_submit(SettingsMessage::VerifyVault);
+        let password_input = Container::new(password_input).width(300);
+
+        let verify_btn = Button::new(Text::new(t!("verify_vault")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(ITEM_PADDING)
+            .on_press_maybe(
+                (!self.advanced_password.is_empty()).then_some(SettingsMessage::VerifyVault),
+            );
+        let mut buttons_row = Row::new().spacing(ITEM_PADDING).push(verify_btn);
+
+        // repair is offered only after a failed check.
+        if self.verify_report.as_ref().is_some_and(|r| !r.is_ok()) {
+            let repair_btn = Button::new(Text::new(t!("repair_vault")).size(14))
+                .style(zebra_ui::styles::button::outline_danger)
+                .padding(ITEM_PADDING)
+                .on_press(SettingsMessage::RepairVault);
+            let backup_btn = Button::new(Text::new(t!("repair_from_backup")).size(14))
+                .style(zebra_ui::styles::button::outline_danger)
+                .padding(ITEM_PADDING)
+                .on_press(SettingsMessage::RepairFromBackup);
+
+            buttons_row = buttons_row.push(repair_btn).push(backup_btn);
+        }
+
+        let mut main_col = Column::new()
+            .padding(MAIN_PADDING)
+            .push(title)
+            .push(Space::new(0, MAIN_PADDING))
+            .push(description)
+            .push(Space::new(0, ITEM_PADDING))
+            .push(password_input)
+            .push(Space::new(0, ITEM_PADDING))
+            .push(buttons_row);
+
+        if !self.advanced_error.is_empty() {
+            let error = Text::new(&self.advanced_error)
+                .size(14)
+                .style(zebra_ui::styles::text::danger);
+
+            main_col = main_col.push(Space::new(0, ITEM_PADDING)).push(error);
+        }
+
+        if let Some(report) = &self.verify_report {
+            main_col = main_col
+                .push(Space::new(0, MAIN_PADDING))
+                .push(view_report(report));
+        }
+
+        main_col = main_col
+            .push(Space::new(0, MAIN_PADDING))
+            .push(self.view_attachment_limit(&core))
+            .push(Space::new(0, MAIN_PADDING))
+            .push(self.view_agent(&core))
+            .push(Space::new(0, MAIN_PADDING))
+            .push(self.view_breach_db(core));
 
         Container::new(main_col)
     }
 
+    fn view_attachment_limit<'a>(&self, core: &Core) -> Row<'a, SettingsMessage> {
+        let description = Text::new(t!("attachment_limit_description"))
+            .size(14)
+            .style(zebra_ui::styles::text::muted)
+            .width(Length::Fill);
+        let limit_pick_list = pick_list(
+            ATTACHMENT_LIMITS.map(LimitOption),
+            Some(LimitOption(core.state.settings.records.attachment_limit)),
+            SettingsMessage::AttachmentLimit,
+        )
+        .text_size(14)
+        .padding(4)
+        .style(zebra_ui::styles::pick_list::primary_field);
+
+        Row::new()
+            .spacing(ITEM_PADDING)
+            .push(description)
+            .push(limit_pick_list)
+            .align_items(iced::Alignment::Center)
+    }
+
+    fn view_agent<'a>(&self, core: &Core) -> Column<'a, SettingsMessage> {
+        let settings = &core.state.settings.agent;
+        let description = Text::new(t!("agent_description"))
+            .size(14)
+            .style(zebra_ui::styles::text::muted)
+            .width(Length::Fill);
+        let enabled = Checkbox::new(t!("agent_enable"), settings.enabled)
+            .on_toggle(SettingsMessage::ToggleAgent)
+            .text_size(14);
+        let confirm = Checkbox::new(t!("agent_confirm"), settings.confirm)
+            .on_toggle(SettingsMessage::ToggleAgentConfirm)
+            .text_size(14);
+        let col = Column::new()
+            .spacing(ITEM_PADDING)
+            .push(description)
+            .push(enabled)
+            .push(confirm);
+
+        // what goes into SSH_AUTH_SOCK.
+        #[cfg(unix)]
+        let col = match zebra_lib::agent::socket_path() {
+            Ok(path) => col.push(
+                Text::new(t!("agent_socket", path => path.display()))
+                    .size(14)
+                    .style(zebra_ui::styles::text::muted),
+            ),
+            Err(_) => col,
+        };
+
+        col
+    }
+
+    fn view_breach_db<'a>(&self, core: MutexGuard<Core>) -> Column<'a, SettingsMessage> {
+        let description = Text::new(t!("breach_db_description"))
+            .size(14)
+            .style(zebra_ui::styles::text::muted)
+            .width(Length::Fill);
+        let path = match core.get_breach_db() {
+            Some(db) => Text::new(db.get_path().display().to_string()).size(14),
+            None => Text::new(t!("breach_db_none"))
+                .size(14)
+                .style(zebra_ui::styles::text::muted),
+        };
+        let file_btn = Button::new(Text::new(t!("breach_db_pick_file")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(ITEM_PADDING)
+            .on_press(SettingsMessage::PickBreachFile);
+        let folder_btn = Button::new(Text::new(t!("breach_db_pick_folder")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(ITEM_PADDING)
+            .on_press(SettingsMessage::PickBreachFolder);
+        let clear_btn = Button::new(Text::new(t!("breach_db_clear")).size(14))
+            .style(zebra_ui::styles::button::outline_danger)
+            .padding(ITEM_PADDING)
+            .on_press_maybe(
+                core.state
+                    .settings
+                    .records
+                    .breach_db
+                    .is_some()
+                    .then_some(SettingsMessage::ClearBreachDb),
+            );
+        let buttons_row = Row::new()
+            .spacing(ITEM_PADDING)
+            .push(file_btn)
+            .push(folder_btn)
+            .push(clear_btn);
+
+        Column::new()
+            .spacing(ITEM_PADDING)
+            .push(description)
+            .push(path)
+            .push(buttons_row)
+    }
+
+    fn set_breach_db(&mut self, path: Option<std::path::PathBuf>) {
+        let result = self
+            .core
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|mut core| core.set_breach_db(path).map_err(|e| e.to_string()));
+
+        self.advanced_error = result.err().unwrap_or_default();
+    }
+
+    fn handle_report(&mut self, result: Result<VerifyReport, String>) {
+        match result {
+            Ok(report) => {
+                self.verify_report = Some(report);
+                self.advanced_error = String::new();
+            }
+            Err(e) => {
+                self.advanced_error = e;
+            }
+        }
+    }
+
+    pub fn view_organize(&self, core: MutexGuard<Core>) -> Container<SettingsMessage> {
+        let title = Text::new(&self.options_list[self.selected_index].text)
+            .size(24)
+            .horizontal_alignment(iced::alignment::Horizontal::Left)
+            .width(Length::Fill);
+
+        let tag_input = SmartInput::new()
+            .set_value(&self.new_tag)
+            .padding(8)
+            .set_font_size(14)
+            .set_placeholder(t!("placeholder_new_tag"))
+            .on_input(SettingsMessage::NewTagInput)
+            .on_submit(SettingsMessage::CreateTag);
+        let add_tag_btn = Button::new(Text::new(t!("add")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(ITEM_PADDING)
+            .on_press(SettingsMessage::CreateTag);
+        let tag_row = Row::new()
+            .spacing(ITEM_PADDING)
+            .align_items(iced::Alignment::Center)
+            .push(Container::new(tag_input).width(Length::Fill))
+            .push(add_tag_btn);
+
+        let mut parents = vec![Choice {
+            id: None,
+            label: t!("no_folder").to_string(),
+        }];
+
+        parents.extend(organize::folder_choices(core.get_folders()));
+
+        let folder_input = SmartInput::new()
+            .set_value(&self.new_folder)
+            .padding(8)
+            .set_font_size(14)
+            .set_placeholder(t!("placeholder_new_folder"))
+            .on_input(SettingsMessage::NewFolderInput)
+            .on_submit(SettingsMessage::CreateFolder);
+        let parent_pick_list = pick_list(
+            parents,
+            self.new_folder_parent.clone(),
+            SettingsMessage::NewFolderParent,
+        )
+        .placeholder(t!("folder_parent"))
+        .text_size(14)
+        .padding(4)
+        .style(zebra_ui::styles::pick_list::primary_field);
+        let add_folder_btn = Button::new(Text::new(t!("add")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(ITEM_PADDING)
+            .on_press(SettingsMessage::CreateFolder);
+        let folder_row = Row::new()
+            .spacing(ITEM_PADDING)
+            .align_items(iced::Alignment::Center)
+            .push(Container::new(folder_input).width(Length::Fill))
+            .push(parent_pick_list)
+            .push(add_folder_btn);
+
+        let tags = organize::tag_choices(core.get_tags());
+        let folders = organize::folder_choices(core.get_folders());
+        let tags_col = tags
+            .iter()
+            .fold(Column::new().spacing(ITEM_PADDING), |col, tag| {
+                let id = tag.id.unwrap_or_default();
+                let name = tag.label.trim_start_matches('#').to_string();
+
+                col.push(self.view_organize_item(OrganizeItem::Tag(id), name, &tags))
+            });
+        let folders_col =
+            folders
+                .iter()
+                .fold(Column::new().spacing(ITEM_PADDING), |col, folder| {
+                    let id = folder.id.unwrap_or_default();
+                    let name = core
+                        .get_folders()
+                        .iter()
+                        .find(|f| f.id == id)
+                        .map(|f| f.name.clone())
+                        .unwrap_or_default();
+                    let targets: Vec<Choice> = folders
+                        .iter()
+                        .filter(|choice| {
+                            choice.id.is_some_and(|target| {
+                                !core.folder_descendants(&id).contains(&target)
+                            })
+                        })
+                        .cloned()
+                        .collect();
+                    let label = Text::new(folder.label.clone())
+                        .size(13)
+                        .style(zebra_ui::styles::text::muted);
+
+                    col.push(label).push(self.view_organize_item(
+                        OrganizeItem::Folder(id),
+                        name,
+                        &targets,
+                    ))
+                });
+
+        let category_input = SmartInput::new()
+            .set_value(&self.new_category)
+            .padding(8)
+            .set_font_size(14)
+            .set_placeholder(t!("placeholder_new_category"))
+            .on_input(SettingsMessage::NewCategoryInput)
+            .on_submit(SettingsMessage::CreateCategory);
+        let icon_input = SmartInput::new()
+            .set_value(&self.new_category_icon)
+            .padding(8)
+            .set_font_size(14)
+            .set_placeholder(t!("placeholder_category_icon"))
+            .on_input(SettingsMessage::NewCategoryIconInput)
+            .on_submit(SettingsMessage::CreateCategory);
+        let add_category_btn = Button::new(Text::new(t!("add")).size(14))
+            .style(zebra_ui::styles::button::outline_primary)
+            .padding(ITEM_PADDING)
+            .on_press(SettingsMessage::CreateCategory);
+        let category_row = Row::new()
+            .spacing(ITEM_PADDING)
+            .align_items(iced::Alignment::Center)
+            .push(Container::new(category_input).width(Length::Fill))
+            .push(Container::new(icon_input).width(80))
+            .push(add_category_btn);
+        let categories_col = core.get_custom_categories().iter().fold(
+            Column::new().spacing(ITEM_PADDING),
+            |col, category| {
+                let label = format!("{} {}", category.icon, category.name);
+                let template_btn = Button::new(Text::new(t!("category_template")).size(14))
+                    .padding(0)
+                    .style(zebra_ui::styles::button::ref_primary)
+                    .on_press(SettingsMessage::EditTemplate(category.id));
+                let row = self
+                    .view_organize_item(
+                        OrganizeItem::Category(category.id),
+                        category.name.clone(),
+                        &[],
+                    )
+                    .push(template_btn);
+                let col = col.push(
+                    Text::new(label.trim().to_string())
+                        .size(13)
+                        .style(zebra_ui::styles::text::muted),
+                );
+
+                if self.editing_template != Some(category.id) {
+                    return col.push(row);
+                }
+
+                let fields = CustomFields::new()
+                    .set_padding([0.0, 0.0, 0.0, MAIN_PADDING])
+                    .on_input(SettingsMessage::TemplateInput)
+                    .set_list(&self.template);
+                let save_btn = Button::new(Text::new(t!("save_record")).size(14))
+                    .style(zebra_ui::styles::button::outline_primary)
+                    .padding(ITEM_PADDING)
+                    .on_press(SettingsMessage::SaveTemplate);
+
+                col.push(row).push(fields).push(save_btn)
+            },
+        );
+
+        let mut main_col = Column::new()
+            .padding(MAIN_PADDING)
+            .spacing(ITEM_PADDING)
+            .push(title)
+            .push(Space::new(0, ITEM_PADDING))
+            .push(Text::new(t!("tags")).size(16))
+            .push(tag_row)
+            .push(tags_col)
+            .push(Space::new(0, MAIN_PADDING))
+            .push(Text::new(t!("folders")).size(16))
+            .push(folder_row)
+            .push(folders_col)
+            .push(Space::new(0, MAIN_PADDING))
+            .push(Text::new(t!("custom_categories")).size(16))
+            .push(category_row)
+            .push(categories_col);
+
+        if !self.organize_error.is_empty() {
+            let error = Text::new(&self.organize_error)
+                .size(14)
+                .style(zebra_ui::styles::text::danger);
+
+            main_col = main_col.push(error);
+        }
+
+        Container::new(main_col)
+    }
+
+    fn view_organize_item<'a>(
+        &'a self,
+        item: OrganizeItem,
+        name: String,
+        targets: &[Choice],
+    ) -> Row<'a, SettingsMessage> {
+        let mut row = Row::new()
+            .spacing(ITEM_PADDING)
+            .align_items(iced::Alignment::Center);
+
+        if self.renaming == Some(item) {
+            let input = SmartInput::new()
+                .set_value(&self.rename_value)
+                .padding(5)
+                .set_font_size(14)
+                .on_input(SettingsMessage::RenameInput)
+                .on_submit(SettingsMessage::SaveRename);
+            let save_btn = Button::new(Text::new(t!("save_record")).size(14))
+                .padding(0)
+                .style(zebra_ui::styles::button::ref_primary)
+                .on_press(SettingsMessage::SaveRename);
+
+            return row
+                .push(Container::new(input).width(Length::Fill))
+                .push(save_btn);
+        }
+
+        let targets: Vec<Choice> = targets
+            .iter()
+            .filter(|choice| match item {
+                OrganizeItem::Tag(id) | OrganizeItem::Folder(id) | OrganizeItem::Category(id) => {
+                    choice.id != Some(id)
+                }
+            })
+            .cloned()
+            .collect();
+        let merge_pick_list = pick_list(targets, None::<Choice>, move |choice| {
+            SettingsMessage::Merge(item, choice)
+        })
+        .placeholder(t!("merge_into"))
+        .text_size(13)
+        .padding(4)
+        .style(zebra_ui::styles::pick_list::primary_field);
+        let rename_btn = Button::new(Text::new(t!("rename")).size(14))
+            .padding(0)
+            .style(zebra_ui::styles::button::ref_primary)
+            .on_press(SettingsMessage::StartRename(item, name.clone()));
+        let delete_btn = Button::new(Text::new(t!("remove")).size(14))
+            .padding(0)
+            .style(zebra_ui::styles::button::ref_danger)
+            .on_press(SettingsMessage::Delete(item));
+
+        row = row.push(Text::new(name).size(14).width(Length::Fill));
+
+        // categories can't be merged, their templates differ.
+        if !matches!(item, OrganizeItem::Category(_)) {
+            row = row.push(merge_pick_list);
+        }
+
+        row = row.push(rename_btn).push(delete_btn);
+
+        row
+    }
+
+    fn organize<F>(&mut self, action: F) -> iced::Command<GlobalMessage>
+    where
+        F: FnOnce(&mut Core) -> Result<(), ZebraErrors>,
+    {
+        let result = self
+            .core
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|mut core| action(&mut core).map_err(|e| e.to_string()));
+
+        self.organize_error = result.err().unwrap_or_default();
+
+        Command::none()
+    }
+
     pub fn view_crypto(&self) -> Container<SettingsMessage> {
         let main_col = Column::new();
 
@@ -426,3 +1370,41 @@ impl Settings {
         Container::new(main_col)
     }
 }
+
+// the checks of `Core::verify`, also shown when the vault fails to open.
+pub fn view_report<'a, M: 'a>(report: &VerifyReport) -> Column<'a, M> {
+    let checks = [
+        (t!("check_envelope"), &report.envelope),
+        (t!("check_hashsum"), &report.hashsum),
+        (t!("check_cipher_orders"), &report.cipher_orders),
+        (t!("check_key_store"), &report.key_store),
+        (t!("check_data_store"), &report.data_store),
+    ];
+    let mut col = Column::new().spacing(ITEM_PADDING);
+
+    for (name, check) in checks {
+        let status = match check {
+            Check::Passed => Text::new(t!("check_passed")).size(14),
+            Check::Failed(e) => Text::new(e.to_string())
+                .size(14)
+                .style(zebra_ui::styles::text::danger),
+            Check::Skipped => Text::new(t!("check_skipped"))
+                .size(14)
+                .style(zebra_ui::styles::text::muted),
+        };
+        let row = Row::new()
+            .push(Text::new(name).size(14).width(200))
+            .push(status);
+
+        col = col.push(row);
+    }
+
+    let records = Row::new()
+        .push(Text::new(t!("records")).size(14).width(200))
+        .push(Text::new(report.records.to_string()).size(14));
+    let snapshots = Row::new()
+        .push(Text::new(t!("intact_snapshots")).size(14).width(200))
+        .push(Text::new(report.intact_snapshots.to_string()).size(14));
+
+    col.push(records).push(snapshots)
+}
@@ -0,0 +1,568 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::fs;
+use std::io::{Read, Write};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use directories::ProjectDirs;
+use rsa::pkcs1v15;
+use rsa::sha2::Sha256;
+use rsa::signature::{SignatureEncoding, Signer};
+use ssh_key::{HashAlg, PrivateKey};
+
+use crate::config::app::{
+    AGENT_SOCKET_ENV, AGENT_SOCKET_NAME, APPLICATION, ORGANIZATION, QUALIFIER,
+};
+use crate::core::record::{Categories, Uuid};
+use crate::core::Core;
+use crate::errors::ZebraErrors;
+
+// the part of draft-miller-ssh-agent a client needs to log in.
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+const SSH_AGENT_RSA_SHA2_256: u32 = 2;
+const SSH_AGENT_RSA_SHA2_512: u32 = 4;
+
+// OpenSSH refuses longer messages as well.
+const MAX_MESSAGE: usize = 256 * 1024;
+// an unanswered prompt is a no.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+// a signature waiting for the user when `AgentSettings::confirm` is on, `reply` takes the answer.
+#[derive(Debug)]
+pub struct ConfirmRequest {
+    pub id: Uuid,
+    pub name: String,
+    pub fingerprint: String,
+    pub reply: Sender<bool>,
+}
+
+#[derive(Debug)]
+pub struct Agent {
+    path: PathBuf,
+    running: Arc<AtomicBool>,
+}
+
+impl Agent {
+    // listens on `path` until `stop`, only the owner of the socket can talk to it.
+    pub fn start(
+        core: Arc<Mutex<Core>>,
+        path: &Path,
+        confirm: Option<Sender<ConfirmRequest>>,
+    ) -> Result<Self, ZebraErrors> {
+        private_dir(path.parent().ok_or(ZebraErrors::AgentSocketError)?)?;
+
+        // a socket left behind by a crashed app, anything else at the path is not ours to remove.
+        if let Ok(metadata) = fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return Err(ZebraErrors::AgentSocketError);
+            }
+
+            fs::remove_file(path).or(Err(ZebraErrors::AgentSocketError))?;
+        }
+
+        let listener = UnixListener::bind(path).or(Err(ZebraErrors::AgentSocketError))?;
+
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .or(Err(ZebraErrors::AgentSocketError))?;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = Arc::clone(&running);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if !flag.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                if let Ok(stream) = stream {
+                    let core = Arc::clone(&core);
+                    let confirm = confirm.clone();
+                    let flag = Arc::clone(&flag);
+
+                    thread::spawn(move || serve(stream, &core, confirm.as_ref(), &flag));
+                }
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            running,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn stop(&mut self) {
+        if !self.running.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        // wakes the accept loop up so it sees the flag.
+        let _ = UnixStream::connect(&self.path);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl Drop for Agent {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// what SSH_AUTH_SOCK should point at.
+pub fn socket_path() -> Result<PathBuf, ZebraErrors> {
+    if let Some(path) = std::env::var_os(AGENT_SOCKET_ENV) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let dirs = ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION)
+        .ok_or(ZebraErrors::StoragePathError)?;
+    // without XDG_RUNTIME_DIR a dir of our own in the temp dir, `start` checks it is private.
+    let dir = match dirs.runtime_dir() {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::temp_dir().join(format!("{}-{}", APPLICATION, current_uid())),
+    };
+
+    Ok(dir.join(AGENT_SOCKET_NAME))
+}
+
+// the socket is reachable only through `dir`, so it has to be ours and closed to others
+// before anything is bound in it.
+fn private_dir(dir: &Path) -> Result<(), ZebraErrors> {
+    if !dir.exists() {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .or(Err(ZebraErrors::AgentSocketError))?;
+    }
+
+    let metadata = fs::symlink_metadata(dir).or(Err(ZebraErrors::AgentSocketError))?;
+
+    if !metadata.is_dir() || metadata.uid() != current_uid() || metadata.mode() & 0o077 != 0 {
+        return Err(ZebraErrors::AgentSocketError);
+    }
+
+    Ok(())
+}
+
+fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and never fails.
+    unsafe { libc::getuid() }
+}
+
+// clients already connected are dropped once the agent stops.
+fn serve(
+    mut stream: UnixStream,
+    core: &Mutex<Core>,
+    confirm: Option<&Sender<ConfirmRequest>>,
+    running: &AtomicBool,
+) {
+    loop {
+        let mut len = [0u8; 4];
+
+        if stream.read_exact(&mut len).is_err() {
+            return;
+        }
+
+        let len = u32::from_be_bytes(len) as usize;
+
+        if len == 0 || len > MAX_MESSAGE {
+            return;
+        }
+
+        let mut message = vec![0u8; len];
+
+        if stream.read_exact(&mut message).is_err() || !running.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let response = handle(core, confirm, running, &message);
+        let mut frame = (response.len() as u32).to_be_bytes().to_vec();
+
+        frame.extend(response);
+
+        if stream.write_all(&frame).is_err() {
+            return;
+        }
+    }
+}
+
+// answers one agent message, everything but listing and signing fails.
+pub fn handle(
+    core: &Mutex<Core>,
+    confirm: Option<&Sender<ConfirmRequest>>,
+    running: &AtomicBool,
+    message: &[u8],
+) -> Vec<u8> {
+    let (kind, body) = match message.split_first() {
+        Some(split) if running.load(Ordering::SeqCst) => split,
+        _ => return vec![SSH_AGENT_FAILURE],
+    };
+    let response = match *kind {
+        SSH_AGENTC_REQUEST_IDENTITIES => identities(core),
+        SSH_AGENTC_SIGN_REQUEST => sign(core, confirm, running, body),
+        _ => None,
+    };
+
+    response.unwrap_or_else(|| vec![SSH_AGENT_FAILURE])
+}
+
+// the usable keys of the vault and whether their record is guarded, nothing while it is locked.
+fn keys(core: &Mutex<Core>) -> Vec<(Uuid, String, PrivateKey, bool)> {
+    let core = match core.lock() {
+        Ok(core) => core,
+        Err(_) => return Vec::new(),
+    };
+
+    if !core.is_unlock() {
+        return Vec::new();
+    }
+
+    core.data
+        .iter()
+        .filter(|record| matches!(record, Categories::SshKey(_)))
+        .map(|record| record.get_value())
+        .filter_map(|elem| {
+            let key = elem.ssh_private_key().ok()?;
+
+            (!key.is_encrypted()).then(|| (elem.id, elem.name.clone(), key, elem.reprompt))
+        })
+        .collect()
+}
+
+fn identities(core: &Mutex<Core>) -> Option<Vec<u8>> {
+    let keys = keys(core);
+    let mut response = vec![SSH_AGENT_IDENTITIES_ANSWER];
+
+    response.extend((keys.len() as u32).to_be_bytes());
+
+    for (_, name, key, _) in keys {
+        let blob = key.public_key().to_bytes().ok()?;
+
+        put_string(&mut response, &blob);
+        put_string(&mut response, name.as_bytes());
+    }
+
+    Some(response)
+}
+
+fn sign(
+    core: &Mutex<Core>,
+    confirm: Option<&Sender<ConfirmRequest>>,
+    running: &AtomicBool,
+    body: &[u8],
+) -> Option<Vec<u8>> {
+    let mut reader = body;
+    let blob = get_string(&mut reader)?;
+    let data = get_string(&mut reader)?;
+    let flags = get_u32(&mut reader).unwrap_or(0);
+    // the core is not held while the user makes up their mind.
+    let (id, name, key, guarded) = keys(core)
+        .into_iter()
+        .find(|(_, _, key, _)| key.public_key().to_bytes().ok().as_deref() == Some(blob))?;
+    // a guarded record is never used without asking.
+    let needs_confirm = guarded
+        || core
+            .lock()
+            .map(|core| core.state.settings.agent.confirm)
+            .unwrap_or(true);
+
+    if needs_confirm {
+        // nobody to ask means no.
+        let confirm = confirm?;
+        let (reply, answer) = mpsc::channel();
+        let request = ConfirmRequest {
+            id,
+            name,
+            fingerprint: key.fingerprint(HashAlg::Sha256).to_string(),
+            reply,
+        };
+
+        confirm.send(request).ok()?;
+
+        // the agent may have been turned off while the prompt was open.
+        if !answer.recv_timeout(CONFIRM_TIMEOUT).unwrap_or(false) || !running.load(Ordering::SeqCst)
+        {
+            return None;
+        }
+    }
+
+    let (algorithm, signature) = match key.key_data().rsa() {
+        // ssh-key only signs with sha-512, the old sha-1 one is not offered at all.
+        Some(rsa) if flags & SSH_AGENT_RSA_SHA2_512 == 0 => {
+            if flags & SSH_AGENT_RSA_SHA2_256 == 0 {
+                return None;
+            }
+
+            let signer = pkcs1v15::SigningKey::<Sha256>::try_from(rsa).ok()?;
+            let signature = signer.try_sign(data).ok()?;
+
+            ("rsa-sha2-256".to_string(), signature.to_vec())
+        }
+        _ => {
+            let signature: ssh_key::Signature = key.try_sign(data).ok()?;
+
+            (
+                signature.algorithm().as_str().to_string(),
+                signature.as_bytes().to_vec(),
+            )
+        }
+    };
+    let mut encoded = Vec::new();
+    let mut response = vec![SSH_AGENT_SIGN_RESPONSE];
+
+    put_string(&mut encoded, algorithm.as_bytes());
+    put_string(&mut encoded, &signature);
+    put_string(&mut response, &encoded);
+
+    Some(response)
+}
+
+fn put_string(buf: &mut Vec<u8>, bytes: &[u8]) {
+    buf.extend((bytes.len() as u32).to_be_bytes());
+    buf.extend(bytes);
+}
+
+fn get_u32(reader: &mut &[u8]) -> Option<u32> {
+    if reader.len() < 4 {
+        return None;
+    }
+
+    let (head, rest) = reader.split_at(4);
+
+    *reader = rest;
+
+    Some(u32::from_be_bytes([head[0], head[1], head[2], head[3]]))
+}
+
+fn get_string<'a>(reader: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = get_u32(reader)? as usize;
+
+    if reader.len() < len {
+        return None;
+    }
+
+    let (head, rest) = reader.split_at(len);
+
+    *reader = rest;
+
+    Some(head)
+}
+
+impl Core {
+    pub fn set_agent(&mut self, enabled: bool, confirm: bool) -> Result<(), ZebraErrors> {
+        self.state.settings.agent.enabled = enabled;
+        self.state.settings.agent.confirm = confirm;
+        self.state_update()
+    }
+}
+
+#[cfg(test)]
+mod agent_tests {
+    use super::*;
+    use crate::core::ssh::SshKeyType;
+    use crate::core::test_core;
+    use rsa::signature::Verifier;
+
+    fn vault() -> Mutex<Core> {
+        let mut core = test_core();
+
+        core.add_element(Categories::generate_ssh_key(SshKeyType::Ed25519, "laptop").unwrap())
+            .unwrap();
+
+        Mutex::new(core)
+    }
+
+    fn sign_request(blob: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut message = vec![SSH_AGENTC_SIGN_REQUEST];
+
+        put_string(&mut message, blob);
+        put_string(&mut message, data);
+        message.extend(0u32.to_be_bytes());
+
+        message
+    }
+
+    #[test]
+    fn test_identities_and_sign() {
+        let core = vault();
+        let running = AtomicBool::new(true);
+        let response = handle(&core, None, &running, &[SSH_AGENTC_REQUEST_IDENTITIES]);
+        let mut reader = &response[1..];
+
+        assert_eq!(response[0], SSH_AGENT_IDENTITIES_ANSWER);
+        assert_eq!(get_u32(&mut reader), Some(1));
+
+        let blob = get_string(&mut reader).unwrap().to_vec();
+
+        assert_eq!(get_string(&mut reader), Some("laptop".as_bytes()));
+
+        let response = handle(&core, None, &running, &sign_request(&blob, b"session"));
+        let mut reader = &response[1..];
+        let mut signature = get_string(&mut reader).unwrap();
+        let algorithm = get_string(&mut signature).unwrap();
+        let bytes = get_string(&mut signature).unwrap();
+        let signature =
+            ssh_key::Signature::new(ssh_key::Algorithm::Ed25519, bytes.to_vec()).unwrap();
+        let public = ssh_key::PublicKey::from_bytes(&blob).unwrap();
+
+        assert_eq!(response[0], SSH_AGENT_SIGN_RESPONSE);
+        assert_eq!(algorithm, b"ssh-ed25519");
+        assert!(public.key_data().verify(b"session", &signature).is_ok());
+
+        // an unknown key and a garbled message.
+        assert_eq!(
+            handle(&core, None, &running, &sign_request(b"nope", b"session")),
+            vec![SSH_AGENT_FAILURE]
+        );
+        assert_eq!(
+            handle(&core, None, &running, &[SSH_AGENTC_SIGN_REQUEST, 0, 0]),
+            vec![SSH_AGENT_FAILURE]
+        );
+
+        // a denied prompt is a failure, the request names the record.
+        core.lock().unwrap().set_agent(true, true).unwrap();
+
+        assert_eq!(
+            handle(&core, None, &running, &sign_request(&blob, b"session")),
+            vec![SSH_AGENT_FAILURE]
+        );
+
+        let (tx, rx) = mpsc::channel::<ConfirmRequest>();
+        let denier = thread::spawn(move || {
+            let request = rx.recv().unwrap();
+
+            request.reply.send(false).unwrap();
+            request.name
+        });
+
+        assert_eq!(
+            handle(&core, Some(&tx), &running, &sign_request(&blob, b"session")),
+            vec![SSH_AGENT_FAILURE]
+        );
+        assert_eq!(denier.join().unwrap(), "laptop");
+
+        // a guarded key asks even with the prompts turned off.
+        let mut guarded = core.lock().unwrap().data[0].get_value().clone();
+        let id = guarded.id;
+
+        guarded.reprompt = true;
+        core.lock().unwrap().set_agent(true, false).unwrap();
+        core.lock().unwrap().update_element(&id, guarded).unwrap();
+
+        assert_eq!(
+            handle(&core, None, &running, &sign_request(&blob, b"session")),
+            vec![SSH_AGENT_FAILURE]
+        );
+
+        let (tx, rx) = mpsc::channel::<ConfirmRequest>();
+        let approver = thread::spawn(move || {
+            let request = rx.recv().unwrap();
+
+            request.reply.send(true).unwrap();
+        });
+        let response = handle(&core, Some(&tx), &running, &sign_request(&blob, b"session"));
+
+        approver.join().unwrap();
+
+        assert_eq!(response[0], SSH_AGENT_SIGN_RESPONSE);
+
+        // a stopped agent answers nothing.
+        running.store(false, Ordering::SeqCst);
+
+        assert_eq!(
+            handle(&core, None, &running, &[SSH_AGENTC_REQUEST_IDENTITIES]),
+            vec![SSH_AGENT_FAILURE]
+        );
+
+        running.store(true, Ordering::SeqCst);
+
+        // a locked vault has no keys to give.
+        core.lock().unwrap().lock();
+
+        assert_eq!(
+            handle(&core, None, &running, &[SSH_AGENTC_REQUEST_IDENTITIES]),
+            vec![SSH_AGENT_IDENTITIES_ANSWER, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            handle(&core, None, &running, &sign_request(&blob, b"session")),
+            vec![SSH_AGENT_FAILURE]
+        );
+    }
+
+    #[test]
+    fn test_socket() {
+        let core = Arc::new(vault());
+        let dir = std::env::temp_dir().join(format!("zebra_agent_{}", std::process::id()));
+        let path = dir.join(AGENT_SOCKET_NAME);
+
+        // others could reach the socket through a dir open to them.
+        fs::create_dir(&dir).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(matches!(
+            Agent::start(Arc::clone(&core), &path, None),
+            Err(ZebraErrors::AgentSocketError)
+        ));
+        assert!(!path.exists());
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700)).unwrap();
+
+        // a file which is not a socket is left alone.
+        fs::write(&path, b"id_ed25519").unwrap();
+
+        assert!(matches!(
+            Agent::start(Arc::clone(&core), &path, None),
+            Err(ZebraErrors::AgentSocketError)
+        ));
+        assert_eq!(fs::read(&path).unwrap(), b"id_ed25519");
+
+        fs::remove_file(&path).unwrap();
+
+        let mut agent = Agent::start(Arc::clone(&core), &path, None).unwrap();
+        let mut stream = UnixStream::connect(agent.path()).unwrap();
+        let mut len = [0u8; 4];
+
+        stream
+            .write_all(&[0, 0, 0, 1, SSH_AGENTC_REQUEST_IDENTITIES])
+            .unwrap();
+        stream.read_exact(&mut len).unwrap();
+
+        let mut response = vec![0u8; u32::from_be_bytes(len) as usize];
+
+        stream.read_exact(&mut response).unwrap();
+
+        assert_eq!(response[0], SSH_AGENT_IDENTITIES_ANSWER);
+        assert_eq!(response[1..5], [0, 0, 0, 1]);
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+
+        agent.stop();
+
+        assert!(!path.exists());
+
+        // the client connected before the stop is hung up on.
+        let _ = stream.write_all(&[0, 0, 0, 1, SSH_AGENTC_REQUEST_IDENTITIES]);
+
+        assert!(stream.read_exact(&mut len).is_err());
+
+        fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -5,3 +5,17 @@
 pub const QUALIFIER: &str = "com.zebra";
 pub const ORGANIZATION: &str = "Zebra Corp";
 pub const APPLICATION: &str = "Zebra App";
+
+pub const VAULT_PATH_ENV: &str = "ZEBRA_VAULT_PATH";
+pub const PORTABLE_ENV: &str = "ZEBRA_PORTABLE";
+pub const VAULT_PATH_ARG: &str = "--vault";
+pub const PORTABLE_ARG: &str = "--portable";
+// vault dir created next to the executable in portable mode, or inside a picked folder.
+pub const VAULT_DIR_NAME: &str = "zebra_vault";
+// remembers a moved vault for the default location.
+pub const VAULT_LOCATION_FILE: &str = "vault_location";
+// a local Have I Been Pwned dump, a text file, a compact file or a folder of range files.
+pub const BREACH_DB_ENV: &str = "ZEBRA_HIBP_PATH";
+// where the ssh agent listens, by default a socket in the runtime dir.
+pub const AGENT_SOCKET_ENV: &str = "ZEBRA_AGENT_SOCK";
+pub const AGENT_SOCKET_NAME: &str = "agent.sock";
@@ -0,0 +1,158 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+
+use super::app::{
+    APPLICATION, ORGANIZATION, PORTABLE_ARG, PORTABLE_ENV, QUALIFIER, VAULT_DIR_NAME,
+    VAULT_LOCATION_FILE, VAULT_PATH_ARG, VAULT_PATH_ENV,
+};
+use crate::errors::ZebraErrors;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum VaultLocation {
+    // ProjectDirs data dir, or the place the vault was moved to.
+    #[default]
+    Default,
+    Custom(PathBuf),
+    // data lives next to the executable.
+    Portable,
+}
+
+impl VaultLocation {
+    // command-line arguments win over environment variables.
+    pub fn from_args<I>(args: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if arg == PORTABLE_ARG {
+                return VaultLocation::Portable;
+            }
+
+            if arg == VAULT_PATH_ARG {
+                if let Some(path) = args.next() {
+                    return VaultLocation::Custom(PathBuf::from(path));
+                }
+            } else if let Some(path) = arg.strip_prefix(&format!("{}=", VAULT_PATH_ARG)) {
+                return VaultLocation::Custom(PathBuf::from(path));
+            }
+        }
+
+        VaultLocation::from_env()
+    }
+
+    pub fn from_env() -> Self {
+        if let Some(path) = std::env::var_os(VAULT_PATH_ENV).filter(|p| !p.is_empty()) {
+            return VaultLocation::Custom(PathBuf::from(path));
+        }
+
+        match std::env::var(PORTABLE_ENV) {
+            Ok(v) if v == "1" || v.eq_ignore_ascii_case("true") => VaultLocation::Portable,
+            _ => VaultLocation::Default,
+        }
+    }
+
+    pub fn resolve(&self) -> Result<PathBuf, ZebraErrors> {
+        match self {
+            VaultLocation::Custom(path) => Ok(path.clone()),
+            VaultLocation::Portable => {
+                let exe = std::env::current_exe().or(Err(ZebraErrors::StoragePathError))?;
+                let dir = exe.parent().ok_or(ZebraErrors::StoragePathError)?;
+
+                Ok(dir.join(VAULT_DIR_NAME))
+            }
+            VaultLocation::Default => {
+                let dirs = project_dirs()?;
+                let pointer = dirs.config_dir().join(VAULT_LOCATION_FILE);
+
+                match fs::read_to_string(pointer) {
+                    Ok(path) if !path.trim().is_empty() => Ok(PathBuf::from(path.trim())),
+                    _ => Ok(dirs.data_dir().to_path_buf()),
+                }
+            }
+        }
+    }
+
+    // only the default location needs to remember where the vault went,
+    // custom and portable paths are given again on every start.
+    pub fn remember(&self, path: &Path) -> Result<(), ZebraErrors> {
+        if *self != VaultLocation::Default {
+            return Ok(());
+        }
+
+        let dirs = project_dirs()?;
+
+        fs::create_dir_all(dirs.config_dir()).or(Err(ZebraErrors::FailToCreateFile))?;
+        fs::write(
+            dirs.config_dir().join(VAULT_LOCATION_FILE),
+            path.to_string_lossy().as_bytes(),
+        )
+        .or(Err(ZebraErrors::FailToWriteFile))?;
+
+        Ok(())
+    }
+
+    // drops the pointer left by `remember` if it leads to `path`,
+    // true when the default location is back at the data dir.
+    pub fn forget(&self, path: &Path) -> Result<bool, ZebraErrors> {
+        if *self != VaultLocation::Default {
+            return Ok(false);
+        }
+
+        let pointer = project_dirs()?.config_dir().join(VAULT_LOCATION_FILE);
+
+        match fs::read_to_string(&pointer) {
+            Ok(saved) if Path::new(saved.trim()) == path => {
+                fs::remove_file(pointer).or(Err(ZebraErrors::StorageAccessError))?;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+fn project_dirs() -> Result<ProjectDirs, ZebraErrors> {
+    ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION).ok_or(ZebraErrors::StoragePathError)
+}
+
+#[cfg(test)]
+mod location_tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args() {
+        assert_eq!(
+            VaultLocation::from_args(args(&["--vault", "/tmp/zebra"])),
+            VaultLocation::Custom(PathBuf::from("/tmp/zebra"))
+        );
+        assert_eq!(
+            VaultLocation::from_args(args(&["--vault=/tmp/zebra"])),
+            VaultLocation::Custom(PathBuf::from("/tmp/zebra"))
+        );
+        assert_eq!(
+            VaultLocation::from_args(args(&["--portable"])),
+            VaultLocation::Portable
+        );
+        assert_eq!(
+            VaultLocation::from_args(args(&["--portable"]))
+                .resolve()
+                .unwrap(),
+            std::env::current_exe()
+                .unwrap()
+                .parent()
+                .unwrap()
+                .join(VAULT_DIR_NAME)
+        );
+    }
+}
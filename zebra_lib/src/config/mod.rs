@@ -2,3 +2,4 @@
 //! -- Email: hicarus@yandex.ru
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
 pub mod app;
+pub mod location;
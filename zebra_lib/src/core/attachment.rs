@@ -0,0 +1,203 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::record::{Attachment, Uuid};
+use super::Core;
+use crate::errors::ZebraErrors;
+use crate::storage::keys::SLED_ATTACHMENT_KEY;
+use crate::utils::unix_time;
+
+fn storage_key(id: &Uuid) -> String {
+    format!("{}_{}", SLED_ATTACHMENT_KEY, id)
+}
+
+impl Attachment {
+    // "png", "jpg" and the like can be shown in the app.
+    pub fn is_image(&self) -> bool {
+        let ext = Path::new(&self.name)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        matches!(
+            ext.as_str(),
+            "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp"
+        )
+    }
+}
+
+impl Core {
+    // encrypts `bytes` with the vault keys into a blob of its own, the record only keeps a reference.
+    pub fn add_attachment(
+        &mut self,
+        id: &Uuid,
+        name: &str,
+        bytes: Vec<u8>,
+    ) -> Result<Uuid, ZebraErrors> {
+        let index = self.position(id)?;
+        let size = bytes.len() as u64;
+
+        if size > self.state.settings.records.attachment_limit {
+            return Err(ZebraErrors::AttachmentTooLarge);
+        }
+
+        let keys = self.keys.as_ref().ok_or(ZebraErrors::GuardIsNotEnable)?;
+        let cipher = keys.encrypt(bytes, &self.state.settings.cipher.cipher_orders)?;
+        let attachment = Attachment {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            size,
+            created: unix_time()?,
+        };
+
+        self.db
+            .set_raw(&storage_key(&attachment.id), cipher.as_bytes())?;
+
+        let elem = self.data[index].get_value_mut();
+
+        elem.attachments.push(attachment.clone());
+        elem.updated = attachment.created;
+        elem.device = self.device;
+        self.update()?;
+
+        Ok(attachment.id)
+    }
+
+    pub fn attach_file(&mut self, id: &Uuid, path: &Path) -> Result<Uuid, ZebraErrors> {
+        let size = fs::metadata(path)
+            .or(Err(ZebraErrors::StorageAccessError))?
+            .len();
+
+        // no need to read what can't be attached.
+        if size > self.state.settings.records.attachment_limit {
+            return Err(ZebraErrors::AttachmentTooLarge);
+        }
+
+        let bytes = fs::read(path).or(Err(ZebraErrors::StorageAccessError))?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        self.add_attachment(id, &name, bytes)
+    }
+
+    pub fn get_attachment(&self, attachment: &Uuid) -> Result<Vec<u8>, ZebraErrors> {
+        let keys = self.keys.as_ref().ok_or(ZebraErrors::GuardIsNotEnable)?;
+        let raw = self
+            .db
+            .get_raw(&storage_key(attachment))
+            .or(Err(ZebraErrors::AttachmentNotFound))?;
+        let cipher = String::from_utf8(raw).or(Err(ZebraErrors::StorageDataBroken))?;
+
+        keys.decrypt(&cipher, &self.state.settings.cipher.cipher_orders)
+    }
+
+    pub fn save_attachment(&self, attachment: &Uuid, path: &Path) -> Result<(), ZebraErrors> {
+        let bytes = self.get_attachment(attachment)?;
+
+        fs::write(path, bytes).or(Err(ZebraErrors::FailToWriteFile))
+    }
+
+    pub fn remove_attachment(&mut self, id: &Uuid, attachment: &Uuid) -> Result<(), ZebraErrors> {
+        let index = self.position(id)?;
+        let elem = self.data[index].get_value_mut();
+        let position = elem
+            .attachments
+            .iter()
+            .position(|a| &a.id == attachment)
+            .ok_or(ZebraErrors::AttachmentNotFound)?;
+
+        elem.attachments.remove(position);
+        elem.updated = unix_time()?;
+        elem.device = self.device;
+        self.update()?;
+        self.drop_orphan_attachments()
+    }
+
+    // plain size of every attachment of the vault, the trash included.
+    pub fn get_attachments_size(&self) -> u64 {
+        self.data
+            .iter()
+            .chain(self.trash.iter().map(|entry| &entry.record))
+            .flat_map(|record| record.get_value().attachments.iter())
+            .map(|attachment| attachment.size)
+            .sum()
+    }
+
+    pub fn set_attachment_limit(&mut self, limit: u64) -> Result<(), ZebraErrors> {
+        self.state.settings.records.attachment_limit = limit;
+        self.state_update()
+    }
+
+    // removes blobs no record or trash entry points to any more.
+    pub(super) fn drop_orphan_attachments(&self) -> Result<(), ZebraErrors> {
+        let used: HashSet<String> = self
+            .data
+            .iter()
+            .chain(self.trash.iter().map(|entry| &entry.record))
+            .flat_map(|record| record.get_value().attachments.iter())
+            .map(|attachment| storage_key(&attachment.id))
+            .collect();
+
+        for key in self.db.keys(SLED_ATTACHMENT_KEY)? {
+            if !used.contains(&key) {
+                self.db.remove(&key)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod attachment_tests {
+    use super::*;
+    use crate::core::record::Categories;
+    use crate::core::test_core;
+
+    #[test]
+    fn test_attachments() {
+        let mut core = test_core();
+
+        let id = core
+            .add_element(Categories::Other(Default::default()))
+            .unwrap();
+        let pdf = b"%PDF-1.7 recovery codes".to_vec();
+        let attachment = core.add_attachment(&id, "codes.pdf", pdf.clone()).unwrap();
+        let stored = core.db.get_raw(&storage_key(&attachment)).unwrap();
+
+        assert!(!stored.windows(pdf.len()).any(|w| w == pdf.as_slice()));
+        assert_eq!(core.get_attachment(&attachment).unwrap(), pdf);
+        assert_eq!(core.get_attachments_size(), pdf.len() as u64);
+
+        let elem = core.get_element(&id).unwrap().get_value();
+
+        assert_eq!(elem.attachments[0].name, "codes.pdf");
+        assert!(!elem.attachments[0].is_image());
+
+        core.set_attachment_limit(4).unwrap();
+
+        assert_eq!(
+            core.add_attachment(&id, "big.png", vec![0; 5]),
+            Err(ZebraErrors::AttachmentTooLarge)
+        );
+
+        // a record in the trash keeps its files until it is purged.
+        core.remove_element(&id).unwrap();
+
+        assert_eq!(core.get_attachment(&attachment).unwrap(), pdf);
+
+        core.empty_trash().unwrap();
+
+        assert_eq!(
+            core.get_attachment(&attachment),
+            Err(ZebraErrors::AttachmentNotFound)
+        );
+        assert!(core.db.keys(SLED_ATTACHMENT_KEY).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::collections::HashMap;
+
+use super::password_strength::password_strength;
+use super::record::{Categories, Element, Item, ItemKind, Uuid};
+use super::Core;
+use crate::errors::ZebraErrors;
+use crate::utils::unix_time;
+
+// below this `password_strength` score a password is reported as weak.
+pub const WEAK_STRENGTH: u8 = 15;
+// edits which turn one password into another one still count as "near".
+const SIMILAR_DISTANCE: usize = 2;
+const DAY: i64 = 24 * 60 * 60;
+
+// a field of a record, `field` counts `fields` and then `extra_fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldRef {
+    pub id: Uuid,
+    pub field: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeakPassword {
+    pub at: FieldRef,
+    pub strength: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreachedPassword {
+    pub at: FieldRef,
+    // times it was seen in the local breach database.
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StalePassword {
+    pub at: FieldRef,
+    pub days: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    // passwords which were looked at.
+    pub checked: usize,
+    pub weak: Vec<WeakPassword>,
+    // empty without a breach database, see `Core::breach_count`.
+    pub breached: Vec<BreachedPassword>,
+    // every group shares one password.
+    pub reused: Vec<Vec<FieldRef>>,
+    pub similar: Vec<(FieldRef, FieldRef)>,
+    pub stale: Vec<StalePassword>,
+    // logins without a one-time code.
+    pub missing_2fa: Vec<Uuid>,
+}
+
+impl AuditReport {
+    pub fn issues(&self) -> usize {
+        self.weak.len()
+            + self.breached.len()
+            + self.reused.len()
+            + self.similar.len()
+            + self.stale.len()
+            + self.missing_2fa.len()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issues() == 0
+    }
+}
+
+impl Item {
+    // card codes and seed phrases are secrets, but not passwords.
+    pub fn is_password(&self) -> bool {
+        self.kind == ItemKind::Password && (self.key.is_empty() || self.key == "password")
+    }
+}
+
+impl Element {
+    // an empty secret doesn't count as a second factor.
+    pub fn has_totp(&self) -> bool {
+        self.fields
+            .iter()
+            .chain(self.extra_fields.iter())
+            .any(|item| item.kind == ItemKind::Totp && !item.value.trim().is_empty())
+    }
+}
+
+impl Core {
+    pub fn audit(&self) -> Result<AuditReport, ZebraErrors> {
+        let now = unix_time()?;
+        let rotation_days = self.state.settings.records.rotation_days;
+        let mut report = AuditReport::default();
+        let mut passwords: Vec<(FieldRef, &str)> = Vec::new();
+
+        for record in &self.data {
+            let elem = record.get_value();
+
+            if matches!(record, Categories::Login(_)) && !elem.has_totp() {
+                report.missing_2fa.push(elem.id);
+            }
+
+            for (field, item) in elem
+                .fields
+                .iter()
+                .chain(elem.extra_fields.iter())
+                .enumerate()
+            {
+                // a shared password is checked where it is stored.
+                if !item.is_password() || item.value.is_empty() || item.reference().is_some() {
+                    continue;
+                }
+
+                let at = FieldRef { id: elem.id, field };
+                let strength = password_strength(&item.value)?;
+
+                if strength < WEAK_STRENGTH {
+                    report.weak.push(WeakPassword { at, strength });
+                }
+
+                if let Some(count) = self.breach_count(&item.value)?.filter(|count| *count > 0) {
+                    report.breached.push(BreachedPassword { at, count });
+                }
+
+                let days = ((now - item.last_changed(elem.created)).max(0) / DAY) as u32;
+
+                if rotation_days > 0 && days > rotation_days {
+                    report.stale.push(StalePassword { at, days });
+                }
+
+                passwords.push((at, &item.value));
+            }
+        }
+
+        report.checked = passwords.len();
+        report.reused = reused(&passwords);
+        report.similar = similar(&passwords);
+
+        Ok(report)
+    }
+
+    pub fn set_rotation_days(&mut self, days: u32) -> Result<(), ZebraErrors> {
+        self.state.settings.records.rotation_days = days;
+        self.state_update()
+    }
+}
+
+fn reused(passwords: &[(FieldRef, &str)]) -> Vec<Vec<FieldRef>> {
+    let mut groups: HashMap<&str, Vec<FieldRef>> = HashMap::new();
+    let mut order = Vec::new();
+
+    for (at, value) in passwords {
+        let group = groups.entry(value).or_default();
+
+        if group.is_empty() {
+            order.push(*value);
+        }
+
+        group.push(*at);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|value| groups.remove(value))
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+// "Summer2023!" and "summer2024!" differ in case and one digit.
+fn similar(passwords: &[(FieldRef, &str)]) -> Vec<(FieldRef, FieldRef)> {
+    let lowered: Vec<Vec<char>> = passwords
+        .iter()
+        .map(|(_, value)| value.to_lowercase().chars().collect())
+        .collect();
+    let mut pairs = Vec::new();
+
+    for i in 0..passwords.len() {
+        for j in i + 1..passwords.len() {
+            if passwords[i].1 == passwords[j].1 {
+                continue;
+            }
+
+            if distance(&lowered[i], &lowered[j]) <= SIMILAR_DISTANCE {
+                pairs.push((passwords[i].0, passwords[j].0));
+            }
+        }
+    }
+
+    pairs
+}
+
+// levenshtein distance, gives up early once it is over `SIMILAR_DISTANCE`.
+fn distance(a: &[char], b: &[char]) -> usize {
+    if a.len().abs_diff(b.len()) > SIMILAR_DISTANCE {
+        return SIMILAR_DISTANCE + 1;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+
+            row[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+
+        if row.iter().min().is_some_and(|min| *min > SIMILAR_DISTANCE) {
+            return SIMILAR_DISTANCE + 1;
+        }
+
+        std::mem::swap(&mut prev, &mut row);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod audit_tests {
+    use super::*;
+    use crate::core::record::HistoryEntry;
+    use crate::core::test_core;
+
+    fn password(value: &str) -> Item {
+        Item {
+            key: "password".to_string(),
+            kind: ItemKind::Password,
+            value: value.to_string(),
+            hide: true,
+            ..Default::default()
+        }
+    }
+
+    fn login(fields: Vec<Item>) -> Categories {
+        Categories::Login(Element {
+            fields,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_distance() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+
+        assert_eq!(distance(&chars("summer2023!"), &chars("summer2024!")), 1);
+        assert_eq!(distance(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(distance(&chars("abc"), &chars("abcdefgh")), 3);
+    }
+
+    #[test]
+    fn test_audit() {
+        let mut core = test_core();
+
+        let strong = "bXdN*6k4d55LdyT5ULj8awiW7^M9Z9CDhzx2";
+        let weak = core.add_element(login(vec![password("qwerty")])).unwrap();
+        let first = core.add_element(login(vec![password(strong)])).unwrap();
+        let second = core.add_element(login(vec![password(strong)])).unwrap();
+        let near = core
+            .add_element(login(vec![password(&strong.to_lowercase())]))
+            .unwrap();
+        let protected = core
+            .add_element(login(vec![
+                password("Oj3$kPz9!wQm#2Lx8vTn"),
+                Item {
+                    kind: ItemKind::Totp,
+                    value: "JBSWY3DPEHPK3PXP".to_string(),
+                    ..Default::default()
+                },
+            ]))
+            .unwrap();
+
+        core.add_element(Categories::CreditCard(Element {
+            fields: vec![Item {
+                key: "cvv".to_string(),
+                kind: ItemKind::Password,
+                value: "123".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }))
+        .unwrap();
+
+        let report = core.audit().unwrap();
+
+        assert_eq!(report.checked, 5);
+        assert_eq!(report.weak.len(), 1);
+        assert_eq!(report.weak[0].at, FieldRef { id: weak, field: 0 });
+        assert_eq!(
+            report.reused,
+            vec![vec![
+                FieldRef {
+                    id: first,
+                    field: 0
+                },
+                FieldRef {
+                    id: second,
+                    field: 0
+                }
+            ]]
+        );
+        assert_eq!(report.similar.len(), 2);
+        assert!(report
+            .similar
+            .iter()
+            .all(|(_, b)| *b == FieldRef { id: near, field: 0 }));
+        assert!(report.stale.is_empty());
+        assert_eq!(report.missing_2fa.len(), 4);
+        assert!(!report.missing_2fa.contains(&protected));
+
+        // a password replaced two years ago.
+        let index = core.position(&protected).unwrap();
+        let two_years = unix_time().unwrap() - 2 * 365 * DAY;
+
+        core.data[index].get_value_mut().fields[0].history = vec![HistoryEntry {
+            value: "old".to_string(),
+            changed: two_years,
+        }];
+
+        let report = core.audit().unwrap();
+
+        assert_eq!(report.stale.len(), 1);
+        assert_eq!(report.stale[0].at.id, protected);
+        assert!(report.stale[0].days >= 2 * 365);
+
+        core.set_rotation_days(0).unwrap();
+
+        assert!(core.audit().unwrap().stale.is_empty());
+    }
+}
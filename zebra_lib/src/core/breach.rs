@@ -0,0 +1,381 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+use sha1::{Digest, Sha1};
+
+use super::Core;
+use crate::config::app::BREACH_DB_ENV;
+use crate::errors::ZebraErrors;
+
+// header of the file written by `BreachDb::compact`.
+const COMPACT_MAGIC: &[u8; 8] = b"ZHIBP001";
+// sha-1 digest and a little endian u32 count.
+const COMPACT_RECORD: usize = 24;
+// range files are named after the first five hex digits of the hash.
+const RANGE_PREFIX: usize = 5;
+
+#[derive(Debug)]
+enum Source {
+    // "HASH:COUNT" lines ordered by hash, as the HIBP downloader writes them.
+    Text(Mmap),
+    Compact(Mmap),
+    // a folder of "ABCDE.txt" range files with "SUFFIX:COUNT" lines.
+    Ranges(PathBuf),
+}
+
+// a local copy of Have I Been Pwned, nothing is sent over the network.
+#[derive(Debug)]
+pub struct BreachDb {
+    path: PathBuf,
+    source: Source,
+}
+
+impl BreachDb {
+    pub fn open(path: &Path) -> Result<Self, ZebraErrors> {
+        if path.is_dir() {
+            return Ok(Self {
+                path: path.to_path_buf(),
+                source: Source::Ranges(path.to_path_buf()),
+            });
+        }
+
+        let map = map_file(path)?;
+        let source = if map.starts_with(COMPACT_MAGIC) {
+            if (map.len() - COMPACT_MAGIC.len()) % COMPACT_RECORD != 0 {
+                return Err(ZebraErrors::BreachDbBroken);
+            }
+
+            Source::Compact(map)
+        } else {
+            Source::Text(map)
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    // how many times the password showed up in breaches, zero when never.
+    pub fn count(&self, password: &str) -> Result<u32, ZebraErrors> {
+        let digest: [u8; 20] = Sha1::digest(password.as_bytes()).into();
+        let hash = hex::encode_upper(digest);
+
+        match &self.source {
+            Source::Text(map) => search_lines(map, hash.as_bytes()),
+            Source::Compact(map) => Ok(search_compact(&map[COMPACT_MAGIC.len()..], &digest)),
+            Source::Ranges(dir) => {
+                let (prefix, suffix) = hash.split_at(RANGE_PREFIX);
+                let file = dir.join(format!("{}.txt", prefix));
+
+                if !file.exists() {
+                    return Ok(0);
+                }
+
+                search_lines(&map_file(&file)?, suffix.as_bytes())
+            }
+        }
+    }
+
+    // turns a sorted text dump into fixed size records, about half the size.
+    pub fn compact(src: &Path, dst: &Path) -> Result<u64, ZebraErrors> {
+        let reader = BufReader::new(File::open(src).or(Err(ZebraErrors::BreachDbNotFound))?);
+        let mut writer = BufWriter::new(File::create(dst).or(Err(ZebraErrors::FailToCreateFile))?);
+        let mut last: Option<[u8; 20]> = None;
+        let mut records = 0;
+
+        writer
+            .write_all(COMPACT_MAGIC)
+            .or(Err(ZebraErrors::FailToWriteFile))?;
+
+        for line in reader.lines() {
+            let line = line.or(Err(ZebraErrors::BreachDbBroken))?;
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (hash, count) = parse_line(line.as_bytes()).ok_or(ZebraErrors::BreachDbBroken)?;
+            let mut digest = [0u8; 20];
+
+            hex::decode_to_slice(hash, &mut digest).or(Err(ZebraErrors::BreachDbBroken))?;
+
+            // binary search needs the order the dump promises.
+            if last.is_some_and(|last| last >= digest) {
+                return Err(ZebraErrors::BreachDbBroken);
+            }
+
+            writer
+                .write_all(&digest)
+                .and_then(|_| writer.write_all(&count.to_le_bytes()))
+                .or(Err(ZebraErrors::FailToWriteFile))?;
+            last = Some(digest);
+            records += 1;
+        }
+
+        writer.flush().or(Err(ZebraErrors::FailToWriteFile))?;
+
+        Ok(records)
+    }
+}
+
+impl Core {
+    // the path from the settings wins over `ZEBRA_HIBP_PATH`.
+    pub fn load_breach_db(&mut self) {
+        let path = self
+            .state
+            .settings
+            .records
+            .breach_db
+            .clone()
+            .or_else(|| std::env::var_os(BREACH_DB_ENV).map(PathBuf::from));
+
+        self.breach = path.and_then(|path| BreachDb::open(&path).ok());
+    }
+
+    pub fn get_breach_db(&self) -> Option<&BreachDb> {
+        self.breach.as_ref()
+    }
+
+    pub fn set_breach_db(&mut self, path: Option<PathBuf>) -> Result<(), ZebraErrors> {
+        self.breach = match &path {
+            Some(path) => Some(BreachDb::open(path)?),
+            None => None,
+        };
+        self.state.settings.records.breach_db = path;
+        self.state_update()
+    }
+
+    // `None` when there is no breach database to ask.
+    pub fn breach_count(&self, password: &str) -> Result<Option<u32>, ZebraErrors> {
+        self.breach
+            .as_ref()
+            .map(|db| db.count(password))
+            .transpose()
+    }
+}
+
+fn map_file(path: &Path) -> Result<Mmap, ZebraErrors> {
+    let file = File::open(path).or(Err(ZebraErrors::BreachDbNotFound))?;
+
+    // the file is only read, a dump changed under us gives wrong answers, not ub in safe code.
+    unsafe { Mmap::map(&file) }.or(Err(ZebraErrors::BreachDbNotFound))
+}
+
+fn parse_line(line: &[u8]) -> Option<(&[u8], u32)> {
+    let split = line.iter().position(|b| *b == b':')?;
+    let count = std::str::from_utf8(&line[split + 1..]).ok()?;
+
+    Some((&line[..split], count.trim().parse().ok()?))
+}
+
+// binary search over lines of different length, every probe snaps to its line.
+fn search_lines(data: &[u8], key: &[u8]) -> Result<u32, ZebraErrors> {
+    let (mut lo, mut hi) = (0, data.len());
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let start = data[lo..mid]
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(lo, |i| lo + i + 1);
+        let mut end = data[mid..hi]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(hi, |i| mid + i);
+        let mut line = data[start..end].trim_ascii();
+
+        // a blank line says nothing about the order, the next line with a hash does.
+        while line.is_empty() && end < hi {
+            let next = end + 1;
+
+            end = data[next..hi]
+                .iter()
+                .position(|b| *b == b'\n')
+                .map_or(hi, |i| next + i);
+            line = data[next..end].trim_ascii();
+        }
+
+        if line.is_empty() {
+            hi = start;
+            continue;
+        }
+
+        let (hash, count) = parse_line(line).ok_or(ZebraErrors::BreachDbBroken)?;
+
+        match compare_hex(hash, key) {
+            Ordering::Equal => return Ok(count),
+            Ordering::Less => lo = end + 1,
+            Ordering::Greater => hi = start,
+        }
+    }
+
+    Ok(0)
+}
+
+fn search_compact(records: &[u8], digest: &[u8; 20]) -> u32 {
+    let (mut lo, mut hi) = (0, records.len() / COMPACT_RECORD);
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let record = &records[mid * COMPACT_RECORD..(mid + 1) * COMPACT_RECORD];
+
+        match record[..20].cmp(digest) {
+            Ordering::Equal => {
+                let mut count = [0u8; 4];
+
+                count.copy_from_slice(&record[20..]);
+
+                return u32::from_le_bytes(count);
+            }
+            Ordering::Less => lo = mid + 1,
+            Ordering::Greater => hi = mid,
+        }
+    }
+
+    0
+}
+
+fn compare_hex(a: &[u8], b: &[u8]) -> Ordering {
+    a.iter()
+        .map(u8::to_ascii_uppercase)
+        .cmp(b.iter().map(u8::to_ascii_uppercase))
+}
+
+#[cfg(test)]
+mod breach_tests {
+    use super::*;
+
+    fn sha1_hex(password: &str) -> String {
+        hex::encode_upper(Sha1::digest(password.as_bytes()))
+    }
+
+    fn dump(dir: &Path) -> PathBuf {
+        let mut lines: Vec<String> = ["password", "123456", "qwerty", "letmein", "dragon"]
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("{}:{}", sha1_hex(p), (i + 1) * 100))
+            .collect();
+
+        lines.sort();
+
+        let path = dir.join("pwned.txt");
+
+        std::fs::write(&path, lines.join("\r\n") + "\r\n").unwrap();
+
+        path
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("zebra_breach_{}_{}", name, std::process::id()));
+
+        std::fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn test_text_and_compact() {
+        let dir = temp_dir("text");
+        let text = dump(&dir);
+        let compact = dir.join("pwned.bin");
+
+        assert_eq!(BreachDb::compact(&text, &compact).unwrap(), 5);
+
+        for path in [&text, &compact] {
+            let db = BreachDb::open(path).unwrap();
+
+            assert_eq!(db.count("password").unwrap(), 100);
+            assert_eq!(db.count("dragon").unwrap(), 500);
+            assert_eq!(db.count("correct horse battery staple").unwrap(), 0);
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let dir = temp_dir("blank");
+        let mut lines: Vec<String> = (0..16)
+            .map(|i| format!("{}:{}", sha1_hex(&i.to_string()), i + 1))
+            .collect();
+
+        lines.sort();
+
+        let counts: Vec<u32> = lines
+            .iter()
+            .map(|line| line[41..].parse().unwrap())
+            .collect();
+
+        // wide enough for the first probe to land on it.
+        lines.insert(8, " ".repeat(2048));
+        lines.insert(3, String::new());
+
+        let path = dir.join("pwned.txt");
+
+        std::fs::write(&path, lines.join("\n") + "\n\n").unwrap();
+
+        let db = BreachDb::open(&path).unwrap();
+
+        for (i, count) in counts.iter().enumerate() {
+            let password = (count - 1).to_string();
+
+            assert_eq!(db.count(&password).unwrap(), *count, "line {}", i);
+        }
+
+        assert_eq!(db.count("correct horse battery staple").unwrap(), 0);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_ranges() {
+        let dir = temp_dir("ranges");
+        let hash = sha1_hex("qwerty");
+        let (prefix, suffix) = hash.split_at(RANGE_PREFIX);
+
+        std::fs::write(
+            dir.join(format!("{}.txt", prefix)),
+            format!("00000000000000000000000000000000000:1\n{}:42\n", suffix),
+        )
+        .unwrap();
+
+        let db = BreachDb::open(&dir).unwrap();
+
+        assert_eq!(db.count("qwerty").unwrap(), 42);
+        assert_eq!(db.count("letmein").unwrap(), 0);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_compact_rejects_unsorted() {
+        let dir = temp_dir("unsorted");
+        let src = dir.join("bad.txt");
+
+        std::fs::write(
+            &src,
+            format!("{}:1\n{}:1\n", "F".repeat(40), "0".repeat(40)),
+        )
+        .unwrap();
+
+        assert_eq!(
+            BreachDb::compact(&src, &dir.join("bad.bin")),
+            Err(ZebraErrors::BreachDbBroken)
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
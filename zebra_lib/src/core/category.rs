@@ -0,0 +1,195 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use serde::{Deserialize, Serialize};
+
+use super::organize::valid_name;
+use super::record::{Categories, Element, Item, Uuid};
+use super::Core;
+use crate::errors::ZebraErrors;
+
+// a record type made by the user, its records are `Categories::Custom`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct CustomCategory {
+    pub id: Uuid,
+    pub name: String,
+    // copied into `Element::icon` of new records.
+    pub icon: String,
+    // fields a new record starts with, their values are the defaults.
+    pub template: Vec<Item>,
+}
+
+impl CustomCategory {
+    pub fn new_record(&self) -> Categories {
+        let fields = self
+            .template
+            .iter()
+            .map(|item| Item {
+                history: Vec::new(),
+                ..item.clone()
+            })
+            .collect();
+
+        Categories::Custom(
+            self.id,
+            Element {
+                icon: self.icon.clone(),
+                fields,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Categories {
+    pub fn custom_id(&self) -> Option<&Uuid> {
+        match self {
+            Categories::Custom(id, _) => Some(id),
+            _ => None,
+        }
+    }
+}
+
+impl Core {
+    pub fn get_custom_categories(&self) -> &[CustomCategory] {
+        &self.categories
+    }
+
+    pub fn get_custom_category(&self, id: &Uuid) -> Option<&CustomCategory> {
+        self.categories.iter().find(|category| category.id == *id)
+    }
+
+    pub fn create_custom_category(
+        &mut self,
+        name: &str,
+        icon: &str,
+        template: Vec<Item>,
+    ) -> Result<Uuid, ZebraErrors> {
+        let name = valid_name(name)?;
+        let id = Uuid::new_v4();
+
+        self.categories.push(CustomCategory {
+            id,
+            name,
+            icon: icon.trim().to_string(),
+            template,
+        });
+        self.update()?;
+
+        Ok(id)
+    }
+
+    // records made before keep their fields, only new ones get the template.
+    pub fn update_custom_category(
+        &mut self,
+        id: &Uuid,
+        name: &str,
+        icon: &str,
+        template: Vec<Item>,
+    ) -> Result<(), ZebraErrors> {
+        let name = valid_name(name)?;
+        let index = self.category_position(id)?;
+        let category = &mut self.categories[index];
+
+        category.name = name;
+        category.icon = icon.trim().to_string();
+        category.template = template;
+        self.update()
+    }
+
+    // records of the category become `Other` ones.
+    pub fn delete_custom_category(&mut self, id: &Uuid) -> Result<(), ZebraErrors> {
+        let index = self.category_position(id)?;
+
+        self.categories.remove(index);
+
+        for record in self.all_records_mut() {
+            if record.custom_id() == Some(id) {
+                *record = Categories::Other(record.get_value().clone());
+            }
+        }
+
+        self.update()
+    }
+
+    fn category_position(&self, id: &Uuid) -> Result<usize, ZebraErrors> {
+        self.categories
+            .iter()
+            .position(|category| category.id == *id)
+            .ok_or(ZebraErrors::CoreCategoryNotFound)
+    }
+}
+
+#[cfg(test)]
+mod category_tests {
+    use super::*;
+    use crate::core::payload::Payload;
+    use crate::core::record::ItemKind;
+    use crate::core::test_core;
+
+    #[test]
+    fn test_custom_categories() {
+        let mut core = test_core();
+
+        let template = vec![
+            Item {
+                title: "License key".to_string(),
+                hide: true,
+                copy: true,
+                ..Default::default()
+            },
+            Item {
+                title: "Seats".to_string(),
+                kind: ItemKind::Number,
+                value: "1".to_string(),
+                ..Default::default()
+            },
+        ];
+        let license = core
+            .create_custom_category(" Software license ", "🔑", template)
+            .unwrap();
+        let category = core.get_custom_category(&license).unwrap();
+        let record = category.new_record();
+
+        assert_eq!(category.name, "Software license");
+        assert_eq!(
+            core.create_custom_category("", "", vec![]),
+            Err(ZebraErrors::CoreInvalidName)
+        );
+
+        let id = core.add_element(record).unwrap();
+        let saved = core.get_element(&id).unwrap();
+
+        assert_eq!(saved.custom_id(), Some(&license));
+        assert_eq!(saved.get_value().fields[1].value, "1");
+        assert_eq!(saved.get_value().icon, "🔑");
+
+        // the registry is part of the encrypted payload.
+        core.unlock("password").unwrap();
+
+        assert_eq!(core.get_custom_categories().len(), 1);
+
+        core.delete_custom_category(&license).unwrap();
+
+        assert!(matches!(core.get_element(&id), Some(Categories::Other(_))));
+        assert_eq!(
+            core.delete_custom_category(&license),
+            Err(ZebraErrors::CoreCategoryNotFound)
+        );
+    }
+
+    #[test]
+    fn test_builtin_json_unchanged() {
+        let json = r#"{"records":[{"Login":{"icon":"","created":0,"updated":0,"favourite":false,"note":"","name":"a","fields":[],"extra_fields":[]}}]}"#;
+        let payload = Payload::from_slice(json.as_bytes()).unwrap();
+
+        assert!(matches!(payload.records[0], Categories::Login(_)));
+        assert!(payload.categories.is_empty());
+
+        let custom = Categories::Custom(Uuid::nil(), Element::default());
+        let json = serde_json::to_string(&custom).unwrap();
+
+        assert!(json.starts_with(r#"{"Custom":["00000000-0000-0000-0000-000000000000","#));
+        assert_eq!(serde_json::from_str::<Categories>(&json).unwrap(), custom);
+    }
+}
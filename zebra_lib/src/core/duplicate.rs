@@ -0,0 +1,296 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::collections::HashMap;
+
+use super::kind::url_host;
+use super::payload::TrashEntry;
+use super::record::{Element, HistoryEntry, Item, Uuid};
+use super::uri::base_domain;
+use super::Core;
+use crate::errors::ZebraErrors;
+use crate::utils::unix_time;
+
+// template fields which name the account of a record.
+const USERNAME_KEYS: [&str; 2] = ["username", "email"];
+
+impl Element {
+    // lowercased, without the extra spaces imports tend to leave.
+    fn normalized_name(&self) -> String {
+        self.name
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    }
+
+    // the base domain of the first website, the host when there is none.
+    fn normalized_site(&self) -> String {
+        self.login_uris()
+            .iter()
+            .filter_map(|uri| url_host(uri.uri.trim()))
+            .map(|host| host.trim_end_matches('.').to_lowercase())
+            .map(|host| base_domain(&host).unwrap_or(host))
+            .next()
+            .unwrap_or_default()
+    }
+
+    fn normalized_username(&self) -> String {
+        USERNAME_KEYS
+            .iter()
+            .filter_map(|key| self.fields.iter().find(|item| item.key == *key))
+            .map(|item| item.value.trim().to_lowercase())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+    }
+
+    // pulls the fields of `other` in, nothing of it gets lost.
+    fn absorb(&mut self, other: &Element, depth: usize) {
+        for theirs in other.fields.iter().chain(other.extra_fields.iter()) {
+            let ours = self
+                .fields
+                .iter_mut()
+                .chain(self.extra_fields.iter_mut())
+                .find(|item| same_field(item, theirs));
+
+            match ours {
+                Some(ours) if ours.value.is_empty() => {
+                    ours.value.clone_from(&theirs.value);
+                    merge_history(ours, &theirs.history, depth);
+                }
+                Some(ours) if ours.value == theirs.value || theirs.value.is_empty() => {
+                    merge_history(ours, &theirs.history, depth);
+                }
+                // an older secret goes into the history like a replaced one.
+                Some(ours) if ours.hide => {
+                    let replaced = [HistoryEntry {
+                        value: theirs.value.clone(),
+                        changed: other.updated,
+                    }];
+
+                    merge_history(ours, &replaced, depth);
+                    merge_history(ours, &theirs.history, depth);
+                }
+                _ => self.extra_fields.push(theirs.clone()),
+            }
+        }
+
+        for tag in &other.tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(*tag);
+            }
+        }
+
+        // the blobs stay where they are, only the references move.
+        self.attachments.extend(other.attachments.iter().cloned());
+
+        for uri in &other.uris {
+            if !self.uris.contains(uri) {
+                self.uris.push(uri.clone());
+            }
+        }
+
+        let note = other.note.trim();
+
+        if !note.is_empty() && !self.note.contains(note) {
+            self.note = if self.note.trim().is_empty() {
+                note.to_string()
+            } else {
+                format!("{}\n\n{}", self.note.trim_end(), note)
+            };
+        }
+
+        self.favourite |= other.favourite;
+        // a merged secret stays behind the master password if either copy asked for it.
+        self.reprompt |= other.reprompt;
+        self.folder = self.folder.or(other.folder);
+        self.expires = self.expires.or(other.expires);
+        self.created = self.created.min(other.created);
+        self.accessed = self.accessed.max(other.accessed);
+    }
+}
+
+impl Core {
+    // groups of records, oldest first, which look like one account:
+    // the same category, name, site and username.
+    pub fn find_duplicates(&self) -> Vec<Vec<Uuid>> {
+        let mut groups: HashMap<_, Vec<&Element>> = HashMap::new();
+        let mut order = Vec::new();
+
+        for record in &self.data {
+            let elem = record.get_value();
+            let site = elem.normalized_site();
+            let username = elem.normalized_username();
+
+            // nothing to tell the account by.
+            if site.is_empty() && username.is_empty() {
+                continue;
+            }
+
+            let key = (
+                std::mem::discriminant(record),
+                elem.normalized_name(),
+                site,
+                username,
+            );
+            let group = groups.entry(key.clone()).or_default();
+
+            if group.is_empty() {
+                order.push(key);
+            }
+
+            group.push(elem);
+        }
+
+        order
+            .into_iter()
+            .filter_map(|key| groups.remove(&key))
+            .filter(|group| group.len() > 1)
+            .map(|mut group| {
+                group.sort_by_key(|elem| elem.created);
+                group.iter().map(|elem| elem.id).collect()
+            })
+            .collect()
+    }
+
+    // `keep` takes over the fields and the history of `others`, which go to the trash.
+    pub fn merge_elements(&mut self, keep: &Uuid, others: &[Uuid]) -> Result<(), ZebraErrors> {
+        let index = self.position(keep)?;
+        let depth = self.state.settings.records.history_depth;
+        let now = unix_time()?;
+        let mut merged = self.data[index].get_value().clone();
+        let mut removed = Vec::new();
+
+        for id in others.iter().filter(|id| *id != keep) {
+            let record = self.data[self.position(id)?].clone();
+
+            merged.absorb(record.get_value(), depth);
+            removed.push(record);
+        }
+
+        merged.sync_expiry();
+        merged.updated = now;
+        merged.device = self.device;
+
+        let record = self.data[index].update_element(merged);
+
+        record.validate()?;
+        self.data[index] = record;
+        self.data
+            .retain(|record| !removed.iter().any(|r| r.get_id() == record.get_id()));
+        self.trash
+            .extend(removed.into_iter().map(|record| TrashEntry {
+                record,
+                deleted: now,
+            }));
+        self.update()
+    }
+}
+
+// template fields match by key, custom ones by title and kind.
+fn same_field(a: &Item, b: &Item) -> bool {
+    if a.key.is_empty() && b.key.is_empty() {
+        a.kind == b.kind && a.title.trim().eq_ignore_ascii_case(b.title.trim())
+    } else {
+        a.key == b.key
+    }
+}
+
+// newest first without repeats, like the history `update_element` writes.
+fn merge_history(item: &mut Item, entries: &[HistoryEntry], depth: usize) {
+    for entry in entries {
+        if entry.value != item.value && !item.history.iter().any(|h| h.value == entry.value) {
+            item.history.push(entry.clone());
+        }
+    }
+
+    item.history
+        .sort_by_key(|entry| std::cmp::Reverse(entry.changed));
+    item.history.truncate(depth);
+}
+
+#[cfg(test)]
+mod duplicate_tests {
+    use super::*;
+    use crate::core::record::{Categories, ItemKind};
+    use crate::core::test_core;
+
+    fn item(key: &str, kind: ItemKind, value: &str) -> Item {
+        Item {
+            key: key.to_string(),
+            kind,
+            value: value.to_string(),
+            hide: kind == ItemKind::Password,
+            ..Default::default()
+        }
+    }
+
+    fn login(name: &str, site: &str, username: &str, password: &str) -> Categories {
+        Categories::Login(Element {
+            name: name.to_string(),
+            fields: vec![
+                item("domain", ItemKind::Url, site),
+                item("username", ItemKind::Text, username),
+                item("password", ItemKind::Password, password),
+            ],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let mut core = test_core();
+        let first = core
+            .add_element(login("GitHub", "https://github.com", "rina", "a"))
+            .unwrap();
+        let second = core
+            .add_element(login(" github ", "gist.github.com/x", "Rina", "b"))
+            .unwrap();
+
+        core.add_element(login("GitHub", "https://github.com", "work", "c"))
+            .unwrap();
+        core.add_element(login("Notes", "", "", "d")).unwrap();
+        core.add_element(login("Notes", "", "", "e")).unwrap();
+
+        assert_eq!(core.find_duplicates(), vec![vec![first, second]]);
+    }
+
+    #[test]
+    fn test_merge_elements() {
+        let mut core = test_core();
+        let keep = core
+            .add_element(login("GitHub", "github.com", "rina", "new"))
+            .unwrap();
+        let mut other = login("GitHub", "github.com", "rina", "old");
+
+        other.get_value_mut().note = "recovery codes in the safe".to_string();
+        other.get_value_mut().extra_fields = vec![Item {
+            title: "PIN".to_string(),
+            value: "1234".to_string(),
+            ..Default::default()
+        }];
+
+        other.get_value_mut().reprompt = true;
+
+        let other = core.add_element(other).unwrap();
+
+        core.merge_elements(&keep, &[other]).unwrap();
+
+        let merged = core.get_element(&keep).unwrap().get_value();
+        let password = &merged.fields[2];
+
+        assert_eq!(core.data.len(), 1);
+        assert_eq!(core.get_trash().len(), 1);
+        assert_eq!(password.value, "new");
+        assert_eq!(password.history[0].value, "old");
+        assert_eq!(merged.extra_fields.len(), 1);
+        assert_eq!(merged.extra_fields[0].value, "1234");
+        assert_eq!(merged.note, "recovery codes in the safe");
+        assert!(merged.reprompt);
+        assert!(core.find_duplicates().is_empty());
+        assert_eq!(
+            core.merge_elements(&keep, &[Uuid::new_v4()]),
+            Err(ZebraErrors::CoreElementNotFound)
+        );
+    }
+}
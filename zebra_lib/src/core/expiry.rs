@@ -0,0 +1,317 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::fmt;
+
+use super::record::{Categories, Element, ExpiryDate, Item, Uuid};
+use super::template::{parse_date, parse_expiry};
+use super::Core;
+use crate::errors::ZebraErrors;
+use crate::utils::unix_time;
+
+const DAY: i64 = 24 * 60 * 60;
+// template fields which hold the end of validity.
+const EXPIRY_KEYS: [&str; 2] = ["expiry_date", "card_expiry"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryKind {
+    // a document or a card runs out.
+    Document,
+    // a login password is due to be changed.
+    Rotation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expiration {
+    pub id: Uuid,
+    pub kind: ExpiryKind,
+    pub date: ExpiryDate,
+    // negative once the date has passed.
+    pub days_left: i64,
+}
+
+impl ExpiryDate {
+    // "2030-01-31", the format of `ItemKind::Date`.
+    pub fn parse(value: &str) -> Option<Self> {
+        parse_date(value.trim()).map(|(year, month, day)| Self { year, month, day })
+    }
+
+    // a card is valid through the last day of its month.
+    pub fn from_card(value: &str) -> Option<Self> {
+        let (month, year) = parse_expiry(value.trim())?;
+        let next = if month == 12 {
+            Self::new(year + 1, 1, 1)
+        } else {
+            Self::new(year, month + 1, 1)
+        };
+
+        Some(Self::from_days(next.days() - 1))
+    }
+
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        Self { year, month, day }
+    }
+
+    // days since 1970-01-01, http://howardhinnant.github.io/date_algorithms.html
+    pub fn days(&self) -> i64 {
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let month = i64::from(self.month);
+        let doy =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+        era * 146097 + doe - 719468
+    }
+
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+
+        Self { year, month, day }
+    }
+}
+
+impl fmt::Display for ExpiryDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Item {
+    // the newest history entry is the moment of the last change.
+    pub fn last_changed(&self, created: i64) -> i64 {
+        self.history
+            .first()
+            .map(|entry| entry.changed)
+            .unwrap_or(created)
+    }
+}
+
+impl Element {
+    // records with an expiry field keep `expires` in step with it.
+    pub fn sync_expiry(&mut self) {
+        let field = self
+            .fields
+            .iter()
+            .find(|item| EXPIRY_KEYS.contains(&item.key.as_str()));
+
+        if let Some(item) = field {
+            self.expires = match item.key.as_str() {
+                "card_expiry" => ExpiryDate::from_card(&item.value),
+                _ => ExpiryDate::parse(&item.value),
+            };
+        }
+    }
+
+    pub fn has_expiry_field(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|item| EXPIRY_KEYS.contains(&item.key.as_str()))
+    }
+}
+
+impl Core {
+    // documents running out and logins due for a new password within `within` days,
+    // overdue ones included, soonest first.
+    pub fn upcoming_expirations(&self, within: u32) -> Result<Vec<Expiration>, ZebraErrors> {
+        let today = unix_time()?.div_euclid(DAY);
+        let rotation_days = i64::from(self.state.settings.records.rotation_days);
+        let mut found = Vec::new();
+
+        for record in &self.data {
+            let elem = record.get_value();
+
+            if let Some(date) = elem.expires {
+                found.push(Expiration {
+                    id: elem.id,
+                    kind: ExpiryKind::Document,
+                    date,
+                    days_left: date.days() - today,
+                });
+            }
+
+            if rotation_days == 0 || !matches!(record, Categories::Login(_)) {
+                continue;
+            }
+
+            // the oldest password of the login decides.
+            let changed = elem
+                .fields
+                .iter()
+                .chain(elem.extra_fields.iter())
+                .filter(|item| item.is_password() && !item.value.is_empty())
+                .map(|item| item.last_changed(elem.created))
+                .min();
+
+            if let Some(changed) = changed {
+                let due = ExpiryDate::from_days(changed.div_euclid(DAY) + rotation_days);
+
+                found.push(Expiration {
+                    id: elem.id,
+                    kind: ExpiryKind::Rotation,
+                    date: due,
+                    days_left: due.days() - today,
+                });
+            }
+        }
+
+        found.retain(|expiration| expiration.days_left <= i64::from(within));
+        found.sort_by_key(|expiration| expiration.days_left);
+
+        Ok(found)
+    }
+
+    // records saved before `expires` existed, runs on unlock.
+    pub(super) fn backfill_expiry(&mut self) -> bool {
+        let mut changed = false;
+
+        for record in self.data.iter_mut() {
+            changed |= record.restore_keys();
+
+            let elem = record.get_value_mut();
+            let before = elem.expires;
+
+            elem.sync_expiry();
+            changed |= elem.expires != before;
+        }
+
+        changed
+    }
+
+    pub fn set_expiry_notice_days(&mut self, days: u32) -> Result<(), ZebraErrors> {
+        self.state.settings.records.expiry_notice_days = days;
+        self.state_update()
+    }
+}
+
+#[cfg(test)]
+mod expiry_tests {
+    use super::*;
+    use crate::core::record::{HistoryEntry, ItemKind};
+    use crate::core::test_core;
+
+    fn item(key: &str, kind: ItemKind, value: &str) -> Item {
+        Item {
+            key: key.to_string(),
+            kind,
+            value: value.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(ExpiryDate::new(1970, 1, 1).days(), 0);
+        assert_eq!(ExpiryDate::new(2000, 3, 1).days(), 11017);
+        assert_eq!(ExpiryDate::from_days(11017), ExpiryDate::new(2000, 3, 1));
+        assert_eq!(ExpiryDate::from_days(-1), ExpiryDate::new(1969, 12, 31));
+        assert_eq!(
+            ExpiryDate::from_card("02/24"),
+            Some(ExpiryDate::new(2024, 2, 29))
+        );
+        assert_eq!(
+            ExpiryDate::from_card("12/2030"),
+            Some(ExpiryDate::new(2030, 12, 31))
+        );
+        assert_eq!(ExpiryDate::parse("2030-02-30"), None);
+        assert_eq!(ExpiryDate::new(2030, 1, 5).to_string(), "2030-01-05");
+    }
+
+    #[test]
+    fn test_upcoming_expirations() {
+        let mut core = test_core();
+
+        let today = unix_time().unwrap().div_euclid(DAY);
+        let soon = ExpiryDate::from_days(today + 10);
+        let later = ExpiryDate::from_days(today + 400);
+        let passport = core
+            .add_element(Categories::Passport(Element {
+                fields: vec![item("expiry_date", ItemKind::Date, &soon.to_string())],
+                ..Default::default()
+            }))
+            .unwrap();
+
+        core.add_element(Categories::DriverLicense(Element {
+            fields: vec![item("expiry_date", ItemKind::Date, &later.to_string())],
+            ..Default::default()
+        }))
+        .unwrap();
+
+        let login = core
+            .add_element(Categories::Login(Element {
+                fields: vec![item("password", ItemKind::Password, "hunter2")],
+                ..Default::default()
+            }))
+            .unwrap();
+
+        assert_eq!(
+            core.get_element(&passport).unwrap().get_value().expires,
+            Some(soon)
+        );
+
+        let found = core.upcoming_expirations(30).unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, passport);
+        assert_eq!(found[0].kind, ExpiryKind::Document);
+        assert_eq!(found[0].days_left, 10);
+
+        // the password was set 350 days ago and is due in 15.
+        let index = core.position(&login).unwrap();
+
+        core.data[index].get_value_mut().fields[0].history = vec![HistoryEntry {
+            value: "old".to_string(),
+            changed: (today - 350) * DAY,
+        }];
+
+        let found = core.upcoming_expirations(30).unwrap();
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].id, login);
+        assert_eq!(found[1].kind, ExpiryKind::Rotation);
+        assert_eq!(found[1].days_left, 15);
+
+        core.set_rotation_days(0).unwrap();
+
+        assert_eq!(core.upcoming_expirations(30).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_backfill_on_unlock() {
+        let mut core = test_core();
+
+        let today = unix_time().unwrap().div_euclid(DAY);
+        let soon = ExpiryDate::from_days(today + 10);
+        let passport = Uuid::new_v4();
+
+        // written before template keys and `expires`.
+        core.data.push(Categories::Passport(Element {
+            id: passport,
+            fields: vec![Item {
+                title: "Expiry date".to_string(),
+                kind: ItemKind::Date,
+                value: soon.to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }));
+        core.update().unwrap();
+        core.lock();
+        core.unlock("password").unwrap();
+
+        let elem = core.get_element(&passport).unwrap().get_value();
+
+        assert_eq!(elem.fields[0].key, "expiry_date");
+        assert_eq!(elem.expires, Some(soon));
+        assert_eq!(core.upcoming_expirations(30).unwrap()[0].id, passport);
+    }
+}
@@ -0,0 +1,237 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use super::email::is_valid_email;
+use super::otp::OtpAuth;
+use super::record::{Element, ItemKind};
+use super::template::parse_date;
+use crate::errors::ZebraErrors;
+
+impl ItemKind {
+    pub const ALL: [ItemKind; 10] = [
+        ItemKind::Text,
+        ItemKind::Password,
+        ItemKind::Url,
+        ItemKind::Email,
+        ItemKind::Phone,
+        ItemKind::Date,
+        ItemKind::Multiline,
+        ItemKind::Number,
+        ItemKind::Totp,
+        ItemKind::File,
+    ];
+
+    // values which are masked by default.
+    pub fn is_secret(&self) -> bool {
+        matches!(self, ItemKind::Password | ItemKind::Totp)
+    }
+
+    pub fn validate(&self, value: &str) -> Result<(), ZebraErrors> {
+        let valid = match self {
+            ItemKind::Url => url_host(value).is_some(),
+            ItemKind::Email => is_valid_email(value)?,
+            ItemKind::Phone => is_valid_phone(value),
+            ItemKind::Date => parse_date(value).is_some(),
+            ItemKind::Number => is_valid_number(value),
+            ItemKind::Totp => is_valid_totp(value),
+            _ => return Ok(()),
+        };
+
+        if valid {
+            return Ok(());
+        }
+
+        Err(match self {
+            ItemKind::Url => ZebraErrors::CoreInvalidUrl,
+            ItemKind::Email => ZebraErrors::CoreInvalidEmail,
+            ItemKind::Phone => ZebraErrors::CoreInvalidPhone,
+            ItemKind::Date => ZebraErrors::CoreInvalidDate,
+            ItemKind::Number => ZebraErrors::CoreInvalidNumber,
+            _ => ZebraErrors::CoreInvalidTotp,
+        })
+    }
+
+    // the form a value is stored in, secrets and free text are never touched.
+    pub fn format(&self, value: &str) -> String {
+        match self {
+            ItemKind::Text | ItemKind::Password | ItemKind::Multiline => value.to_string(),
+            ItemKind::Phone => value.split_whitespace().collect::<Vec<_>>().join(" "),
+            ItemKind::Totp if !value.trim().starts_with("otpauth://") => value
+                .chars()
+                .filter(|c| !c.is_whitespace() && *c != '=')
+                .collect::<String>()
+                .to_uppercase(),
+            _ => value.trim().to_string(),
+        }
+    }
+
+    // what an "open" button should hand over to the system, only web, mail and phone links:
+    // a file path or another scheme could start a program.
+    pub fn link(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+
+        if value.is_empty() || self.validate(value).is_err() {
+            return None;
+        }
+
+        match self {
+            ItemKind::Url => match value.split_once("://") {
+                Some((scheme, _))
+                    if scheme.eq_ignore_ascii_case("http")
+                        || scheme.eq_ignore_ascii_case("https") =>
+                {
+                    Some(value.to_string())
+                }
+                Some(_) => None,
+                None => Some(format!("https://{}", value)),
+            },
+            ItemKind::Email => Some(format!("mailto:{}", value)),
+            ItemKind::Phone => Some(format!("tel:{}", value.replace(' ', ""))),
+            _ => None,
+        }
+    }
+}
+
+impl Element {
+    pub fn normalize(&mut self) {
+        for item in self.fields.iter_mut().chain(self.extra_fields.iter_mut()) {
+            if item.reference().is_some() {
+                continue;
+            }
+
+            item.value = item.kind.format(&item.value);
+        }
+
+        self.sync_expiry();
+        self.sync_ssh_key();
+    }
+}
+
+// "https://user@www.example.com:443/x" and "example.com/x" both give the host.
+pub fn url_host(value: &str) -> Option<&str> {
+    if value.is_empty() || value.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let (scheme, rest) = match value.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, value),
+    };
+
+    if scheme.is_some_and(|s| s.is_empty() || !s.chars().all(|c| c.is_ascii_alphanumeric())) {
+        return None;
+    }
+
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit('@').next()?;
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    let looks_like_host = host.contains('.') || host == "localhost" || scheme.is_some();
+
+    (!host.is_empty() && looks_like_host).then_some(host)
+}
+
+// an optional leading "+", digits and the usual separators.
+fn is_valid_phone(value: &str) -> bool {
+    let rest = value.strip_prefix('+').unwrap_or(value);
+    let digits = rest.chars().filter(char::is_ascii_digit).count();
+
+    (5..=15).contains(&digits)
+        && rest
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '(' | ')' | '.'))
+}
+
+fn is_valid_number(value: &str) -> bool {
+    let value = value.strip_prefix('-').unwrap_or(value);
+    let mut parts = value.splitn(2, ['.', ',']);
+    let int = parts.next().unwrap_or_default();
+    let frac = parts.next();
+
+    !int.is_empty()
+        && int.chars().all(|c| c.is_ascii_digit())
+        && !frac.is_some_and(|f| f.is_empty() || !f.chars().all(|c| c.is_ascii_digit()))
+}
+
+// a base32 secret or an otpauth:// link.
+fn is_valid_totp(value: &str) -> bool {
+    OtpAuth::parse(value).is_ok()
+}
+
+#[cfg(test)]
+mod kind_tests {
+    use super::*;
+    use crate::core::record::Item;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(ItemKind::Url.validate("github.com"), Ok(()));
+        assert_eq!(ItemKind::Url.validate("http://localhost:8080/x"), Ok(()));
+        assert_eq!(
+            ItemKind::Url.validate("not a url"),
+            Err(ZebraErrors::CoreInvalidUrl)
+        );
+        assert_eq!(ItemKind::Phone.validate("+1 (555) 010-9999"), Ok(()));
+        assert_eq!(
+            ItemKind::Phone.validate("call me"),
+            Err(ZebraErrors::CoreInvalidPhone)
+        );
+        assert_eq!(ItemKind::Number.validate("-12,5"), Ok(()));
+        assert_eq!(
+            ItemKind::Number.validate("12."),
+            Err(ZebraErrors::CoreInvalidNumber)
+        );
+        assert_eq!(ItemKind::Totp.validate("jbsw y3dp ehpk 3pxp"), Ok(()));
+        assert_eq!(
+            ItemKind::Totp.validate("jbsw1"),
+            Err(ZebraErrors::CoreInvalidTotp)
+        );
+        assert_eq!(
+            ItemKind::Date.validate("2024-13-01"),
+            Err(ZebraErrors::CoreInvalidDate)
+        );
+        assert_eq!(ItemKind::Password.validate(" any thing "), Ok(()));
+    }
+
+    #[test]
+    fn test_format_and_link() {
+        assert_eq!(
+            ItemKind::Totp.format("jbsw y3dp ehpk 3pxp=="),
+            "JBSWY3DPEHPK3PXP"
+        );
+        assert_eq!(ItemKind::Phone.format(" +1  555 0100 "), "+1 555 0100");
+        assert_eq!(ItemKind::Password.format(" secret "), " secret ");
+        assert_eq!(
+            ItemKind::Url.link("example.com"),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(
+            ItemKind::Email.link("a@b.io"),
+            Some("mailto:a@b.io".to_string())
+        );
+        assert_eq!(ItemKind::Text.link("example.com"), None);
+        assert_eq!(ItemKind::Url.link("not a url"), None);
+        assert_eq!(
+            ItemKind::Url.link("HTTP://example.com/x"),
+            Some("HTTP://example.com/x".to_string())
+        );
+        assert_eq!(ItemKind::Url.link("file://host/bin/sh"), None);
+        assert_eq!(ItemKind::Url.link("smb://nas/share"), None);
+        assert_eq!(ItemKind::File.link("/usr/bin/xterm"), None);
+
+        let mut elem = Element {
+            extra_fields: vec![Item {
+                kind: ItemKind::Url,
+                value: " example.com ".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        elem.normalize();
+
+        assert_eq!(elem.extra_fields[0].value, "example.com");
+    }
+}
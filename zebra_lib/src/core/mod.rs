@@ -1,23 +1,60 @@
 //! -- Copyright (c) 2023 Rina Khasanshin
 //! -- Email: hicarus@yandex.ru
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+pub mod attachment;
+pub mod audit;
+pub mod breach;
+pub mod category;
+pub mod duplicate;
 pub mod email;
+pub mod expiry;
+pub mod kind;
+pub mod organize;
+pub mod otp;
 pub mod passgen;
 pub mod password_strength;
+pub mod payload;
 pub mod record;
-
-use std::fs::File;
+pub mod reference;
+pub mod reprompt;
+pub mod search;
+pub mod ssh;
+pub mod template;
+pub mod uri;
+pub mod verify;
+
+use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 
-use crate::core::record::Categories;
+use crate::core::breach::BreachDb;
+use crate::core::category::CustomCategory;
+use crate::core::organize::{Folder, Tag};
+use crate::core::payload::{Payload, TrashEntry};
+use crate::core::record::{Categories, Element, HistoryEntry, Item, Uuid};
+use crate::core::search::SearchIndex;
+use crate::core::verify::{verify_key, VerifyReport};
 use crate::keychain::keys::{KeyChain, AES_KEY_SIZE};
 use crate::{
     bip39::mnemonic::Mnemonic,
-    config::app::{APPLICATION, ORGANIZATION, QUALIFIER},
+    config::{
+        app::{APPLICATION, ORGANIZATION, QUALIFIER},
+        location::VaultLocation,
+    },
     errors::ZebraErrors,
     state::State,
-    storage::db::LocalStorage,
+    storage::{
+        backend::StorageBackend,
+        db::LocalStorage,
+        file_backend::FileBackend,
+        keys::{
+            SLED_ACCESSED_KEY, SLED_BROKEN_KEY, SLED_DEVICE_KEY, SLED_SNAPSHOT_KEY, SLED_STATE_KEY,
+            SNAPSHOTS_LIMIT,
+        },
+        memory_backend::MemoryBackend,
+        sled_backend::SledBackend,
+    },
+    utils::unix_time,
 };
 use ntrulp::params::params1277::{PUBLICKEYS_BYTES, SECRETKEYS_BYTES};
 use std::{borrow::Cow, fmt};
@@ -25,8 +62,16 @@ use std::{borrow::Cow, fmt};
 pub struct Core {
     pub state: State<'static>,
     pub data: Vec<Categories>,
+    trash: Vec<TrashEntry>,
+    tags: Vec<Tag>,
+    folders: Vec<Folder>,
+    categories: Vec<CustomCategory>,
+    index: SearchIndex,
+    breach: Option<BreachDb>,
     keys: Option<KeyChain>,
     db: LocalStorage,
+    location: VaultLocation,
+    device: Uuid,
 }
 
 impl fmt::Debug for Core {
@@ -48,23 +93,133 @@ impl Core {
         organization: &str,
         application: &str,
     ) -> Result<Self, ZebraErrors> {
-        let db = LocalStorage::new(qualifier, organization, application)?;
+        let backend = SledBackend::from(qualifier, organization, application)?;
+
+        Ok(Core::with_backend(backend))
+    }
+
+    pub fn from_location(location: VaultLocation) -> Result<Self, ZebraErrors> {
+        let path = location.resolve()?;
+        let backend = SledBackend::new(&path)?;
+        let mut core = Core::with_backend(backend);
+
+        core.location = location;
+
+        Ok(core)
+    }
+
+    pub fn with_backend<B>(backend: B) -> Self
+    where
+        B: StorageBackend + 'static,
+    {
+        let db = LocalStorage::with_backend(Box::new(backend));
         let state = State::new();
         let data = Vec::default();
+        let trash = Vec::default();
+        let tags = Vec::default();
+        let folders = Vec::default();
+        let categories = Vec::default();
+        let index = SearchIndex::default();
+        let breach = None;
         let keys = None;
+        let location = VaultLocation::default();
+        let device = Uuid::nil();
 
-        Ok(Self {
+        Self {
             db,
             state,
             data,
+            trash,
+            tags,
+            folders,
+            categories,
+            index,
+            breach,
             keys,
-        })
+            location,
+            device,
+        }
     }
 
-    pub fn get_data_dir(&self) -> &Path {
+    pub fn get_data_dir(&self) -> Option<&Path> {
         self.db.get_path()
     }
 
+    pub fn get_location(&self) -> &VaultLocation {
+        &self.location
+    }
+
+    // moves sled files into `path`, which has to be empty or not exist yet.
+    // a portable vault lives next to the executable, so it stays put.
+    // a custom one has to be started with the new path afterwards.
+    pub fn move_vault(&mut self, path: &Path) -> Result<(), ZebraErrors> {
+        if self.db.get_path() == Some(path) {
+            return Ok(());
+        }
+
+        if self.location == VaultLocation::Portable {
+            return Err(ZebraErrors::StorageMoveNotAllowed);
+        }
+
+        if let Ok(mut dir) = std::fs::read_dir(path) {
+            if dir.next().is_some() {
+                return Err(ZebraErrors::StoragePathExists);
+            }
+        }
+
+        let backend = SledBackend::new(path)?;
+
+        self.db.move_to(Box::new(backend))?;
+        self.location.remember(path)?;
+
+        if let VaultLocation::Custom(_) = self.location {
+            self.location = VaultLocation::Custom(path.to_path_buf());
+        }
+
+        Ok(())
+    }
+
+    // wipes every file of the vault and leaves an empty one at the same place.
+    pub fn destroy_vault(&mut self, password: &str) -> Result<(), ZebraErrors> {
+        session_keys(&self.state, password.as_bytes())
+            .or(Err(ZebraErrors::GuardInvalidPassword))?;
+
+        let path = self.db.get_path().map(Path::to_path_buf);
+        let is_dir = path.as_ref().is_some_and(|p| p.is_dir());
+
+        // nothing is forgotten until the files are gone.
+        self.db.destroy()?;
+        self.keys = None;
+        self.data = Vec::new();
+        self.trash = Vec::new();
+        self.tags = Vec::new();
+        self.folders = Vec::new();
+        self.categories = Vec::new();
+        self.index = SearchIndex::default();
+
+        match path {
+            Some(path) if is_dir => {
+                // a moved default vault starts over in the data dir.
+                let path = if self.location.forget(&path)? {
+                    self.location.resolve()?
+                } else {
+                    path
+                };
+
+                self.db = LocalStorage::with_backend(Box::new(SledBackend::new(&path)?));
+            }
+            Some(path) => {
+                self.db = LocalStorage::with_backend(Box::new(FileBackend::new(&path)?));
+            }
+            None => {}
+        }
+
+        self.state = State::new();
+        self.sync()?;
+
+        Ok(())
+    }
+
     pub fn get_data_size(&self) -> u64 {
         self.db.get_db_size()
     }
@@ -74,17 +229,23 @@ impl Core {
     }
 
     pub fn export_to_file(&self, path: &Path) -> Result<(), ZebraErrors> {
-        self.db.save_as_file(path)?;
+        self.db.save_as_file(SLED_STATE_KEY, path)?;
 
         Ok(())
     }
 
     pub fn sync(&mut self) -> Result<(), ZebraErrors> {
         self.state.sync(&self.db)?;
+        self.device = self.load_device()?;
+        self.load_breach_db();
 
         Ok(())
     }
 
+    pub fn get_device(&self) -> &Uuid {
+        &self.device
+    }
+
     pub fn state_update(&self) -> Result<(), ZebraErrors> {
         self.state.state_update(&self.db)
     }
@@ -140,40 +301,352 @@ impl Core {
 
     pub fn unlock(&mut self, password: &str) -> Result<(), ZebraErrors> {
         self.try_unlock(password.as_bytes())?;
-        self.data = self.get_data()?;
+
+        let payload = self.get_data()?;
+
+        self.data = payload.records.into_owned();
+        self.trash = payload.trash.into_owned();
+        self.tags = payload.tags.into_owned();
+        self.folders = payload.folders.into_owned();
+        self.categories = payload.categories.into_owned();
+        self.load_accessed();
+
+        let mut migrated = self.purge_expired()?;
+
+        for elem in self.data.iter_mut() {
+            if elem.get_id().is_nil() {
+                elem.get_value_mut().id = Uuid::new_v4();
+                migrated = true;
+            }
+        }
+
+        migrated |= self.backfill_expiry();
+
+        if migrated {
+            self.update()?;
+            self.drop_orphan_attachments()?;
+        } else {
+            self.reindex();
+        }
 
         Ok(())
     }
 
-    pub fn add_element(&mut self, elem: Categories) -> Result<(), ZebraErrors> {
+    pub fn add_element(&mut self, mut elem: Categories) -> Result<Uuid, ZebraErrors> {
+        elem.get_value_mut().normalize();
+        elem.validate()?;
+
+        let id = Uuid::new_v4();
+        let now = unix_time()?;
+        let value = elem.get_value_mut();
+
+        value.id = id;
+        value.created = now;
+        value.updated = now;
+        value.accessed = 0;
+        value.device = self.device;
+
         self.data.push(elem);
         self.update()?;
 
         // TODO: add email validator.
-        // TODO: add created, updated time.
+
+        Ok(id)
+    }
+
+    pub fn get_element(&self, id: &Uuid) -> Option<&Categories> {
+        self.data.iter().find(|elem| elem.get_id() == id)
+    }
+
+    // keeps the category and the id, replaces everything else.
+    pub fn update_element(&mut self, id: &Uuid, mut element: Element) -> Result<(), ZebraErrors> {
+        element.normalize();
+        element.validate()?;
+
+        let index = self.position(id)?;
+        let old = self.data[index].get_value();
+        let now = unix_time()?;
+        let depth = self.state.settings.records.history_depth;
+
+        record_history(&old.fields, &mut element.fields, now, depth);
+        record_history(&old.extra_fields, &mut element.extra_fields, now, depth);
+
+        // files change only through `add_attachment` and `remove_attachment`.
+        element.attachments.clone_from(&old.attachments);
+        element.id = *id;
+        element.created = old.created;
+        element.accessed = old.accessed;
+        element.updated = now;
+        element.device = self.device;
+
+        self.data[index] = self.data[index].update_element(element);
+        self.update()?;
 
         Ok(())
     }
 
-    pub fn remove_element(&mut self, index: usize) -> Result<(), ZebraErrors> {
-        self.data.remove(index);
+    // puts an old value back, the current one goes into the history.
+    pub fn restore_history(
+        &mut self,
+        id: &Uuid,
+        field: usize,
+        entry: usize,
+    ) -> Result<(), ZebraErrors> {
+        let index = self.position(id)?;
+        let now = unix_time()?;
+        let depth = self.state.settings.records.history_depth;
+        let element = self.data[index].get_value_mut();
+        let item = element
+            .get_field_mut(field)
+            .ok_or(ZebraErrors::CoreElementNotFound)?;
+
+        if entry >= item.history.len() {
+            return Err(ZebraErrors::CoreElementNotFound);
+        }
+
+        let restored = item.history.remove(entry).value;
+        let current = std::mem::replace(&mut item.value, restored);
+
+        item.history.insert(
+            0,
+            HistoryEntry {
+                value: current,
+                changed: now,
+            },
+        );
+        item.history.truncate(depth);
+        element.updated = now;
+        element.device = self.device;
+
+        self.update()
+    }
+
+    pub fn set_history_depth(&mut self, depth: usize) -> Result<(), ZebraErrors> {
+        self.state.settings.records.history_depth = depth;
+        self.state_update()
+    }
+
+    // marks the record as used, called whenever one of its secrets is copied.
+    pub fn touch_element(&mut self, id: &Uuid) -> Result<(), ZebraErrors> {
+        let index = self.position(id)?;
+
+        self.data[index].get_value_mut().accessed = unix_time()?;
+
+        let accessed: Vec<(Uuid, i64)> = self
+            .data
+            .iter()
+            .map(|record| (*record.get_id(), record.get_value().accessed))
+            .filter(|(_, accessed)| *accessed > 0)
+            .collect();
+        let json = serde_json::to_vec(&accessed).or(Err(ZebraErrors::GuardBrokenData))?;
+        let keys = self.keys.as_ref().ok_or(ZebraErrors::GuardIsNotEnable)?;
+        let cipher = keys.encrypt(json, &self.state.settings.cipher.cipher_orders)?;
+
+        self.db.set_raw(SLED_ACCESSED_KEY, cipher.as_bytes())?;
+
+        Ok(())
+    }
+
+    // use times written by `touch_element`, a broken or missing list is ignored.
+    fn load_accessed(&mut self) {
+        let Some(keys) = self.keys.as_ref() else {
+            return;
+        };
+        let accessed: Vec<(Uuid, i64)> = self
+            .db
+            .get_raw(SLED_ACCESSED_KEY)
+            .ok()
+            .and_then(|raw| String::from_utf8(raw).ok())
+            .and_then(|cipher| {
+                keys.decrypt(&cipher, &self.state.settings.cipher.cipher_orders)
+                    .ok()
+            })
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .unwrap_or_default();
+
+        for (id, time) in accessed {
+            if let Ok(index) = self.position(&id) {
+                let elem = self.data[index].get_value_mut();
+
+                elem.accessed = elem.accessed.max(time);
+            }
+        }
+    }
+
+    // moves the record into the trash.
+    pub fn remove_element(&mut self, id: &Uuid) -> Result<(), ZebraErrors> {
+        let index = self.position(id)?;
+        let record = self.data.remove(index);
+        let deleted = unix_time()?;
+
+        self.trash.push(TrashEntry { record, deleted });
         self.update()?;
 
         Ok(())
     }
 
+    pub fn get_trash(&self) -> &[TrashEntry] {
+        &self.trash
+    }
+
+    pub fn restore_element(&mut self, id: &Uuid) -> Result<(), ZebraErrors> {
+        let index = self.trash_position(id)?;
+        let entry = self.trash.remove(index);
+
+        self.data.push(entry.record);
+        self.update()?;
+
+        Ok(())
+    }
+
+    pub fn purge_element(&mut self, id: &Uuid) -> Result<(), ZebraErrors> {
+        let index = self.trash_position(id)?;
+
+        self.trash.remove(index);
+        self.update()?;
+        self.drop_orphan_attachments()
+    }
+
+    pub fn empty_trash(&mut self) -> Result<(), ZebraErrors> {
+        self.trash.clear();
+        self.update()?;
+        self.drop_orphan_attachments()
+    }
+
+    pub fn set_trash_retention(&mut self, days: u32) -> Result<(), ZebraErrors> {
+        self.state.settings.records.trash_retention_days = days;
+        self.state_update()
+    }
+
+    fn load_device(&self) -> Result<Uuid, ZebraErrors> {
+        let stored = self
+            .db
+            .get_raw(SLED_DEVICE_KEY)
+            .ok()
+            .and_then(|raw| Uuid::try_parse_ascii(&raw).ok());
+
+        match stored {
+            Some(device) => Ok(device),
+            None => {
+                let device = Uuid::new_v4();
+
+                self.db
+                    .set_raw(SLED_DEVICE_KEY, device.to_string().as_bytes())?;
+
+                Ok(device)
+            }
+        }
+    }
+
+    // drops records kept in the trash longer than the retention, returns if any was dropped.
+    fn purge_expired(&mut self) -> Result<bool, ZebraErrors> {
+        const DAY: i64 = 24 * 60 * 60;
+
+        let days = self.state.settings.records.trash_retention_days;
+
+        if days == 0 {
+            return Ok(false);
+        }
+
+        let deadline = unix_time()? - days as i64 * DAY;
+        let len = self.trash.len();
+
+        self.trash.retain(|entry| entry.deleted > deadline);
+
+        Ok(self.trash.len() != len)
+    }
+
+    fn trash_position(&self, id: &Uuid) -> Result<usize, ZebraErrors> {
+        self.trash
+            .iter()
+            .position(|entry| entry.record.get_id() == id)
+            .ok_or(ZebraErrors::CoreElementNotFound)
+    }
+
+    fn position(&self, id: &Uuid) -> Result<usize, ZebraErrors> {
+        self.data
+            .iter()
+            .position(|elem| elem.get_id() == id)
+            .ok_or(ZebraErrors::CoreElementNotFound)
+    }
+
+    // checks what is on disk, not the state in memory.
+    pub fn verify(&self, password: &str) -> Result<VerifyReport, ZebraErrors> {
+        let mut report = verify_key(&self.db, SLED_STATE_KEY, password.as_bytes());
+
+        report.intact_snapshots = self
+            .db
+            .snapshots(SLED_SNAPSHOT_KEY)?
+            .iter()
+            .filter(|key| verify_key(&self.db, key, password.as_bytes()).is_ok())
+            .count();
+
+        Ok(report)
+    }
+
+    // restores the newest intact snapshot, or the `backup` made by `export_to_file`.
+    pub fn repair(
+        &mut self,
+        password: &str,
+        backup: Option<&Path>,
+    ) -> Result<VerifyReport, ZebraErrors> {
+        let report = self.verify(password)?;
+
+        if report.is_ok() {
+            return Ok(report);
+        }
+
+        let mut candidates = Vec::new();
+
+        for key in self.db.snapshots(SLED_SNAPSHOT_KEY)? {
+            candidates.push(self.db.get_raw(&key)?);
+        }
+
+        if let Some(path) = backup {
+            candidates.push(fs::read(path).or(Err(ZebraErrors::StorageAccessError))?);
+        }
+
+        for raw in candidates {
+            let probe = LocalStorage::with_backend(Box::new(MemoryBackend::new()));
+
+            probe.set_raw(SLED_STATE_KEY, &raw)?;
+
+            if !verify_key(&probe, SLED_STATE_KEY, password.as_bytes()).is_ok() {
+                continue;
+            }
+
+            self.db
+                .snapshot(SLED_STATE_KEY, SLED_BROKEN_KEY, SNAPSHOTS_LIMIT)?;
+            self.db.set_raw(SLED_STATE_KEY, &raw)?;
+            self.state = State::new();
+            self.sync()?;
+            self.unlock(password)?;
+
+            return self.verify(password);
+        }
+
+        Err(ZebraErrors::StorageRepairFailed)
+    }
+
     pub fn is_unlock(&self) -> bool {
         self.keys.is_some()
     }
 
+    // forgets the keys and everything decrypted until the next `unlock`.
+    pub fn lock(&mut self) {
+        self.keys = None;
+        self.data = Vec::new();
+        self.trash = Vec::new();
+        self.tags = Vec::new();
+        self.folders = Vec::new();
+        self.categories = Vec::new();
+        self.index = SearchIndex::default();
+    }
+
     // gen_keys from password
     // -> decrypt keys_session(bip39)
     // -> decrypt secure_data via (bip39) keys
     fn try_unlock(&mut self, password: &[u8]) -> Result<(), ZebraErrors> {
-        let orders = &self.state.settings.cipher.cipher_orders;
-        let difficulty = self.state.settings.cipher.difficulty;
-        let secure_key_store = &self.state.secure_key_store;
-
         if !self.state.inited {
             return Err(ZebraErrors::StateNotInited);
         }
@@ -181,35 +654,21 @@ impl Core {
             return Err(ZebraErrors::StateNotRead);
         }
 
-        let pass_keys =
-            KeyChain::from_pass(password, difficulty).or(Err(ZebraErrors::GuardInvalidPassword))?;
-        let session = pass_keys.decrypt(secure_key_store, orders)?;
-        let aes_key: [u8; AES_KEY_SIZE] = session[..AES_KEY_SIZE]
-            .try_into()
-            .or(Err(ZebraErrors::KeyChainKeysDamaged))?;
-        let pq_pk: [u8; PUBLICKEYS_BYTES] = session[AES_KEY_SIZE..PUBLICKEYS_BYTES + AES_KEY_SIZE]
-            .try_into()
-            .or(Err(ZebraErrors::KeyChainKeysDamaged))?;
-        let pq_sk: [u8; SECRETKEYS_BYTES] = session[AES_KEY_SIZE + PUBLICKEYS_BYTES..]
-            .try_into()
-            .or(Err(ZebraErrors::KeyChainKeysDamaged))?;
-        let bip39_keys = KeyChain::from_keys(aes_key, pq_sk, pq_pk)?;
+        let bip39_keys = session_keys(&self.state, password)?;
 
         self.keys = Some(bip39_keys);
 
         Ok(())
     }
 
-    fn get_data(&self) -> Result<Vec<Categories>, ZebraErrors> {
+    fn get_data(&self) -> Result<Payload<'static>, ZebraErrors> {
         let orders = &self.state.settings.cipher.cipher_orders;
         let secure_data_store = &self.state.secure_data_store;
 
         let keys = self.keys.as_ref().ok_or(ZebraErrors::GuardIsNotEnable)?;
         let json_bytes = keys.decrypt(secure_data_store, orders)?;
 
-        let data = serde_json::from_slice(&json_bytes).or(Err(ZebraErrors::StorageDataBroken))?;
-
-        Ok(data)
+        Payload::from_slice(&json_bytes)
     }
 
     fn bip39_cipher_from_password(
@@ -226,7 +685,7 @@ impl Core {
         let bip39_keys_bytes = bip39_keys.as_bytes().to_vec();
         let keys_cipher = pwd_keys.encrypt(bip39_keys_bytes, orders)?;
 
-        let json = serde_json::to_string(&self.data).or(Err(ZebraErrors::GuardBrokenData))?;
+        let json = self.payload_json()?;
         let data_cipher = bip39_keys.encrypt(json.as_bytes().to_vec(), orders)?;
 
         self.keys = Some(bip39_keys);
@@ -240,10 +699,12 @@ impl Core {
     }
 
     fn update(&mut self) -> Result<(), ZebraErrors> {
+        self.reindex();
+
         let orders = &self.state.settings.cipher.cipher_orders;
 
         let bip39_keys = self.keys.as_ref().ok_or(ZebraErrors::GuardIsNotEnable)?;
-        let json = serde_json::to_string(&self.data).or(Err(ZebraErrors::GuardBrokenData))?;
+        let json = self.payload_json()?;
         let data_cipher = bip39_keys.encrypt(json.as_bytes().to_vec(), orders)?;
 
         self.state.secure_data_store = Cow::from(data_cipher);
@@ -252,6 +713,18 @@ impl Core {
         Ok(())
     }
 
+    fn payload_json(&self) -> Result<String, ZebraErrors> {
+        let payload = Payload {
+            records: Cow::Borrowed(&self.data),
+            trash: Cow::Borrowed(&self.trash),
+            tags: Cow::Borrowed(&self.tags),
+            folders: Cow::Borrowed(&self.folders),
+            categories: Cow::Borrowed(&self.categories),
+        };
+
+        serde_json::to_string(&payload).or(Err(ZebraErrors::GuardBrokenData))
+    }
+
     fn get_address<'a>(&self) -> Result<Cow<'a, str>, ZebraErrors> {
         let keys = self.keys.as_ref().ok_or(ZebraErrors::GuardIsNotEnable)?;
         let hex = Cow::from(hex::encode(keys.get_address()));
@@ -260,12 +733,81 @@ impl Core {
     }
 }
 
+// fields are matched by position and title, the form never reorders them.
+fn record_history(old: &[Item], new: &mut [Item], now: i64, depth: usize) {
+    for (old_item, new_item) in old.iter().zip(new.iter_mut()) {
+        if old_item.title != new_item.title || !new_item.hide {
+            continue;
+        }
+
+        new_item.history.clone_from(&old_item.history);
+
+        if old_item.value != new_item.value && !old_item.value.is_empty() {
+            new_item.history.insert(
+                0,
+                HistoryEntry {
+                    value: old_item.value.clone(),
+                    changed: now,
+                },
+            );
+        }
+
+        new_item.history.truncate(depth);
+    }
+}
+
+fn session_keys(state: &State, password: &[u8]) -> Result<KeyChain, ZebraErrors> {
+    let orders = &state.settings.cipher.cipher_orders;
+    let difficulty = state.settings.cipher.difficulty;
+    let secure_key_store = &state.secure_key_store;
+
+    let pass_keys =
+        KeyChain::from_pass(password, difficulty).or(Err(ZebraErrors::GuardInvalidPassword))?;
+    let session = pass_keys.decrypt(secure_key_store, orders)?;
+
+    if session.len() != AES_KEY_SIZE + PUBLICKEYS_BYTES + SECRETKEYS_BYTES {
+        return Err(ZebraErrors::KeyChainKeysDamaged);
+    }
+
+    let aes_key: [u8; AES_KEY_SIZE] = session[..AES_KEY_SIZE]
+        .try_into()
+        .or(Err(ZebraErrors::KeyChainKeysDamaged))?;
+    let pq_pk: [u8; PUBLICKEYS_BYTES] = session[AES_KEY_SIZE..PUBLICKEYS_BYTES + AES_KEY_SIZE]
+        .try_into()
+        .or(Err(ZebraErrors::KeyChainKeysDamaged))?;
+    let pq_sk: [u8; SECRETKEYS_BYTES] = session[AES_KEY_SIZE + PUBLICKEYS_BYTES..]
+        .try_into()
+        .or(Err(ZebraErrors::KeyChainKeysDamaged))?;
+
+    KeyChain::from_keys(aes_key, pq_sk, pq_pk)
+}
+
+// an unlocked vault in memory, the master password is "password".
+#[cfg(test)]
+pub(crate) fn test_core() -> Core {
+    test_core_on(crate::storage::memory_backend::MemoryBackend::new())
+}
+
+// same as `test_core`, the caller keeps a handle on the backend to reopen the vault.
+#[cfg(test)]
+pub(crate) fn test_core_on(backend: impl StorageBackend + 'static) -> Core {
+    let mut rng = rand::thread_rng();
+    let m = Mnemonic::gen(&mut rng, 12, crate::bip39::mnemonic::Language::English).unwrap();
+    let mut core = Core::with_backend(backend);
+
+    core.sync().unwrap();
+    core.init_data(false, "", "password", "", &m).unwrap();
+
+    core
+}
+
 #[cfg(test)]
 mod core_tests {
     use crate::bip39::mnemonic::Language;
 
     use super::*;
-    use crate::core::record::{Categories, Element};
+    use crate::core::record::{Categories, Element, SortOrder};
+    use crate::storage::memory_backend::MemoryBackend;
     use rand;
     use rand::RngCore;
 
@@ -273,7 +815,8 @@ mod core_tests {
 
     #[test]
     fn test_init() {
-        let mut core_data: Core = Core::from("tes0", "tes1", "test2").unwrap();
+        let backend = MemoryBackend::new();
+        let mut core_data: Core = Core::with_backend(backend.clone());
         core_data.sync().unwrap();
 
         let mut rng = rand::thread_rng();
@@ -284,7 +827,7 @@ mod core_tests {
 
         drop(core_data);
 
-        let mut new_core_data: Core = Core::from("tes0", "tes1", "test2").unwrap();
+        let mut new_core_data: Core = Core::with_backend(backend);
         new_core_data.sync().unwrap();
 
         assert!(new_core_data
@@ -294,24 +837,75 @@ mod core_tests {
         assert!(new_core_data.try_unlock(password.as_bytes()).is_ok());
     }
 
+    #[test]
+    fn test_baseline_state() {
+        use sha2::{Digest, Sha256};
+
+        let backend = MemoryBackend::new();
+        let mut core = Core::with_backend(backend.clone());
+        let mut rng = rand::thread_rng();
+        let m = Mnemonic::gen(&mut rng, 12, Language::English).unwrap();
+
+        core.sync().unwrap();
+        core.init_data(false, "", "password", "", &m).unwrap();
+        core.add_element(Categories::Login(Element::default()))
+            .unwrap();
+
+        // the state as it was written before the records and agent settings.
+        let raw = core.db.get_raw(SLED_STATE_KEY).unwrap();
+        let mut data: serde_json::Value = serde_json::from_slice(&raw).unwrap();
+        let mut payload = data["payload"].take();
+        let settings = payload["settings"].as_object_mut().unwrap();
+
+        settings.remove("records");
+        settings.remove("agent");
+
+        let payload = payload.to_string();
+        let hashsum = hex::encode(Sha256::digest(payload.as_bytes()));
+        let old = format!(
+            r#"{{"payload":{},"version":0,"last_update":0,"hashsum":"{}"}}"#,
+            payload, hashsum
+        );
+
+        backend.write(SLED_STATE_KEY, old.as_bytes()).unwrap();
+        drop(core);
+
+        let mut core = Core::with_backend(backend);
+
+        core.sync().unwrap();
+        core.unlock("password").unwrap();
+
+        assert_eq!(core.data.len(), 1);
+    }
+
     #[test]
     fn test_init_unlock() {
         let mut rng = rand::thread_rng();
 
         let m = Mnemonic::gen(&mut rng, 12, Language::English).unwrap();
-        let mut core: Core = Core::from("tes03242", "tes12323", "test299").unwrap();
+        let backend = MemoryBackend::new();
+        let mut core: Core = Core::with_backend(backend.clone());
 
         let mut password = [0u8; 1245];
         let words_password = "test";
         let data = vec![Categories::Login(Element {
+            id: Uuid::new_v4(),
             icon: "test_icon_url".to_string(),
             name: String::new(),
             created: 0,
             updated: 0,
+            accessed: 0,
+            device: Uuid::nil(),
             note: String::new(),
             favourite: false,
             fields: vec![],
             extra_fields: vec![],
+            tags: vec![],
+            folder: None,
+            uris: vec![],
+            expires: None,
+            attachments: vec![],
+            reprompt: false,
         })];
 
         rng.fill_bytes(&mut password);
@@ -335,7 +929,7 @@ mod core_tests {
         drop(core);
 
         // testing unlock
-        let mut new_core: Core = Core::from("tes03242", "tes12323", "test299").unwrap();
+        let mut new_core: Core = Core::with_backend(backend);
 
         assert!(new_core.keys.is_none());
         assert!(!new_core.state.ready);
@@ -356,6 +950,339 @@ mod core_tests {
             core_keys.as_ref().unwrap().as_bytes()
         );
         assert_eq!(secure_data_store, new_core.state.secure_data_store);
-        assert_eq!(&data, &decrypted_data);
+        assert_eq!(data.as_slice(), decrypted_data.records.as_ref());
+    }
+
+    #[test]
+    fn test_elements_by_id() {
+        let password = "password";
+        let backend = MemoryBackend::new();
+        let mut core = test_core_on(backend.clone());
+
+        let login = core
+            .add_element(Categories::Login(Element::default()))
+            .unwrap();
+        let other = core
+            .add_element(Categories::Other(Element::default()))
+            .unwrap();
+
+        assert_ne!(login, other);
+        assert!(!login.is_nil());
+
+        let element = Element {
+            name: "github".to_string(),
+            ..Default::default()
+        };
+
+        core.update_element(&login, element).unwrap();
+
+        match core.get_element(&login).unwrap() {
+            Categories::Login(elem) => {
+                assert_eq!(elem.name, "github");
+                assert_eq!(elem.id, login);
+            }
+            _ => panic!("category changed"),
+        }
+
+        core.remove_element(&other).unwrap();
+
+        assert!(core.get_element(&other).is_none());
+        assert_eq!(
+            core.remove_element(&other),
+            Err(ZebraErrors::CoreElementNotFound)
+        );
+
+        // records without ids get them on unlock.
+        core.data.push(Categories::Other(Element::default()));
+        core.data_update().unwrap();
+
+        let mut new_core = Core::with_backend(backend);
+
+        new_core.sync().unwrap();
+        new_core.unlock(password).unwrap();
+
+        assert_eq!(new_core.data.len(), 2);
+        assert!(new_core.data.iter().all(|elem| !elem.get_id().is_nil()));
+        assert!(new_core.get_element(&login).is_some());
+    }
+
+    #[test]
+    fn test_change_metadata() {
+        let backend = MemoryBackend::new();
+        let mut core = test_core_on(backend.clone());
+
+        let device = *core.get_device();
+        let form = Element {
+            created: 42,
+            ..Default::default()
+        };
+        let first = core.add_element(Categories::Login(form)).unwrap();
+        let second = core
+            .add_element(Categories::Login(Element::default()))
+            .unwrap();
+        let created = core.get_element(&first).unwrap().get_value().created;
+
+        assert!(!device.is_nil());
+        assert_ne!(created, 42);
+        assert_eq!(core.get_element(&first).unwrap().get_value().device, device);
+
+        core.update_element(&first, Element::default()).unwrap();
+        core.touch_element(&second).unwrap();
+
+        let elem = core.get_element(&first).unwrap().get_value();
+
+        assert_eq!(elem.created, created);
+        assert!(elem.updated >= created);
+        assert_eq!(elem.accessed, 0);
+        assert!(core.get_element(&second).unwrap().get_value().accessed > 0);
+
+        let mut records = core.data.clone();
+
+        SortOrder::RecentlyUsed.sort(&mut records);
+        assert_eq!(records[0].get_id(), &second);
+
+        let mut new_core = Core::with_backend(backend);
+
+        new_core.sync().unwrap();
+        assert_eq!(new_core.get_device(), &device);
+    }
+
+    #[test]
+    fn test_password_history() {
+        let mut core = test_core();
+        let with_password = |value: &str| Element {
+            fields: vec![Item {
+                title: "password".to_string(),
+                value: value.to_string(),
+                hide: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let values = |core: &Core, id: &Uuid| -> Vec<String> {
+            let item = core
+                .get_element(id)
+                .unwrap()
+                .get_value()
+                .get_field(0)
+                .unwrap();
+
+            item.history.iter().map(|h| h.value.clone()).collect()
+        };
+
+        let id = core
+            .add_element(Categories::Login(with_password("a")))
+            .unwrap();
+
+        for value in ["b", "c"] {
+            let mut element = core.get_element(&id).unwrap().get_value().clone();
+
+            element.fields[0].value = value.to_string();
+            core.update_element(&id, element).unwrap();
+        }
+
+        assert_eq!(values(&core, &id), vec!["b", "a"]);
+
+        core.restore_history(&id, 0, 1).unwrap();
+
+        let element = core.get_element(&id).unwrap().get_value();
+
+        assert_eq!(element.fields[0].value, "a");
+        assert_eq!(values(&core, &id), vec!["c", "b"]);
+        assert!(core.restore_history(&id, 0, 5).is_err());
+
+        core.set_history_depth(1).unwrap();
+        core.update_element(&id, with_password("d")).unwrap();
+
+        assert_eq!(values(&core, &id), vec!["a"]);
+        assert_eq!(core.state.settings.records.history_depth, 1);
+    }
+
+    #[test]
+    fn test_trash() {
+        let password = "password";
+        let backend = MemoryBackend::new();
+        let mut core = test_core_on(backend.clone());
+
+        let first = core
+            .add_element(Categories::Login(Element::default()))
+            .unwrap();
+        let second = core
+            .add_element(Categories::Login(Element::default()))
+            .unwrap();
+
+        core.remove_element(&first).unwrap();
+        core.remove_element(&second).unwrap();
+
+        assert!(core.data.is_empty());
+        assert_eq!(core.get_trash().len(), 2);
+
+        core.restore_element(&first).unwrap();
+        core.purge_element(&second).unwrap();
+
+        assert!(core.get_element(&first).is_some());
+        assert!(core.get_trash().is_empty());
+        assert!(core.restore_element(&second).is_err());
+
+        core.remove_element(&first).unwrap();
+        core.trash[0].deleted -= 31 * 24 * 60 * 60;
+        core.set_trash_retention(0).unwrap();
+        core.data_update().unwrap();
+
+        let mut new_core = Core::with_backend(backend);
+
+        new_core.sync().unwrap();
+        new_core.unlock(password).unwrap();
+
+        // a zero retention keeps the trash forever.
+        assert_eq!(new_core.get_trash().len(), 1);
+
+        new_core.set_trash_retention(30).unwrap();
+        new_core.unlock(password).unwrap();
+
+        assert!(new_core.get_trash().is_empty());
+        assert!(new_core.data.is_empty());
+    }
+
+    #[test]
+    fn test_verify_repair() {
+        let password = "password";
+        let backend = MemoryBackend::new();
+        let mut core = test_core_on(backend.clone());
+
+        core.add_element(Categories::Login(Element::default()))
+            .unwrap();
+        core.add_element(Categories::Other(Element::default()))
+            .unwrap();
+
+        let report = core.verify(password).unwrap();
+
+        assert!(report.is_ok());
+        assert_eq!(report.records, 2);
+        assert!(report.intact_snapshots > 0);
+        assert!(!core.verify("invalid password").unwrap().is_ok());
+
+        let raw = backend.read(SLED_STATE_KEY).unwrap().unwrap();
+        let broken = String::from_utf8(raw)
+            .unwrap()
+            .replace("\"inited\":true", "\"inited\":false");
+
+        backend.write(SLED_STATE_KEY, broken.as_bytes()).unwrap();
+
+        // a restart must not write a fresh state over the broken one.
+        let mut core = Core::with_backend(backend.clone());
+
+        assert_eq!(core.sync(), Err(ZebraErrors::StorageHashsumError));
+        assert_eq!(
+            backend.read(SLED_STATE_KEY).unwrap().unwrap(),
+            broken.as_bytes()
+        );
+
+        let report = core.verify(password).unwrap();
+
+        assert!(report.envelope.is_passed());
+        assert_eq!(
+            report.hashsum,
+            verify::Check::Failed(ZebraErrors::StorageHashsumError)
+        );
+
+        let report = core.repair(password, None).unwrap();
+
+        assert!(report.is_ok());
+        assert_eq!(report.records, 1);
+        assert_eq!(core.data.len(), 1);
+
+        let kept = core.db.snapshots(SLED_BROKEN_KEY).unwrap();
+
+        assert_eq!(kept.len(), 1);
+        assert_eq!(core.db.get_raw(&kept[0]).unwrap(), broken.as_bytes());
+    }
+
+    #[test]
+    fn test_destroy_vault() {
+        let mut rng = rand::thread_rng();
+        let m = Mnemonic::gen(&mut rng, 12, Language::English).unwrap();
+        let password = "password";
+        let path = std::env::temp_dir().join(format!("zebra_destroy_{}", std::process::id()));
+        let mut core = Core::with_backend(FileBackend::new(&path).unwrap());
+
+        core.sync().unwrap();
+        core.init_data(false, "", password, "", &m).unwrap();
+        core.add_element(Categories::Login(Element::default()))
+            .unwrap();
+
+        assert_eq!(
+            core.destroy_vault("invalid password"),
+            Err(ZebraErrors::GuardInvalidPassword)
+        );
+        assert!(core.is_unlock());
+
+        core.destroy_vault(password).unwrap();
+
+        assert!(!core.is_unlock());
+        assert!(!core.state.inited);
+        assert!(core.data.is_empty());
+
+        drop(core);
+
+        let mut new_core = Core::with_backend(FileBackend::new(&path).unwrap());
+
+        new_core.sync().unwrap();
+        assert!(!new_core.state.inited);
+        new_core.db.destroy().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_touch_element() {
+        let mut core = test_core();
+        let id = core
+            .add_element(Categories::Login(Element::default()))
+            .unwrap();
+        let snapshots = core.db.snapshots(SLED_SNAPSHOT_KEY).unwrap();
+
+        for _ in 0..10 {
+            core.touch_element(&id).unwrap();
+        }
+
+        assert_eq!(core.db.snapshots(SLED_SNAPSHOT_KEY).unwrap(), snapshots);
+
+        let accessed = core.get_element(&id).unwrap().get_value().accessed;
+
+        core.lock();
+        core.unlock("password").unwrap();
+
+        assert!(accessed > 0);
+        assert_eq!(
+            core.get_element(&id).unwrap().get_value().accessed,
+            accessed
+        );
+    }
+
+    #[test]
+    fn test_move_vault_location() {
+        let mut core = test_core();
+        let path = std::env::temp_dir().join(format!("zebra_move_{}", std::process::id()));
+
+        core.location = VaultLocation::Portable;
+
+        assert_eq!(
+            core.move_vault(&path),
+            Err(ZebraErrors::StorageMoveNotAllowed)
+        );
+        assert!(!path.exists());
+        assert!(core.db.get_path().is_none());
+
+        core.location = VaultLocation::Custom("/nonexistent/zebra".into());
+        core.move_vault(&path).unwrap();
+
+        assert_eq!(core.location, VaultLocation::Custom(path.clone()));
+        assert_eq!(core.db.get_path(), Some(path.as_path()));
+
+        core.lock();
+        core.unlock("password").unwrap();
+        core.db.destroy().unwrap();
+
+        assert!(!path.exists());
     }
 }
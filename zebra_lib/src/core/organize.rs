@@ -0,0 +1,308 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use serde::{Deserialize, Serialize};
+
+use super::record::{Categories, Uuid};
+use super::Core;
+use crate::errors::ZebraErrors;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct Tag {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct Folder {
+    pub id: Uuid,
+    pub name: String,
+    // `None` for top level folders.
+    pub parent: Option<Uuid>,
+}
+
+impl Core {
+    pub fn get_tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    pub fn get_folders(&self) -> &[Folder] {
+        &self.folders
+    }
+
+    pub fn create_tag(&mut self, name: &str) -> Result<Uuid, ZebraErrors> {
+        let name = valid_name(name)?;
+        let id = Uuid::new_v4();
+
+        self.tags.push(Tag { id, name });
+        self.update()?;
+
+        Ok(id)
+    }
+
+    pub fn rename_tag(&mut self, id: &Uuid, name: &str) -> Result<(), ZebraErrors> {
+        let name = valid_name(name)?;
+        let tag = self
+            .tags
+            .iter_mut()
+            .find(|tag| tag.id == *id)
+            .ok_or(ZebraErrors::CoreTagNotFound)?;
+
+        tag.name = name;
+        self.update()
+    }
+
+    // every record tagged by `from` gets `into`, then `from` is deleted.
+    pub fn merge_tags(&mut self, from: &Uuid, into: &Uuid) -> Result<(), ZebraErrors> {
+        if from == into {
+            return Ok(());
+        }
+
+        self.tag_position(into)?;
+
+        let index = self.tag_position(from)?;
+
+        self.tags.remove(index);
+
+        for record in self.all_records_mut() {
+            let tags = &mut record.get_value_mut().tags;
+
+            if let Some(pos) = tags.iter().position(|tag| tag == from) {
+                tags.remove(pos);
+
+                if !tags.contains(into) {
+                    tags.push(*into);
+                }
+            }
+        }
+
+        self.update()
+    }
+
+    pub fn delete_tag(&mut self, id: &Uuid) -> Result<(), ZebraErrors> {
+        let index = self.tag_position(id)?;
+
+        self.tags.remove(index);
+
+        for record in self.all_records_mut() {
+            record.get_value_mut().tags.retain(|tag| tag != id);
+        }
+
+        self.update()
+    }
+
+    pub fn create_folder(&mut self, name: &str, parent: Option<Uuid>) -> Result<Uuid, ZebraErrors> {
+        let name = valid_name(name)?;
+        let id = Uuid::new_v4();
+
+        if let Some(parent) = &parent {
+            self.folder_position(parent)?;
+        }
+
+        self.folders.push(Folder { id, name, parent });
+        self.update()?;
+
+        Ok(id)
+    }
+
+    pub fn rename_folder(&mut self, id: &Uuid, name: &str) -> Result<(), ZebraErrors> {
+        let name = valid_name(name)?;
+        let index = self.folder_position(id)?;
+
+        self.folders[index].name = name;
+        self.update()
+    }
+
+    // records and sub folders of `from` move into `into`, then `from` is deleted.
+    pub fn merge_folders(&mut self, from: &Uuid, into: &Uuid) -> Result<(), ZebraErrors> {
+        if from == into {
+            return Ok(());
+        }
+
+        self.folder_position(into)?;
+
+        if self.folder_descendants(from).contains(into) {
+            return Err(ZebraErrors::CoreFolderCycle);
+        }
+
+        self.replace_folder(from, Some(*into))
+    }
+
+    // records and sub folders go one level up.
+    pub fn delete_folder(&mut self, id: &Uuid) -> Result<(), ZebraErrors> {
+        let parent = self.folders[self.folder_position(id)?].parent;
+
+        self.replace_folder(id, parent)
+    }
+
+    // the folder itself and all folders nested into it.
+    pub fn folder_descendants(&self, id: &Uuid) -> Vec<Uuid> {
+        let mut found = vec![*id];
+        let mut index = 0;
+
+        while index < found.len() {
+            let current = found[index];
+
+            for folder in &self.folders {
+                if folder.parent == Some(current) && !found.contains(&folder.id) {
+                    found.push(folder.id);
+                }
+            }
+
+            index += 1;
+        }
+
+        found
+    }
+
+    // names from the top level folder down to `id`.
+    pub fn folder_path(&self, id: &Uuid) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut current = Some(*id);
+
+        while let Some(folder) = current.and_then(|id| self.folders.iter().find(|f| f.id == id)) {
+            if path.len() > self.folders.len() {
+                break;
+            }
+
+            path.insert(0, folder.name.as_str());
+            current = folder.parent;
+        }
+
+        path
+    }
+
+    fn replace_folder(&mut self, id: &Uuid, next: Option<Uuid>) -> Result<(), ZebraErrors> {
+        let index = self.folder_position(id)?;
+
+        self.folders.remove(index);
+
+        for folder in self.folders.iter_mut() {
+            if folder.parent == Some(*id) {
+                folder.parent = next;
+            }
+        }
+
+        for record in self.all_records_mut() {
+            let folder = &mut record.get_value_mut().folder;
+
+            if *folder == Some(*id) {
+                *folder = next;
+            }
+        }
+
+        self.update()
+    }
+
+    pub(super) fn all_records_mut(&mut self) -> impl Iterator<Item = &mut Categories> {
+        self.data
+            .iter_mut()
+            .chain(self.trash.iter_mut().map(|entry| &mut entry.record))
+    }
+
+    fn tag_position(&self, id: &Uuid) -> Result<usize, ZebraErrors> {
+        self.tags
+            .iter()
+            .position(|tag| tag.id == *id)
+            .ok_or(ZebraErrors::CoreTagNotFound)
+    }
+
+    fn folder_position(&self, id: &Uuid) -> Result<usize, ZebraErrors> {
+        self.folders
+            .iter()
+            .position(|folder| folder.id == *id)
+            .ok_or(ZebraErrors::CoreFolderNotFound)
+    }
+}
+
+pub(super) fn valid_name(name: &str) -> Result<String, ZebraErrors> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(ZebraErrors::CoreInvalidName);
+    }
+
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod organize_tests {
+    use super::*;
+    use crate::core::record::Element;
+    use crate::core::test_core;
+
+    #[test]
+    fn test_tags() {
+        let mut core = test_core();
+        let work = core.create_tag("work").unwrap();
+        let job = core.create_tag(" job ").unwrap();
+        let record = core
+            .add_element(Categories::Login(Element {
+                tags: vec![work, job],
+                ..Default::default()
+            }))
+            .unwrap();
+
+        assert_eq!(core.get_tags()[1].name, "job");
+        assert_eq!(core.create_tag("  "), Err(ZebraErrors::CoreInvalidName));
+
+        core.rename_tag(&job, "office").unwrap();
+        core.merge_tags(&job, &work).unwrap();
+
+        assert_eq!(core.get_tags().len(), 1);
+        assert_eq!(
+            core.get_element(&record).unwrap().get_value().tags,
+            vec![work]
+        );
+
+        core.remove_element(&record).unwrap();
+        core.delete_tag(&work).unwrap();
+
+        assert!(core.get_tags().is_empty());
+        assert!(core.get_trash()[0].record.get_value().tags.is_empty());
+        assert_eq!(core.delete_tag(&work), Err(ZebraErrors::CoreTagNotFound));
+    }
+
+    #[test]
+    fn test_folders() {
+        let mut core = test_core();
+        let root = core.create_folder("root", None).unwrap();
+        let child = core.create_folder("child", Some(root)).unwrap();
+        let leaf = core.create_folder("leaf", Some(child)).unwrap();
+        let other = core.create_folder("other", None).unwrap();
+        let record = core
+            .add_element(Categories::Login(Element {
+                folder: Some(child),
+                ..Default::default()
+            }))
+            .unwrap();
+
+        assert_eq!(core.folder_path(&leaf), vec!["root", "child", "leaf"]);
+        assert_eq!(core.folder_descendants(&root), vec![root, child, leaf]);
+        assert_eq!(
+            core.merge_folders(&root, &leaf),
+            Err(ZebraErrors::CoreFolderCycle)
+        );
+
+        core.delete_folder(&child).unwrap();
+
+        assert_eq!(core.folder_path(&leaf), vec!["root", "leaf"]);
+        assert_eq!(
+            core.get_element(&record).unwrap().get_value().folder,
+            Some(root)
+        );
+
+        core.merge_folders(&root, &other).unwrap();
+        core.rename_folder(&other, "merged").unwrap();
+
+        assert_eq!(core.folder_path(&leaf), vec!["merged", "leaf"]);
+        assert_eq!(
+            core.get_element(&record).unwrap().get_value().folder,
+            Some(other)
+        );
+        assert_eq!(
+            core.create_folder("x", Some(root)),
+            Err(ZebraErrors::CoreFolderNotFound)
+        );
+    }
+}
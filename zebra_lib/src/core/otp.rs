@@ -0,0 +1,415 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+
+use super::record::{ItemKind, Uuid};
+use super::Core;
+use crate::errors::ZebraErrors;
+
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const DEFAULT_PERIOD: u64 = 30;
+const DEFAULT_DIGITS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OtpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtpKind {
+    // RFC 6238, the counter is the unix time divided by the period.
+    Totp { period: u64 },
+    // RFC 4226, the counter is stored with the secret.
+    Hotp { counter: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtpAuth {
+    pub kind: OtpKind,
+    pub algorithm: OtpAlgorithm,
+    pub digits: u32,
+    pub secret: Vec<u8>,
+    pub issuer: String,
+    pub account: String,
+}
+
+impl OtpAuth {
+    // an otpauth:// link or a bare base32 secret, which is a TOTP with default settings.
+    pub fn parse(value: &str) -> Result<Self, ZebraErrors> {
+        let value = value.trim();
+        let rest = match value.strip_prefix("otpauth://") {
+            Some(rest) => rest,
+            None => {
+                return Ok(Self {
+                    kind: OtpKind::Totp {
+                        period: DEFAULT_PERIOD,
+                    },
+                    algorithm: OtpAlgorithm::Sha1,
+                    digits: DEFAULT_DIGITS,
+                    secret: base32_decode(value).ok_or(ZebraErrors::CoreInvalidTotp)?,
+                    issuer: String::new(),
+                    account: String::new(),
+                })
+            }
+        };
+        let (kind, rest) = rest.split_once('/').ok_or(ZebraErrors::CoreInvalidTotp)?;
+        let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let label = percent_decode(label);
+        let (mut issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (issuer.trim().to_string(), account.trim().to_string()),
+            None => (String::new(), label.trim().to_string()),
+        };
+        let mut secret = None;
+        let mut algorithm = OtpAlgorithm::Sha1;
+        let mut digits = DEFAULT_DIGITS;
+        let mut period = DEFAULT_PERIOD;
+        let mut counter = None;
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value);
+
+            match key.to_lowercase().as_str() {
+                "secret" => secret = base32_decode(&value),
+                "issuer" => issuer = value,
+                "algorithm" => {
+                    algorithm = match value.to_uppercase().as_str() {
+                        "SHA1" => OtpAlgorithm::Sha1,
+                        "SHA256" => OtpAlgorithm::Sha256,
+                        "SHA512" => OtpAlgorithm::Sha512,
+                        _ => return Err(ZebraErrors::CoreInvalidTotp),
+                    }
+                }
+                "digits" => digits = value.parse().or(Err(ZebraErrors::CoreInvalidTotp))?,
+                "period" => period = value.parse().or(Err(ZebraErrors::CoreInvalidTotp))?,
+                "counter" => counter = Some(value.parse().or(Err(ZebraErrors::CoreInvalidTotp))?),
+                _ => {}
+            }
+        }
+
+        let kind = match (kind.to_lowercase().as_str(), counter) {
+            ("totp", _) => OtpKind::Totp { period },
+            ("hotp", Some(counter)) => OtpKind::Hotp { counter },
+            _ => return Err(ZebraErrors::CoreInvalidTotp),
+        };
+
+        if !(6..=8).contains(&digits) || period == 0 {
+            return Err(ZebraErrors::CoreInvalidTotp);
+        }
+
+        Ok(Self {
+            kind,
+            algorithm,
+            digits,
+            secret: secret.ok_or(ZebraErrors::CoreInvalidTotp)?,
+            issuer,
+            account,
+        })
+    }
+
+    pub fn to_uri(&self) -> String {
+        let (kind, param) = match self.kind {
+            OtpKind::Totp { period } => ("totp", format!("period={}", period)),
+            OtpKind::Hotp { counter } => ("hotp", format!("counter={}", counter)),
+        };
+        let algorithm = match self.algorithm {
+            OtpAlgorithm::Sha1 => "SHA1",
+            OtpAlgorithm::Sha256 => "SHA256",
+            OtpAlgorithm::Sha512 => "SHA512",
+        };
+        let label = if self.issuer.is_empty() {
+            percent_encode(&self.account)
+        } else {
+            format!(
+                "{}:{}",
+                percent_encode(&self.issuer),
+                percent_encode(&self.account)
+            )
+        };
+        let mut uri = format!(
+            "otpauth://{}/{}?secret={}&algorithm={}&digits={}&{}",
+            kind,
+            label,
+            base32_encode(&self.secret),
+            algorithm,
+            self.digits,
+            param
+        );
+
+        if !self.issuer.is_empty() {
+            uri.push_str(&format!("&issuer={}", percent_encode(&self.issuer)));
+        }
+
+        uri
+    }
+
+    // the code for the moment `now`, HOTP ignores the time.
+    pub fn code(&self, now: i64) -> Result<String, ZebraErrors> {
+        let counter = match self.kind {
+            OtpKind::Totp { period } => now.max(0) as u64 / period,
+            OtpKind::Hotp { counter } => counter,
+        };
+
+        hotp(&self.secret, counter, self.digits, self.algorithm)
+    }
+
+    // seconds until the TOTP code changes.
+    pub fn remaining(&self, now: i64) -> Option<u64> {
+        match self.kind {
+            OtpKind::Totp { period } => Some(period - now.max(0) as u64 % period),
+            OtpKind::Hotp { .. } => None,
+        }
+    }
+}
+
+impl Core {
+    // returns the current HOTP code and moves the stored counter forward.
+    pub fn next_hotp(&mut self, id: &Uuid, field: usize) -> Result<String, ZebraErrors> {
+        let index = self.position(id)?;
+        let item = self.data[index]
+            .get_value_mut()
+            .get_field_mut(field)
+            .filter(|item| item.kind == ItemKind::Totp)
+            .ok_or(ZebraErrors::CoreElementNotFound)?;
+        let mut auth = OtpAuth::parse(&item.value)?;
+        let code = auth.code(0)?;
+
+        auth.kind = match auth.kind {
+            OtpKind::Hotp { counter } => OtpKind::Hotp {
+                counter: counter + 1,
+            },
+            OtpKind::Totp { .. } => return Err(ZebraErrors::CoreInvalidTotp),
+        };
+
+        item.value = auth.to_uri();
+        self.update()?;
+
+        Ok(code)
+    }
+}
+
+// RFC 4226 dynamic truncation of HMAC(secret, counter).
+pub fn hotp(
+    secret: &[u8],
+    counter: u64,
+    digits: u32,
+    algorithm: OtpAlgorithm,
+) -> Result<String, ZebraErrors> {
+    let message = counter.to_be_bytes();
+    let hash = match algorithm {
+        OtpAlgorithm::Sha1 => hmac::<Hmac<Sha1>>(secret, &message)?,
+        OtpAlgorithm::Sha256 => hmac::<Hmac<Sha256>>(secret, &message)?,
+        OtpAlgorithm::Sha512 => hmac::<Hmac<Sha512>>(secret, &message)?,
+    };
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    let code = binary as u64 % 10u64.pow(digits);
+
+    Ok(format!("{:0width$}", code, width = digits as usize))
+}
+
+fn hmac<M: Mac + hmac::digest::KeyInit>(
+    key: &[u8],
+    message: &[u8],
+) -> Result<Vec<u8>, ZebraErrors> {
+    let mut mac = <M as Mac>::new_from_slice(key).or(Err(ZebraErrors::CoreInvalidTotp))?;
+
+    mac.update(message);
+
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+// RFC 4648 without padding, lowercase and spaces are accepted.
+pub fn base32_decode(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in value.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let c = c.to_ascii_uppercase() as u8;
+        let index = BASE32_ALPHABET.iter().position(|a| *a == c)? as u32;
+
+        buffer = (buffer << 5) | index;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    (!bytes.is_empty()).then_some(bytes)
+}
+
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+
+        buffer &= (1 << bits) - 1;
+    }
+
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    out
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                out.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod otp_tests {
+    use super::*;
+    use crate::core::record::{Categories, Element, Item};
+    use crate::core::test_core;
+
+    #[test]
+    fn test_rfc_vectors() {
+        // RFC 4226 appendix D.
+        let secret = b"12345678901234567890";
+        let hotp_codes = ["755224", "287082", "359152", "969429", "338314"];
+
+        for (counter, code) in hotp_codes.iter().enumerate() {
+            assert_eq!(
+                hotp(secret, counter as u64, 6, OtpAlgorithm::Sha1).unwrap(),
+                *code
+            );
+        }
+
+        // RFC 6238 appendix B, the seed is repeated up to the hash size.
+        let sha256 = b"12345678901234567890123456789012";
+        let sha512 = b"1234567890123456789012345678901234567890123456789012345678901234";
+        let cases = [
+            (59, OtpAlgorithm::Sha1, &secret[..], "94287082"),
+            (59, OtpAlgorithm::Sha256, &sha256[..], "46119246"),
+            (59, OtpAlgorithm::Sha512, &sha512[..], "90693936"),
+            (1111111109, OtpAlgorithm::Sha1, &secret[..], "07081804"),
+            (20000000000, OtpAlgorithm::Sha512, &sha512[..], "47863826"),
+        ];
+
+        for (time, algorithm, secret, code) in cases {
+            let auth = OtpAuth {
+                kind: OtpKind::Totp { period: 30 },
+                algorithm,
+                digits: 8,
+                secret: secret.to_vec(),
+                issuer: String::new(),
+                account: String::new(),
+            };
+
+            assert_eq!(auth.code(time).unwrap(), code);
+        }
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let auth = OtpAuth::parse(
+            "otpauth://totp/ACME%20Co:john@example.com?secret=JBSWY3DPEHPK3PXP&issuer=ACME%20Co&algorithm=SHA256&digits=8&period=60",
+        )
+        .unwrap();
+
+        assert_eq!(auth.kind, OtpKind::Totp { period: 60 });
+        assert_eq!(auth.algorithm, OtpAlgorithm::Sha256);
+        assert_eq!(auth.digits, 8);
+        assert_eq!(auth.issuer, "ACME Co");
+        assert_eq!(auth.account, "john@example.com");
+        assert_eq!(auth.secret, b"Hello!\xde\xad\xbe\xef");
+        assert_eq!(auth.remaining(61), Some(59));
+        assert_eq!(OtpAuth::parse(&auth.to_uri()), Ok(auth));
+
+        let bare = OtpAuth::parse("jbsw y3dp ehpk 3pxp").unwrap();
+
+        assert_eq!(bare.kind, OtpKind::Totp { period: 30 });
+        assert_eq!(base32_encode(&bare.secret), "JBSWY3DPEHPK3PXP");
+
+        assert!(OtpAuth::parse("otpauth://hotp/x?secret=JBSWY3DPEHPK3PXP").is_err());
+        assert!(OtpAuth::parse("otpauth://totp/x?secret=JBSWY3DPEHPK3PXP&digits=5").is_err());
+        assert!(OtpAuth::parse("not base32!").is_err());
+    }
+
+    #[test]
+    fn test_next_hotp() {
+        let mut core = test_core();
+
+        let id = core
+            .add_element(Categories::Other(Element {
+                fields: vec![Item {
+                    kind: ItemKind::Totp,
+                    // base32 of the RFC 4226 secret.
+                    value: "otpauth://hotp/x?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&counter=0"
+                        .to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }))
+            .unwrap();
+
+        assert_eq!(core.next_hotp(&id, 0).unwrap(), "755224");
+        assert_eq!(core.next_hotp(&id, 0).unwrap(), "287082");
+        assert!(core.get_element(&id).unwrap().get_value().fields[0]
+            .value
+            .contains("counter=2"));
+    }
+}
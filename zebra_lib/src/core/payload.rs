@@ -0,0 +1,70 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use super::category::CustomCategory;
+use super::organize::{Folder, Tag};
+use super::record::Categories;
+use crate::errors::ZebraErrors;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct TrashEntry {
+    pub record: Categories,
+    // when the record was moved to the trash.
+    pub deleted: i64,
+}
+
+// Everything what lives inside of the encrypted data store.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Payload<'a> {
+    pub records: Cow<'a, [Categories]>,
+    #[serde(default)]
+    pub trash: Cow<'a, [TrashEntry]>,
+    #[serde(default)]
+    pub tags: Cow<'a, [Tag]>,
+    #[serde(default)]
+    pub folders: Cow<'a, [Folder]>,
+    #[serde(default)]
+    pub categories: Cow<'a, [CustomCategory]>,
+}
+
+impl<'a> Payload<'a> {
+    // vaults written before the payload existed keep a bare list of records.
+    pub fn from_slice(bytes: &[u8]) -> Result<Payload<'static>, ZebraErrors> {
+        if let Ok(payload) = serde_json::from_slice::<Payload<'static>>(bytes) {
+            return Ok(payload);
+        }
+
+        let records: Vec<Categories> =
+            serde_json::from_slice(bytes).or(Err(ZebraErrors::StorageDataBroken))?;
+
+        Ok(Payload {
+            records: Cow::Owned(records),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod payload_tests {
+    use super::*;
+    use crate::core::record::Element;
+
+    #[test]
+    fn test_legacy_records() {
+        let records = vec![Categories::Login(Element::default())];
+        let legacy = serde_json::to_vec(&records).unwrap();
+        let payload = Payload::from_slice(&legacy).unwrap();
+
+        assert_eq!(payload.records.as_ref(), records.as_slice());
+        assert!(payload.trash.is_empty());
+
+        let json = serde_json::to_vec(&payload).unwrap();
+
+        assert_eq!(Payload::from_slice(&json).unwrap().records, payload.records);
+        assert!(Payload::from_slice(b"{}").is_err());
+    }
+}
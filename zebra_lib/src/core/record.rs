@@ -3,25 +3,117 @@
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
 use serde::{Deserialize, Serialize};
 
+pub use uuid::Uuid;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct HistoryEntry {
+    pub value: String,
+    // when the value was replaced.
+    pub changed: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ItemKind {
+    #[default]
+    Text,
+    Password,
+    Url,
+    Email,
+    Phone,
+    Date,
+    Multiline,
+    Number,
+    Totp,
+    File,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
 pub struct Item {
+    // id of the template field, empty for custom fields.
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub kind: ItemKind,
     pub title: String,
     pub value: String,
     pub hide: bool,
     pub copy: bool,
     pub reload: bool,
+    // previous values of a hidden field, newest first.
+    #[serde(default)]
+    pub history: Vec<HistoryEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub struct ExpiryDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+pub enum UriMatch {
+    // same registrable domain, "a.example.co.uk" fits "example.co.uk".
+    #[default]
+    BaseDomain,
+    Host,
+    StartsWith,
+    Regex,
+    Never,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct LoginUri {
+    pub uri: String,
+    #[serde(default)]
+    pub mode: UriMatch,
+}
+
+// a file kept encrypted under its own storage key, see `attachment::Core::add_attachment`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub name: String,
+    // size of the plain file.
+    pub size: u64,
+    pub created: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
 pub struct Element {
+    // records written before ids existed get one on unlock.
+    #[serde(default)]
+    pub id: Uuid,
     pub icon: String,
     pub created: i64,
     pub updated: i64,
+    // last time a secret of the record was copied.
+    #[serde(default)]
+    pub accessed: i64,
+    // device which made the last change.
+    #[serde(default)]
+    pub device: Uuid,
     pub favourite: bool,
     pub note: String,
     pub name: String,
     pub fields: Vec<Item>,
     pub extra_fields: Vec<Item>,
+    // ids of `organize::Tag`.
+    #[serde(default)]
+    pub tags: Vec<Uuid>,
+    #[serde(default)]
+    pub folder: Option<Uuid>,
+    // websites of the record, see `uri::LoginUri::matches`.
+    #[serde(default)]
+    pub uris: Vec<LoginUri>,
+    // filled from the expiry field of documents and cards, see `expiry::Element::sync_expiry`.
+    #[serde(default)]
+    pub expires: Option<ExpiryDate>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    // hidden values and editing ask for the master password again, see `reprompt`.
+    #[serde(default)]
+    pub reprompt: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -35,7 +127,56 @@ pub enum Categories {
     Passport(Element),
     DriverLicense(Element),
     WifiPassword(Element),
+    SshKey(Element),
     Other(Element),
+    // a user defined category, the id of `category::CustomCategory`.
+    Custom(Uuid, Element),
+}
+
+impl Element {
+    // `fields` and `extra_fields` are addressed as one list.
+    pub fn get_field(&self, index: usize) -> Option<&Item> {
+        self.fields
+            .iter()
+            .chain(self.extra_fields.iter())
+            .nth(index)
+    }
+
+    pub fn get_field_mut(&mut self, index: usize) -> Option<&mut Item> {
+        self.fields
+            .iter_mut()
+            .chain(self.extra_fields.iter_mut())
+            .nth(index)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SortOrder {
+    #[default]
+    Added,
+    RecentlyUsed,
+    RecentlyModified,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 3] = [
+        SortOrder::Added,
+        SortOrder::RecentlyUsed,
+        SortOrder::RecentlyModified,
+    ];
+
+    // `Added` keeps the order of the vault, the rest put the newest first.
+    pub fn sort(&self, records: &mut [Categories]) {
+        match self {
+            SortOrder::Added => {}
+            SortOrder::RecentlyUsed => {
+                records.sort_by_key(|elem| std::cmp::Reverse(elem.get_value().accessed))
+            }
+            SortOrder::RecentlyModified => {
+                records.sort_by_key(|elem| std::cmp::Reverse(elem.get_value().updated))
+            }
+        }
+    }
 }
 
 impl Categories {
@@ -50,7 +191,30 @@ impl Categories {
             Categories::Passport(_) => Categories::Passport(new_element),
             Categories::DriverLicense(_) => Categories::DriverLicense(new_element),
             Categories::WifiPassword(_) => Categories::WifiPassword(new_element),
+            Categories::SshKey(_) => Categories::SshKey(new_element),
             Categories::Other(_) => Categories::Other(new_element),
+            Categories::Custom(id, _) => Categories::Custom(*id, new_element),
+        }
+    }
+
+    pub fn get_id(&self) -> &Uuid {
+        &self.get_value().id
+    }
+
+    pub fn get_value_mut(&mut self) -> &mut Element {
+        match self {
+            Categories::Login(v) => v,
+            Categories::CreditCard(v) => v,
+            Categories::CryptoWallet(v) => v,
+            Categories::Identity(v) => v,
+            Categories::BankAccount(v) => v,
+            Categories::EmailAccount(v) => v,
+            Categories::Passport(v) => v,
+            Categories::DriverLicense(v) => v,
+            Categories::WifiPassword(v) => v,
+            Categories::SshKey(v) => v,
+            Categories::Other(v) => v,
+            Categories::Custom(_, v) => v,
         }
     }
 
@@ -65,7 +229,9 @@ impl Categories {
             Categories::Passport(v) => v,
             Categories::DriverLicense(v) => v,
             Categories::WifiPassword(v) => v,
+            Categories::SshKey(v) => v,
             Categories::Other(v) => v,
+            Categories::Custom(_, v) => v,
         }
     }
 }
@@ -82,7 +248,9 @@ impl std::fmt::Display for Categories {
             Categories::Passport(_) => "passport",
             Categories::DriverLicense(_) => "driver_license",
             Categories::WifiPassword(_) => "wifi_password",
+            Categories::SshKey(_) => "ssh_key",
             Categories::Other(_) => "other",
+            Categories::Custom(_, _) => "custom",
         };
         write!(f, "{}", text)
     }
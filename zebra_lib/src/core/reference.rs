@@ -0,0 +1,212 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::fmt;
+
+use super::record::{Element, Item, Uuid};
+use super::Core;
+use crate::errors::ZebraErrors;
+
+const REFERENCE_PREFIX: &str = "{ref:";
+const REFERENCE_SUFFIX: &str = "}";
+
+// a value taken from a field of another record, "{ref:<uuid>:password}".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldReference {
+    pub id: Uuid,
+    // the key of a template field or the title of a custom one.
+    pub field: String,
+}
+
+impl FieldReference {
+    pub fn new(id: Uuid, field: &str) -> Self {
+        Self {
+            id,
+            field: field.to_string(),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let inner = value
+            .trim()
+            .strip_prefix(REFERENCE_PREFIX)?
+            .strip_suffix(REFERENCE_SUFFIX)?;
+        let (id, field) = inner.split_once(':')?;
+        let id = Uuid::parse_str(id.trim()).ok()?;
+        let field = field.trim();
+
+        if field.is_empty() {
+            return None;
+        }
+
+        Some(Self::new(id, field))
+    }
+}
+
+impl fmt::Display for FieldReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}:{}{}",
+            REFERENCE_PREFIX, self.id, self.field, REFERENCE_SUFFIX
+        )
+    }
+}
+
+impl Item {
+    pub fn reference(&self) -> Option<FieldReference> {
+        FieldReference::parse(&self.value)
+    }
+}
+
+impl Element {
+    // template fields go by key, custom ones by title.
+    pub fn find_field(&self, name: &str) -> Option<&Item> {
+        self.fields
+            .iter()
+            .find(|item| !item.key.is_empty() && item.key == name)
+            .or_else(|| {
+                self.fields
+                    .iter()
+                    .chain(self.extra_fields.iter())
+                    .find(|item| item.title.trim().eq_ignore_ascii_case(name))
+            })
+    }
+}
+
+impl Core {
+    // the value `item` stands for, references to references are followed.
+    pub fn resolve_value(&self, item: &Item) -> Result<String, ZebraErrors> {
+        let mut seen: Vec<FieldReference> = Vec::new();
+        let mut current = item;
+
+        while let Some(reference) = current.reference() {
+            if seen.contains(&reference) {
+                return Err(ZebraErrors::CoreReferenceCycle);
+            }
+
+            current = self
+                .get_element(&reference.id)
+                .and_then(|record| record.get_value().find_field(&reference.field))
+                .ok_or(ZebraErrors::CoreReferenceNotFound)?;
+            seen.push(reference);
+        }
+
+        Ok(current.value.clone())
+    }
+
+    // a copy to show or copy from, broken references are kept as they are.
+    pub fn resolve_element(&self, element: &Element) -> Element {
+        let mut resolved = element.clone();
+
+        for item in resolved
+            .fields
+            .iter_mut()
+            .chain(resolved.extra_fields.iter_mut())
+        {
+            if item.reference().is_none() {
+                continue;
+            }
+
+            if let Ok(value) = self.resolve_value(item) {
+                item.value = value;
+            }
+        }
+
+        resolved
+    }
+}
+
+#[cfg(test)]
+mod reference_tests {
+    use super::*;
+    use crate::core::record::{Categories, ItemKind};
+    use crate::core::test_core;
+
+    fn login(username: &str, password: &str) -> Categories {
+        Categories::Login(Element {
+            fields: vec![
+                Item {
+                    key: "username".to_string(),
+                    value: username.to_string(),
+                    ..Default::default()
+                },
+                Item {
+                    key: "password".to_string(),
+                    kind: ItemKind::Password,
+                    value: password.to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        let id = Uuid::new_v4();
+        let reference = FieldReference::new(id, "password");
+
+        assert_eq!(
+            FieldReference::parse(&reference.to_string()),
+            Some(reference)
+        );
+        assert_eq!(FieldReference::parse(&format!("{{ref:{}:}}", id)), None);
+        assert_eq!(FieldReference::parse("{ref:nope:password}"), None);
+        assert_eq!(FieldReference::parse("hunter2"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut core = test_core();
+
+        let admin = core.add_element(login("root", "s3cret")).unwrap();
+        let by_ref = |id: Uuid, field: &str| FieldReference::new(id, field).to_string();
+        let server = core
+            .add_element(login(
+                &by_ref(admin, "username"),
+                &by_ref(admin, "password"),
+            ))
+            .unwrap();
+        // a reference to a reference.
+        let backup = core
+            .add_element(login("backup", &by_ref(server, "password")))
+            .unwrap();
+
+        let element = core.get_element(&backup).unwrap().get_value();
+        let resolved = core.resolve_element(element);
+
+        assert_eq!(core.resolve_value(&element.fields[1]).unwrap(), "s3cret");
+        assert_eq!(resolved.fields[0].value, "backup");
+        assert_eq!(resolved.fields[1].value, "s3cret");
+        // the record itself keeps the reference.
+        assert_eq!(element.fields[1].reference().unwrap().id, server);
+
+        // the admin password now points back at the backup.
+        let mut changed = core.get_element(&admin).unwrap().get_value().clone();
+
+        changed.fields[1].value = by_ref(backup, "password");
+        core.update_element(&admin, changed).unwrap();
+
+        let element = core.get_element(&server).unwrap().get_value();
+
+        assert_eq!(
+            core.resolve_value(&element.fields[1]),
+            Err(ZebraErrors::CoreReferenceCycle)
+        );
+        assert_eq!(core.resolve_value(&element.fields[0]).unwrap(), "root");
+
+        core.remove_element(&admin).unwrap();
+
+        let element = core.get_element(&server).unwrap().get_value();
+
+        assert_eq!(
+            core.resolve_value(&element.fields[0]),
+            Err(ZebraErrors::CoreReferenceNotFound)
+        );
+        assert_eq!(
+            core.resolve_element(element).fields[0].value,
+            by_ref(admin, "username")
+        );
+    }
+}
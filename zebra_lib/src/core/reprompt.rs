@@ -0,0 +1,187 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use ntrulp::params::params1277::{PUBLICKEYS_BYTES, SECRETKEYS_BYTES};
+
+use super::record::{Element, Item};
+use super::Core;
+use crate::errors::ZebraErrors;
+use crate::keychain::keys::{KeyChain, AES_KEY_SIZE};
+
+impl Element {
+    // a copy to show until the master password is entered again,
+    // without the hidden values and the ones taken from other records.
+    pub fn masked(&self) -> Element {
+        let mut masked = self.clone();
+
+        for item in masked
+            .fields
+            .iter_mut()
+            .chain(masked.extra_fields.iter_mut())
+            .filter(|item| item.hide || item.reference().is_some())
+        {
+            item.value = String::new();
+            item.history = Vec::new();
+        }
+
+        masked
+    }
+}
+
+impl Core {
+    // the key store opens with the password keys, the session is left as it is.
+    pub fn check_password(&self, password: &str) -> Result<(), ZebraErrors> {
+        let keys = self.keys.as_ref().ok_or(ZebraErrors::GuardIsNotEnable)?;
+        let cipher = &self.state.settings.cipher;
+        let pass_keys = KeyChain::from_pass(password.as_bytes(), cipher.difficulty)
+            .or(Err(ZebraErrors::GuardInvalidPassword))?;
+        let session = pass_keys
+            .decrypt(&self.state.secure_key_store, &cipher.cipher_orders)
+            .or(Err(ZebraErrors::GuardInvalidPassword))?;
+
+        // aes alone has no integrity check, a wrong password gives noise instead of an error.
+        if session.len() != AES_KEY_SIZE + PUBLICKEYS_BYTES + SECRETKEYS_BYTES
+            || session[..AES_KEY_SIZE] != keys.aes_key
+        {
+            return Err(ZebraErrors::GuardInvalidPassword);
+        }
+
+        Ok(())
+    }
+
+    // a record borrowing a value from a guarded one, even through other records, is guarded as well.
+    pub fn needs_reprompt(&self, element: &Element) -> bool {
+        element.reprompt
+            || element
+                .fields
+                .iter()
+                .chain(element.extra_fields.iter())
+                .any(|item| self.reaches_reprompt(item))
+    }
+
+    // follows references the way `resolve_value` does.
+    fn reaches_reprompt(&self, item: &Item) -> bool {
+        let mut seen = Vec::new();
+        let mut current = item;
+
+        while let Some(reference) = current.reference() {
+            if seen.contains(&reference) {
+                return false;
+            }
+
+            let record = match self.get_element(&reference.id) {
+                Some(record) => record.get_value(),
+                None => return false,
+            };
+
+            if record.reprompt {
+                return true;
+            }
+
+            current = match record.find_field(&reference.field) {
+                Some(item) => item,
+                None => return false,
+            };
+            seen.push(reference);
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod reprompt_tests {
+    use super::*;
+    use crate::bip39::mnemonic::{Language, Mnemonic};
+    use crate::core::record::{Categories, ItemKind};
+    use crate::core::reference::FieldReference;
+    use crate::core::test_core;
+    use crate::keychain::keys::CipherOrders;
+    use crate::storage::memory_backend::MemoryBackend;
+
+    fn secret(value: &str) -> Item {
+        Item {
+            key: "password".to_string(),
+            kind: ItemKind::Password,
+            value: value.to_string(),
+            hide: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reprompt() {
+        let mut core = test_core();
+
+        assert!(core.check_password("password").is_ok());
+        assert_eq!(
+            core.check_password("hunter2"),
+            Err(ZebraErrors::GuardInvalidPassword)
+        );
+
+        let bank = core
+            .add_element(Categories::BankAccount(Element {
+                name: "Bank".to_string(),
+                fields: vec![secret("1234")],
+                extra_fields: vec![Item {
+                    title: "Branch".to_string(),
+                    value: "Main st.".to_string(),
+                    ..Default::default()
+                }],
+                reprompt: true,
+                ..Default::default()
+            }))
+            .unwrap();
+        let element = core.get_element(&bank).unwrap().get_value();
+        let masked = element.masked();
+
+        assert!(core.needs_reprompt(element));
+        assert!(masked.fields[0].value.is_empty());
+        assert_eq!(masked.extra_fields[0].value, "Main st.");
+
+        // the pin taken by reference is guarded like the original.
+        let reference = FieldReference::new(bank, "password").to_string();
+        let login = Element {
+            fields: vec![secret(&reference)],
+            ..Default::default()
+        };
+
+        assert!(core.needs_reprompt(&login));
+        assert!(!core.needs_reprompt(&Element::default()));
+
+        // two hops: a plain login takes the password of a plain one which takes the pin.
+        let middle = core
+            .add_element(Categories::Login(Element {
+                fields: vec![secret(&reference)],
+                ..Default::default()
+            }))
+            .unwrap();
+        let outer = Element {
+            fields: vec![Item {
+                value: FieldReference::new(middle, "password").to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(core.needs_reprompt(&outer));
+        assert!(outer.masked().fields[0].value.is_empty());
+    }
+
+    #[test]
+    fn test_check_password_aes_only() {
+        let mut rng = rand::thread_rng();
+        let m = Mnemonic::gen(&mut rng, 12, Language::English).unwrap();
+        let mut core = Core::with_backend(MemoryBackend::new());
+
+        core.sync().unwrap();
+        core.state.settings.cipher.cipher_orders = vec![CipherOrders::AES256];
+        core.init_data(false, "", "password", "", &m).unwrap();
+
+        assert!(core.check_password("password").is_ok());
+        assert_eq!(
+            core.check_password("hunter2"),
+            Err(ZebraErrors::GuardInvalidPassword)
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use super::kind::url_host;
+use super::organize::Tag;
+use super::record::{Categories, Item, ItemKind, Uuid};
+use super::Core;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    Name,
+    Tag,
+    Url,
+    Value,
+    Title,
+    Note,
+}
+
+impl MatchKind {
+    fn weight(&self) -> u32 {
+        match self {
+            MatchKind::Name => 8,
+            MatchKind::Tag => 6,
+            MatchKind::Url => 5,
+            MatchKind::Value => 4,
+            MatchKind::Title => 3,
+            MatchKind::Note => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchHit {
+    pub id: Uuid,
+    pub score: u32,
+    // where the best match was found.
+    pub kind: MatchKind,
+}
+
+#[derive(Debug)]
+struct Term {
+    text: Vec<char>,
+    kind: MatchKind,
+    hidden: bool,
+}
+
+// lowercased copies of the searchable text, lives only while the vault is unlocked.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    entries: Vec<(Uuid, Vec<Term>)>,
+}
+
+impl SearchIndex {
+    pub fn build(records: &[Categories], tags: &[Tag]) -> Self {
+        let entries = records
+            .iter()
+            .map(|record| {
+                let elem = record.get_value();
+                let mut terms = vec![term(&elem.name, MatchKind::Name, false)];
+
+                for item in elem.fields.iter().chain(elem.extra_fields.iter()) {
+                    terms.push(term(&item.title, MatchKind::Title, false));
+                    terms.push(item_term(item));
+                }
+
+                // guarded secrets are not searchable, a hit would tell them apart.
+                if elem.reprompt {
+                    terms.retain(|term| !term.hidden);
+                }
+
+                for uri in &elem.uris {
+                    terms.push(uri_term(&uri.uri));
+                }
+
+                for tag in tags.iter().filter(|tag| elem.tags.contains(&tag.id)) {
+                    terms.push(term(&tag.name, MatchKind::Tag, false));
+                }
+
+                terms.push(term(&elem.note, MatchKind::Note, false));
+                terms.retain(|term| !term.text.is_empty());
+
+                (elem.id, terms)
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    // every word of the query has to match, the best scored records go first.
+    pub fn search(&self, query: &str, include_hidden: bool) -> Vec<SearchHit> {
+        let words: Vec<Vec<char>> = query
+            .split_whitespace()
+            .map(|word| word.to_lowercase().chars().collect())
+            .collect();
+
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<SearchHit> = self
+            .entries
+            .iter()
+            .filter_map(|(id, terms)| {
+                let mut score = 0;
+                let mut best = (0, MatchKind::Name);
+
+                for word in &words {
+                    let (word_score, kind) = terms
+                        .iter()
+                        .filter(|term| include_hidden || !term.hidden)
+                        .filter_map(|term| {
+                            fuzzy_score(word, &term.text)
+                                .map(|s| (s * term.kind.weight(), term.kind))
+                        })
+                        .max_by_key(|(s, _)| *s)?;
+
+                    if word_score > best.0 {
+                        best = (word_score, kind);
+                    }
+
+                    score += word_score;
+                }
+
+                Some(SearchHit {
+                    id: *id,
+                    score,
+                    kind: best.1,
+                })
+            })
+            .collect();
+
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+
+        hits
+    }
+}
+
+impl Core {
+    pub fn search(&self, query: &str, include_hidden: bool) -> Vec<SearchHit> {
+        self.index.search(query, include_hidden)
+    }
+
+    pub(super) fn reindex(&mut self) {
+        self.index = SearchIndex::build(&self.data, &self.tags);
+    }
+}
+
+// substrings beat scattered letters, matches at the start of a word beat the rest.
+pub fn fuzzy_score(pattern: &[char], text: &[char]) -> Option<u32> {
+    if pattern.is_empty() || pattern.len() > text.len() {
+        return None;
+    }
+
+    let is_boundary = |i: usize| i == 0 || !text[i - 1].is_alphanumeric();
+
+    if let Some(start) = text.windows(pattern.len()).position(|w| w == pattern) {
+        let bonus = if start == 0 {
+            40
+        } else if is_boundary(start) {
+            20
+        } else {
+            0
+        };
+        let exact = if pattern.len() == text.len() { 40 } else { 0 };
+
+        return Some(60 + bonus + exact);
+    }
+
+    let mut score = 0;
+    let mut last: Option<usize> = None;
+    let mut rest = pattern.iter().peekable();
+
+    for (i, c) in text.iter().enumerate() {
+        let Some(wanted) = rest.peek() else {
+            break;
+        };
+
+        if c != *wanted {
+            continue;
+        }
+
+        score += match last {
+            Some(prev) if prev + 1 == i => 3,
+            _ if is_boundary(i) => 2,
+            _ => 1,
+        };
+        last = Some(i);
+        rest.next();
+    }
+
+    if rest.peek().is_some() {
+        return None;
+    }
+
+    // keeps scattered matches below any substring match.
+    Some((score * 20 / pattern.len() as u32).min(59))
+}
+
+fn term(text: &str, kind: MatchKind, hidden: bool) -> Term {
+    Term {
+        text: text.trim().to_lowercase().chars().collect(),
+        kind,
+        hidden,
+    }
+}
+
+fn item_term(item: &Item) -> Term {
+    let value = item.value.trim();
+
+    // untyped fields count as urls only with a scheme.
+    let is_url = item.kind == ItemKind::Url || value.contains("://");
+
+    match url_host(value).filter(|_| is_url && !item.hide) {
+        Some(host) => term(
+            host.strip_prefix("www.").unwrap_or(host),
+            MatchKind::Url,
+            false,
+        ),
+        None => term(value, MatchKind::Value, item.hide),
+    }
+}
+
+// regex and prefix rules that are not urls are kept as they are.
+fn uri_term(uri: &str) -> Term {
+    let uri = uri.trim();
+    let text = url_host(uri).map_or(uri, |host| host.strip_prefix("www.").unwrap_or(host));
+
+    term(text, MatchKind::Url, false)
+}
+
+#[cfg(test)]
+mod search_tests {
+    use super::*;
+    use crate::core::record::{Element, LoginUri, UriMatch};
+    use crate::core::test_core;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn item(title: &str, value: &str, hide: bool) -> Item {
+        Item {
+            title: title.to_string(),
+            value: value.to_string(),
+            hide,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        let text = chars("my github account");
+
+        assert!(fuzzy_score(&chars("my"), &text) > fuzzy_score(&chars("git"), &text));
+        assert!(fuzzy_score(&chars("git"), &text) > fuzzy_score(&chars("hub"), &text));
+        assert!(fuzzy_score(&chars("hub"), &text) > fuzzy_score(&chars("gthb"), &text));
+        assert_eq!(fuzzy_score(&chars("xyz"), &text), None);
+        assert_eq!(fuzzy_score(&chars(""), &text), None);
+        assert_eq!(
+            item_term(&item("Site", "https://user@www.example.com:443/x", false)).kind,
+            MatchKind::Url
+        );
+        assert_eq!(
+            item_term(&item("Site", "example.com", false)).kind,
+            MatchKind::Value
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let mut core = test_core();
+
+        let work = core.create_tag("Work").unwrap();
+        let github = core
+            .add_element(Categories::Login(Element {
+                name: "GitHub".to_string(),
+                fields: vec![
+                    item("Domain", "https://github.com/login", false),
+                    item("Password", "hunter2", true),
+                ],
+                tags: vec![work],
+                ..Default::default()
+            }))
+            .unwrap();
+        let mail = core
+            .add_element(Categories::EmailAccount(Element {
+                name: "Mail".to_string(),
+                note: "recovery codes for github".to_string(),
+                ..Default::default()
+            }))
+            .unwrap();
+
+        let hits = core.search("github", false);
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].id, github);
+        assert_eq!(hits[0].kind, MatchKind::Name);
+        assert_eq!(hits[1].id, mail);
+        assert_eq!(hits[1].kind, MatchKind::Note);
+
+        assert!(core.search("hunter2", false).is_empty());
+        assert_eq!(core.search("hunter2", true)[0].id, github);
+        assert_eq!(core.search("work gh", false)[0].id, github);
+        assert!(core.search("  ", false).is_empty());
+
+        let gitea = core
+            .add_element(Categories::Login(Element {
+                name: "Code".to_string(),
+                uris: vec![LoginUri {
+                    uri: "https://www.gitea.io/user/login".to_string(),
+                    mode: UriMatch::BaseDomain,
+                }],
+                ..Default::default()
+            }))
+            .unwrap();
+        let hits = core.search("gitea.io", false);
+
+        assert_eq!(hits[0].id, gitea);
+        assert_eq!(hits[0].kind, MatchKind::Url);
+
+        let guarded = core
+            .add_element(Categories::Login(Element {
+                name: "Bank".to_string(),
+                fields: vec![item("Password", "swordfish", true)],
+                reprompt: true,
+                ..Default::default()
+            }))
+            .unwrap();
+
+        assert!(core.search("swordfish", true).is_empty());
+        assert_eq!(core.search("bank", true)[0].id, guarded);
+
+        core.remove_element(&github).unwrap();
+
+        assert_eq!(core.search("github", false).len(), 1);
+    }
+}
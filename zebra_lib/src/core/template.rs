@@ -0,0 +1,429 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use super::record::{Categories, Element, Item, ItemKind, LoginUri};
+use crate::errors::ZebraErrors;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Text,
+    CardNumber,
+    CardExpiry,
+    Cvv,
+    Iban,
+    Bic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldTemplate {
+    pub key: &'static str,
+    pub kind: ItemKind,
+    pub hide: bool,
+    pub copy: bool,
+    pub reload: bool,
+}
+
+const fn field(key: &'static str) -> FieldTemplate {
+    typed(key, ItemKind::Text)
+}
+
+const fn typed(key: &'static str, kind: ItemKind) -> FieldTemplate {
+    FieldTemplate {
+        key,
+        kind,
+        hide: false,
+        copy: true,
+        reload: false,
+    }
+}
+
+const fn secret(key: &'static str) -> FieldTemplate {
+    FieldTemplate {
+        key,
+        kind: ItemKind::Password,
+        hide: true,
+        copy: true,
+        reload: false,
+    }
+}
+
+// a secret which spans several lines.
+const fn secret_text(key: &'static str) -> FieldTemplate {
+    FieldTemplate {
+        kind: ItemKind::Multiline,
+        ..secret(key)
+    }
+}
+
+const EMAIL: FieldTemplate = typed("email", ItemKind::Email);
+const ISSUE_DATE: FieldTemplate = typed("issue_date", ItemKind::Date);
+const EXPIRY_DATE: FieldTemplate = typed("expiry_date", ItemKind::Date);
+const PASSWORD: FieldTemplate = FieldTemplate {
+    key: "password",
+    kind: ItemKind::Password,
+    hide: true,
+    copy: true,
+    reload: true,
+};
+
+const LOGIN: &[FieldTemplate] = &[
+    typed("domain", ItemKind::Url),
+    field("username"),
+    EMAIL,
+    PASSWORD,
+];
+const CRYPTO_WALLET: &[FieldTemplate] = &[field("address"), secret("seed_phrase"), PASSWORD];
+const CREDIT_CARD: &[FieldTemplate] = &[
+    field("card_holder"),
+    field("card_number"),
+    field("card_expiry"),
+    secret("cvv"),
+];
+const IDENTITY: &[FieldTemplate] = &[
+    field("first_name"),
+    field("last_name"),
+    typed("birth_date", ItemKind::Date),
+    EMAIL,
+    typed("phone", ItemKind::Phone),
+    field("address"),
+];
+const BANK_ACCOUNT: &[FieldTemplate] = &[
+    field("bank_name"),
+    field("account_holder"),
+    field("iban"),
+    field("bic"),
+];
+const EMAIL_ACCOUNT: &[FieldTemplate] =
+    &[EMAIL, PASSWORD, field("imap_server"), field("smtp_server")];
+const PASSPORT: &[FieldTemplate] = &[
+    field("full_name"),
+    field("passport_number"),
+    field("nationality"),
+    ISSUE_DATE,
+    EXPIRY_DATE,
+];
+const DRIVER_LICENSE: &[FieldTemplate] = &[
+    field("full_name"),
+    field("license_number"),
+    field("license_class"),
+    ISSUE_DATE,
+    EXPIRY_DATE,
+];
+const WIFI_PASSWORD: &[FieldTemplate] = &[field("ssid"), PASSWORD, field("security")];
+// the public part is filled from the private key, see `ssh::Element::sync_ssh_key`.
+const SSH_KEY: &[FieldTemplate] = &[
+    field("public_key"),
+    field("fingerprint"),
+    secret_text("private_key"),
+];
+
+impl Categories {
+    pub fn template(&self) -> &'static [FieldTemplate] {
+        match self {
+            Categories::Login(_) => LOGIN,
+            Categories::CryptoWallet(_) => CRYPTO_WALLET,
+            Categories::CreditCard(_) => CREDIT_CARD,
+            Categories::Identity(_) => IDENTITY,
+            Categories::BankAccount(_) => BANK_ACCOUNT,
+            Categories::EmailAccount(_) => EMAIL_ACCOUNT,
+            Categories::Passport(_) => PASSPORT,
+            Categories::DriverLicense(_) => DRIVER_LICENSE,
+            Categories::WifiPassword(_) => WIFI_PASSWORD,
+            Categories::SshKey(_) => SSH_KEY,
+            // see `category::CustomCategory::template`.
+            Categories::Other(_) | Categories::Custom(_, _) => &[],
+        }
+    }
+
+    // fields saved before template keys existed get theirs back by title.
+    pub fn restore_keys(&mut self) -> bool {
+        let template = self.template();
+        let elem = self.get_value_mut();
+        let mut changed = false;
+
+        for tpl in template {
+            if elem.fields.iter().any(|item| item.key == tpl.key) {
+                continue;
+            }
+
+            let found = elem
+                .fields
+                .iter_mut()
+                .find(|item| item.key.is_empty() && title_key(&item.title) == tpl.key);
+
+            if let Some(item) = found {
+                item.key = tpl.key.to_string();
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    // an empty record of every category, titles are the keys until the ui translates them.
+    pub fn templates() -> Vec<Categories> {
+        let all = [
+            Categories::Login(Default::default()),
+            Categories::CreditCard(Default::default()),
+            Categories::CryptoWallet(Default::default()),
+            Categories::Identity(Default::default()),
+            Categories::BankAccount(Default::default()),
+            Categories::EmailAccount(Default::default()),
+            Categories::Passport(Default::default()),
+            Categories::DriverLicense(Default::default()),
+            Categories::WifiPassword(Default::default()),
+            Categories::SshKey(Default::default()),
+            Categories::Other(Default::default()),
+        ];
+
+        all.iter()
+            .map(|category| {
+                let fields = category
+                    .template()
+                    .iter()
+                    .map(|template| Item {
+                        key: template.key.to_string(),
+                        kind: template.kind,
+                        title: template.key.to_string(),
+                        hide: template.hide,
+                        copy: template.copy,
+                        reload: template.reload,
+                        ..Default::default()
+                    })
+                    .collect();
+
+                category.update_element(Element {
+                    fields,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    pub fn validate(&self) -> Result<(), ZebraErrors> {
+        self.get_value().validate()
+    }
+}
+
+impl Element {
+    pub fn validate(&self) -> Result<(), ZebraErrors> {
+        self.fields
+            .iter()
+            .chain(self.extra_fields.iter())
+            .try_for_each(Item::validate)?;
+        self.uris.iter().try_for_each(LoginUri::validate)
+    }
+}
+
+impl Item {
+    pub fn rule(&self) -> Rule {
+        match self.key.as_str() {
+            "card_number" => Rule::CardNumber,
+            "card_expiry" => Rule::CardExpiry,
+            "cvv" => Rule::Cvv,
+            "iban" => Rule::Iban,
+            "bic" => Rule::Bic,
+            _ => Rule::Text,
+        }
+    }
+
+    // empty values are always fine, templates have no required fields.
+    pub fn validate(&self) -> Result<(), ZebraErrors> {
+        let value = self.value.trim();
+
+        // the value it points to was checked in its own record.
+        if value.is_empty() || self.reference().is_some() {
+            return Ok(());
+        }
+
+        self.kind.validate(value)?;
+
+        let error = match self.rule() {
+            Rule::CardNumber if !is_valid_card(value) => ZebraErrors::CoreInvalidCardNumber,
+            Rule::CardExpiry if parse_expiry(value).is_none() => ZebraErrors::CoreInvalidExpiry,
+            Rule::Cvv if !is_valid_cvv(value) => ZebraErrors::CoreInvalidCvv,
+            Rule::Iban if !is_valid_iban(value) => ZebraErrors::CoreInvalidIban,
+            Rule::Bic if !is_valid_bic(value) => ZebraErrors::CoreInvalidBic,
+            _ => return Ok(()),
+        };
+
+        Err(error)
+    }
+}
+
+// spaces and dashes are allowed between groups of digits.
+pub fn is_valid_card(number: &str) -> bool {
+    let digits: Option<Vec<u32>> = number
+        .chars()
+        .filter(|c| *c != ' ' && *c != '-')
+        .map(|c| c.to_digit(10))
+        .collect();
+    let digits = match digits {
+        Some(digits) if (12..=19).contains(&digits.len()) => digits,
+        _ => return false,
+    };
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| match i % 2 {
+            0 => *d,
+            _ if *d * 2 > 9 => *d * 2 - 9,
+            _ => *d * 2,
+        })
+        .sum();
+
+    sum % 10 == 0
+}
+
+// "MM/YY" or "MM/YYYY" into (month, full year).
+pub fn parse_expiry(value: &str) -> Option<(u32, i32)> {
+    let (month, year) = value.split_once('/')?;
+    let month: u32 = month.trim().parse().ok()?;
+    let year = year.trim();
+    let year: i32 = match year.len() {
+        2 => 2000 + year.parse::<i32>().ok()?,
+        4 => year.parse().ok()?,
+        _ => return None,
+    };
+
+    (1..=12).contains(&month).then_some((month, year))
+}
+
+pub fn is_valid_cvv(value: &str) -> bool {
+    (3..=4).contains(&value.len()) && value.chars().all(|c| c.is_ascii_digit())
+}
+
+// ISO 13616: country, check digits and BBAN, mod 97 of the rearranged number is 1.
+pub fn is_valid_iban(value: &str) -> bool {
+    let iban: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+
+    if !(15..=34).contains(&iban.len())
+        || !iban.chars().all(|c| c.is_ascii_alphanumeric())
+        || !iban[..2].chars().all(|c| c.is_ascii_alphabetic())
+        || !iban[2..4].chars().all(|c| c.is_ascii_digit())
+    {
+        return false;
+    }
+
+    let rearranged = iban[4..].chars().chain(iban[..4].chars());
+    let remainder = rearranged.fold(0u32, |acc, c| {
+        // letters count as two digits: A = 10 .. Z = 35.
+        let n = c.to_digit(36).unwrap_or(0);
+
+        if n > 9 {
+            (acc * 100 + n) % 97
+        } else {
+            (acc * 10 + n) % 97
+        }
+    });
+
+    remainder == 1
+}
+
+// ISO 9362: bank, country, location and an optional branch.
+pub fn is_valid_bic(value: &str) -> bool {
+    let bic = value.trim();
+
+    bic.is_ascii()
+        && (bic.len() == 8 || bic.len() == 11)
+        && bic[..6].chars().all(|c| c.is_ascii_alphabetic())
+        && bic[6..].chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+// "YYYY-MM-DD" into (year, month, day).
+pub fn parse_date(value: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = value.split('-');
+    let year: i32 = parts.next().filter(|y| y.len() == 4)?.parse().ok()?;
+    let month: u32 = parts.next().filter(|m| m.len() == 2)?.parse().ok()?;
+    let day: u32 = parts.next().filter(|d| d.len() == 2)?.parse().ok()?;
+
+    if parts.next().is_some() || !(1..=12).contains(&month) {
+        return None;
+    }
+
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+
+    (1..=days).contains(&day).then_some((year, month, day))
+}
+
+// "Expiry date" and "expiry-date" both stand for "expiry_date".
+fn title_key(title: &str) -> String {
+    title.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+#[cfg(test)]
+mod template_tests {
+    use super::*;
+
+    #[test]
+    fn test_validators() {
+        assert!(is_valid_card("4111 1111 1111 1111"));
+        assert!(is_valid_card("5555-5555-5555-4444"));
+        assert!(!is_valid_card("4111 1111 1111 1112"));
+        assert!(!is_valid_card("4111 1111 x111 1111"));
+        assert!(!is_valid_card("0"));
+
+        assert_eq!(parse_expiry("07/27"), Some((7, 2027)));
+        assert_eq!(parse_expiry("12/2030"), Some((12, 2030)));
+        assert_eq!(parse_expiry("13/27"), None);
+        assert_eq!(parse_expiry("0727"), None);
+
+        assert!(is_valid_iban("GB82 WEST 1234 5698 7654 32"));
+        assert!(is_valid_iban("de89370400440532013000"));
+        assert!(!is_valid_iban("GB82 WEST 1234 5698 7654 33"));
+        assert!(!is_valid_iban("GB82"));
+
+        assert!(is_valid_bic("DEUTDEFF"));
+        assert!(is_valid_bic("DEUTDEFF500"));
+        assert!(!is_valid_bic("DEUT"));
+
+        assert_eq!(parse_date("2024-02-29"), Some((2024, 2, 29)));
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2023-1-01"), None);
+        assert!(is_valid_cvv("123"));
+        assert!(!is_valid_cvv("12a"));
+    }
+
+    #[test]
+    fn test_templates() {
+        let templates = Categories::templates();
+        let mut card = templates
+            .iter()
+            .find(|c| matches!(c, Categories::CreditCard(_)))
+            .unwrap()
+            .clone();
+
+        assert_eq!(templates.len(), 11);
+        assert!(templates.iter().all(|c| c.validate().is_ok()));
+        assert_eq!(card.get_value().fields.len(), card.template().len());
+
+        card.get_value_mut().fields[1].value = "4111 1111 1111 1112".to_string();
+        assert_eq!(card.validate(), Err(ZebraErrors::CoreInvalidCardNumber));
+
+        card.get_value_mut().fields[1].value = "4111 1111 1111 1111".to_string();
+        card.get_value_mut().fields[2].value = "00/30".to_string();
+        assert_eq!(card.validate(), Err(ZebraErrors::CoreInvalidExpiry));
+
+        card.get_value_mut().fields[2].value = "01/30".to_string();
+        assert_eq!(card.validate(), Ok(()));
+
+        // custom fields without a key are never checked.
+        card.get_value_mut().extra_fields.push(Item {
+            title: "card_number".to_string(),
+            value: "nope".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(card.validate(), Ok(()));
+    }
+}
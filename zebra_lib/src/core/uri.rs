@@ -0,0 +1,248 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::kind::url_host;
+use super::record::{Categories, Element, ItemKind, LoginUri, UriMatch};
+use super::Core;
+use crate::errors::ZebraErrors;
+
+// https://publicsuffix.org/list/public_suffix_list.dat
+const PUBLIC_SUFFIX_LIST: &str = include_str!("data/public_suffix_list.dat");
+
+static SUFFIX_RULES: OnceLock<HashSet<&'static str>> = OnceLock::new();
+
+impl UriMatch {
+    pub const ALL: [UriMatch; 5] = [
+        UriMatch::BaseDomain,
+        UriMatch::Host,
+        UriMatch::StartsWith,
+        UriMatch::Regex,
+        UriMatch::Never,
+    ];
+}
+
+impl LoginUri {
+    pub fn matches(&self, url: &str) -> bool {
+        let url = url.trim();
+        let uri = self.uri.trim();
+
+        if uri.is_empty() {
+            return false;
+        }
+
+        match self.mode {
+            UriMatch::BaseDomain => match (host_of(uri), host_of(url)) {
+                // ip addresses and single label hosts have no base domain.
+                (Some(a), Some(b)) if is_bare_host(&a) || is_bare_host(&b) => a == b,
+                (Some(a), Some(b)) => base_domain(&a).is_some_and(|d| base_domain(&b) == Some(d)),
+                _ => false,
+            },
+            UriMatch::Host => host_of(uri).is_some() && host_of(uri) == host_of(url),
+            UriMatch::StartsWith => url.to_lowercase().starts_with(&uri.to_lowercase()),
+            UriMatch::Regex => Regex::new(uri).is_ok_and(|re| re.is_match(url)),
+            UriMatch::Never => false,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ZebraErrors> {
+        match self.mode {
+            UriMatch::Regex if Regex::new(self.uri.trim()).is_err() => {
+                Err(ZebraErrors::CoreInvalidUriRegex)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Element {
+    // records made before uris existed are matched by their url fields.
+    pub fn login_uris(&self) -> Vec<LoginUri> {
+        if !self.uris.is_empty() {
+            return self.uris.clone();
+        }
+
+        self.fields
+            .iter()
+            .filter(|item| item.kind == ItemKind::Url || item.key == "domain")
+            .map(|item| LoginUri {
+                uri: item.value.clone(),
+                mode: UriMatch::BaseDomain,
+            })
+            .collect()
+    }
+}
+
+impl Core {
+    // logins which belong to the site at `url`, in vault order.
+    pub fn find_logins_for_url(&self, url: &str) -> Vec<&Categories> {
+        self.data
+            .iter()
+            .filter(|record| matches!(record, Categories::Login(_)))
+            .filter(|record| {
+                record
+                    .get_value()
+                    .login_uris()
+                    .iter()
+                    .any(|uri| uri.matches(url))
+            })
+            .collect()
+    }
+}
+
+fn host_of(url: &str) -> Option<String> {
+    url_host(url).map(|host| host.trim_end_matches('.').to_lowercase())
+}
+
+fn is_bare_host(host: &str) -> bool {
+    !host.contains('.')
+        || host
+            .trim_matches(['[', ']'])
+            .parse::<std::net::IpAddr>()
+            .is_ok()
+}
+
+// the public suffix plus one label, `None` for bare suffixes like "co.uk".
+pub fn base_domain(host: &str) -> Option<String> {
+    let rules = SUFFIX_RULES.get_or_init(|| {
+        PUBLIC_SUFFIX_LIST
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+            .collect()
+    });
+    let host = host.trim_end_matches('.').to_lowercase();
+    let labels: Vec<&str> = host.split('.').collect();
+
+    if labels.iter().any(|label| label.is_empty()) {
+        return None;
+    }
+
+    // without a matching rule the suffix is the last label.
+    let mut suffix_len = 1;
+
+    for i in 0..labels.len() {
+        let candidate = labels[i..].join(".");
+        let parent = labels[i + 1..].join(".");
+
+        if rules.contains(format!("!{}", candidate).as_str()) {
+            suffix_len = labels.len() - i - 1;
+            break;
+        }
+
+        if rules.contains(candidate.as_str())
+            || (!parent.is_empty() && rules.contains(format!("*.{}", parent).as_str()))
+        {
+            suffix_len = labels.len() - i;
+            break;
+        }
+    }
+
+    (labels.len() > suffix_len).then(|| labels[labels.len() - suffix_len - 1..].join("."))
+}
+
+#[cfg(test)]
+mod uri_tests {
+    use super::*;
+    use crate::core::record::Item;
+    use crate::core::test_core;
+
+    fn uri(uri: &str, mode: UriMatch) -> LoginUri {
+        LoginUri {
+            uri: uri.to_string(),
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_base_domain() {
+        assert_eq!(
+            base_domain("www.example.com").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            base_domain("a.b.example.co.uk").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(base_domain("co.uk"), None);
+        // "*.ck" with the "!www.ck" exception.
+        assert_eq!(base_domain("a.b.ck").as_deref(), Some("a.b.ck"));
+        assert_eq!(base_domain("www.ck").as_deref(), Some("www.ck"));
+        assert_eq!(
+            base_domain("foo.unknowntld").as_deref(),
+            Some("foo.unknowntld")
+        );
+        assert_eq!(base_domain("localhost"), None);
+    }
+
+    #[test]
+    fn test_bare_hosts() {
+        assert!(uri("http://nas", UriMatch::BaseDomain).matches("http://nas:5000/login"));
+        assert!(!uri("http://nas", UriMatch::BaseDomain).matches("http://router"));
+        assert!(!uri("localhost", UriMatch::BaseDomain).matches("http://intranet"));
+        assert!(!uri("localhost", UriMatch::BaseDomain).matches("https://co.uk"));
+        assert!(!uri("co.uk", UriMatch::BaseDomain).matches("https://co.uk"));
+        assert!(uri("192.168.1.1", UriMatch::BaseDomain).matches("http://192.168.1.1/admin"));
+        assert!(!uri("192.168.1.1", UriMatch::BaseDomain).matches("http://10.0.1.1"));
+    }
+
+    #[test]
+    fn test_match_modes() {
+        let url = "https://accounts.google.com/signin?x=1";
+
+        assert!(uri("google.com", UriMatch::BaseDomain).matches(url));
+        assert!(!uri("google.co.uk", UriMatch::BaseDomain).matches(url));
+        assert!(uri("https://accounts.google.com", UriMatch::Host).matches(url));
+        assert!(!uri("google.com", UriMatch::Host).matches(url));
+        assert!(uri("https://accounts.google.com/sign", UriMatch::StartsWith).matches(url));
+        assert!(!uri("https://google.com", UriMatch::StartsWith).matches(url));
+        assert!(uri(r"^https://\w+\.google\.com/", UriMatch::Regex).matches(url));
+        assert!(!uri("google.com", UriMatch::Never).matches(url));
+        assert_eq!(
+            uri("(", UriMatch::Regex).validate(),
+            Err(ZebraErrors::CoreInvalidUriRegex)
+        );
+    }
+
+    #[test]
+    fn test_find_logins_for_url() {
+        let mut core = test_core();
+
+        let github = core
+            .add_element(Categories::Login(Element {
+                uris: vec![uri("github.com", UriMatch::BaseDomain)],
+                ..Default::default()
+            }))
+            .unwrap();
+        let legacy = core
+            .add_element(Categories::Login(Element {
+                fields: vec![Item {
+                    kind: ItemKind::Url,
+                    value: "https://gitlab.com".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }))
+            .unwrap();
+
+        core.add_element(Categories::Other(Element {
+            uris: vec![uri("github.com", UriMatch::BaseDomain)],
+            ..Default::default()
+        }))
+        .unwrap();
+
+        let found = core.find_logins_for_url("https://gist.github.com/");
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].get_id(), &github);
+        assert_eq!(
+            core.find_logins_for_url("gitlab.com/users/sign_in")[0].get_id(),
+            &legacy
+        );
+        assert!(core.find_logins_for_url("https://example.com").is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use super::payload::Payload;
+use super::session_keys;
+use crate::{errors::ZebraErrors, state::State, storage::db::LocalStorage};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Passed,
+    Failed(ZebraErrors),
+    // an earlier check failed, so this one could not run.
+    Skipped,
+}
+
+impl Check {
+    pub fn is_passed(&self) -> bool {
+        *self == Check::Passed
+    }
+
+    fn from_result<T>(result: &Result<T, ZebraErrors>) -> Self {
+        match result {
+            Ok(_) => Check::Passed,
+            Err(e) => Check::Failed(e.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    // the stored json parses into `Data<State>`.
+    pub envelope: Check,
+    pub hashsum: Check,
+    // cipher orders are not empty and don't repeat.
+    pub cipher_orders: Check,
+    // the password opens the keys session.
+    pub key_store: Check,
+    // the session opens the records.
+    pub data_store: Check,
+    pub records: usize,
+    // snapshots which pass every check, so `repair` can use them.
+    pub intact_snapshots: usize,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.envelope.is_passed()
+            && self.hashsum.is_passed()
+            && self.cipher_orders.is_passed()
+            && self.key_store.is_passed()
+            && self.data_store.is_passed()
+    }
+}
+
+pub fn verify_key(db: &LocalStorage, key: &str, password: &[u8]) -> VerifyReport {
+    let mut report = VerifyReport {
+        envelope: Check::Skipped,
+        hashsum: Check::Skipped,
+        cipher_orders: Check::Skipped,
+        key_store: Check::Skipped,
+        data_store: Check::Skipped,
+        records: 0,
+        intact_snapshots: 0,
+    };
+
+    // a state with a wrong hashsum is still inspected further.
+    let state = match db.get::<State>(key) {
+        Ok(state) => {
+            report.envelope = Check::Passed;
+            report.hashsum = Check::Passed;
+
+            state
+        }
+        Err(ZebraErrors::StorageHashsumError) => {
+            report.hashsum = Check::Failed(ZebraErrors::StorageHashsumError);
+
+            match db.get_unverified::<State>(key) {
+                Ok(state) => {
+                    report.envelope = Check::Passed;
+
+                    state
+                }
+                Err(e) => {
+                    report.envelope = Check::Failed(e);
+
+                    return report;
+                }
+            }
+        }
+        Err(e) => {
+            report.envelope = Check::Failed(e);
+
+            return report;
+        }
+    };
+
+    report.cipher_orders = Check::from_result(&check_orders(&state));
+
+    if !report.cipher_orders.is_passed() {
+        return report;
+    }
+
+    let keys = session_keys(&state, password);
+
+    report.key_store = Check::from_result(&keys);
+
+    let keys = match keys {
+        Ok(keys) => keys,
+        Err(_) => return report,
+    };
+    let orders = &state.settings.cipher.cipher_orders;
+    let data = keys
+        .decrypt(&state.secure_data_store, orders)
+        .and_then(|bytes| Payload::from_slice(&bytes));
+
+    report.data_store = Check::from_result(&data);
+    report.records = data.map(|payload| payload.records.len()).unwrap_or(0);
+
+    report
+}
+
+fn check_orders(state: &State) -> Result<(), ZebraErrors> {
+    let orders = &state.settings.cipher.cipher_orders;
+
+    if orders.is_empty() {
+        return Err(ZebraErrors::StorageDataBroken);
+    }
+
+    for (index, order) in orders.iter().enumerate() {
+        if orders[..index].contains(order) {
+            return Err(ZebraErrors::StorageDataBroken);
+        }
+    }
+
+    Ok(())
+}
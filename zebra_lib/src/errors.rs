@@ -3,11 +3,15 @@
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
 use std::fmt;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ZebraErrors {
     // Storage errors
     StorageAccessError,
     StoragePathError,
+    StoragePathExists,
+    StorageMoveNotAllowed,
+    StorageLocked,
+    StorageRepairFailed,
     StorageDataNotFound,
     StorageDataBroken,
     StorageHashsumError,
@@ -49,6 +53,39 @@ pub enum ZebraErrors {
 
     // Core
     CoreModelError,
+    CoreElementNotFound,
+    CoreTagNotFound,
+    CoreFolderNotFound,
+    CoreFolderCycle,
+    CoreCategoryNotFound,
+    CoreReferenceNotFound,
+    CoreReferenceCycle,
+    CoreInvalidName,
+    CoreInvalidEmail,
+    CoreInvalidCardNumber,
+    CoreInvalidExpiry,
+    CoreInvalidCvv,
+    CoreInvalidIban,
+    CoreInvalidBic,
+    CoreInvalidDate,
+    CoreInvalidUrl,
+    CoreInvalidPhone,
+    CoreInvalidNumber,
+    CoreInvalidTotp,
+    CoreInvalidUriRegex,
+
+    // Breach
+    BreachDbNotFound,
+    BreachDbBroken,
+
+    // Attachments
+    AttachmentNotFound,
+    AttachmentTooLarge,
+
+    // Ssh keys
+    SshKeyInvalid,
+    SshKeyBadPassphrase,
+    AgentSocketError,
 
     // password gen
     PassGenInvalidRng,
@@ -31,7 +31,7 @@ const SHA256_SIZE: usize = SHA512_SIZE / 2;
 const AES_BLOCK_SIZE: usize = 16;
 pub const AES_KEY_SIZE: usize = 32;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum CipherOrders {
     AES256,
     NTRUP1277,
@@ -163,6 +163,11 @@ impl KeyChain {
     fn aes_decrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, ZebraErrors> {
         let key = GenericArray::from(self.aes_key);
         let cipher = Aes256::new(&key);
+
+        if bytes.len() < 8 {
+            return Err(ZebraErrors::KeyChainSliceError);
+        }
+
         let point_bytes: [u8; 8] = bytes[bytes.len() - 8..]
             .try_into()
             .or(Err(ZebraErrors::KeyChainSliceError))?;
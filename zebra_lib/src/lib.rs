@@ -1,6 +1,8 @@
 // -- Copyright (c) 2023 Rina Khasanshin
 // -- Email: hicarus@yandex.ru
 // -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+#[cfg(unix)]
+pub mod agent;
 pub mod bip39;
 pub mod config;
 pub mod core;
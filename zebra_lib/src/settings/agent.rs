@@ -0,0 +1,13 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AgentSettings {
+    // serve the ssh key records over the agent socket.
+    pub enabled: bool,
+    // every signature waits for a yes in the app.
+    pub confirm: bool,
+}
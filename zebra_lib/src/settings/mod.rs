@@ -2,19 +2,28 @@
 //! -- Email: hicarus@yandex.ru
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
 
+pub mod agent;
 pub mod appearance;
 pub mod cipher;
 pub mod language;
+pub mod records;
 
 use language::Language;
 use serde::{Deserialize, Serialize};
 
+use agent::AgentSettings;
 use appearance::AppearanceSettings;
 use cipher::CipherSettings;
+use records::RecordsSettings;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SettingsPayload {
     pub appearance: AppearanceSettings,
     pub cipher: CipherSettings,
     pub locale: Language,
+    // states written before these settings existed get the defaults.
+    #[serde(default)]
+    pub records: RecordsSettings,
+    #[serde(default)]
+    pub agent: AgentSettings,
 }
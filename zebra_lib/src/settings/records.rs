@@ -0,0 +1,48 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+pub const HISTORY_DEPTH: usize = 10;
+pub const TRASH_RETENTION_DAYS: u32 = 30;
+pub const ROTATION_DAYS: u32 = 365;
+pub const EXPIRY_NOTICE_DAYS: u32 = 30;
+pub const ATTACHMENT_LIMIT: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RecordsSettings {
+    // how many previous values a secret field keeps.
+    pub history_depth: usize,
+    // deleted records are purged after this many days, zero keeps them forever.
+    pub trash_retention_days: u32,
+    // the audit reports passwords older than this, zero turns it off.
+    pub rotation_days: u32,
+    // expirations and rotations this close are shown on the home page.
+    pub expiry_notice_days: u32,
+    // see `core::breach::BreachDb::open`.
+    pub breach_db: Option<PathBuf>,
+    // the largest file which can be attached, in bytes.
+    pub attachment_limit: u64,
+}
+
+impl Default for RecordsSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordsSettings {
+    pub fn new() -> Self {
+        Self {
+            history_depth: HISTORY_DEPTH,
+            trash_retention_days: TRASH_RETENTION_DAYS,
+            rotation_days: ROTATION_DAYS,
+            expiry_notice_days: EXPIRY_NOTICE_DAYS,
+            breach_db: None,
+            attachment_limit: ATTACHMENT_LIMIT,
+        }
+    }
+}
@@ -7,9 +7,13 @@ use std::borrow::Cow;
 use crate::{
     errors::ZebraErrors,
     settings::{
-        appearance::AppearanceSettings, cipher::CipherSettings, language::Language, SettingsPayload,
+        agent::AgentSettings, appearance::AppearanceSettings, cipher::CipherSettings,
+        language::Language, records::RecordsSettings, SettingsPayload,
+    },
+    storage::{
+        db::LocalStorage,
+        keys::{SLED_SNAPSHOT_KEY, SLED_STATE_KEY, SNAPSHOTS_LIMIT},
     },
-    storage::{db::LocalStorage, keys::SLED_STATE_KEY},
 };
 use serde::{Deserialize, Serialize};
 
@@ -52,10 +56,14 @@ impl<'a> State<'a> {
         let appearance = AppearanceSettings::new();
         let cipher = CipherSettings::new();
         let locale = Language::English;
+        let records = RecordsSettings::new();
+        let agent = AgentSettings::default();
         let settings = SettingsPayload {
             cipher,
             appearance,
             locale,
+            records,
+            agent,
         };
         State {
             settings,
@@ -77,6 +85,7 @@ impl<'a> State<'a> {
             return Err(ZebraErrors::StateNotRead);
         }
 
+        db.snapshot(SLED_STATE_KEY, SLED_SNAPSHOT_KEY, SNAPSHOTS_LIMIT)?;
         db.set::<&Self>(SLED_STATE_KEY, self)?;
 
         Ok(())
@@ -87,9 +96,11 @@ impl<'a> State<'a> {
             Ok(payload_store) => {
                 *self = payload_store;
             }
-            Err(_) => {
+            // a fresh vault, anything else is left for `Core::repair`.
+            Err(ZebraErrors::StorageDataNotFound) => {
                 db.set::<&Self>(SLED_STATE_KEY, self)?;
             }
+            Err(e) => return Err(e),
         };
         self.ready = true;
 
@@ -99,13 +110,13 @@ impl<'a> State<'a> {
 
 #[cfg(test)]
 mod settings_tests {
-    use crate::storage::db::LocalStorage;
+    use crate::storage::{db::LocalStorage, memory_backend::MemoryBackend};
 
     use super::*;
 
     #[test]
     fn test_zebra_state() {
-        let db = LocalStorage::new("com.test_state", "test-state Corp", "test_state App").unwrap();
+        let db = LocalStorage::with_backend(Box::new(MemoryBackend::new()));
 
         let mut state = State::new();
 
@@ -0,0 +1,28 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::path::Path;
+
+use crate::errors::ZebraErrors;
+
+// Raw key/value storage, the envelope and hashsum live in `LocalStorage`.
+pub trait StorageBackend: Send + Sync {
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>, ZebraErrors>;
+
+    fn write(&self, key: &str, value: &[u8]) -> Result<(), ZebraErrors>;
+
+    fn remove(&self, key: &str) -> Result<(), ZebraErrors>;
+
+    fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, ZebraErrors>;
+
+    // returns once every write so far is on disk.
+    fn sync(&self) -> Result<(), ZebraErrors>;
+
+    // location on disk, `None` for volatile backends.
+    fn path(&self) -> Option<&Path>;
+
+    fn size(&self) -> u64;
+
+    // closes the backend and removes everything it keeps on disk.
+    fn destroy(self: Box<Self>) -> Result<(), ZebraErrors>;
+}
@@ -9,13 +9,15 @@ use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
-use directories::ProjectDirs;
 use sha2::{Digest, Sha256};
-use sled::{Db, IVec};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
 
+use super::backend::StorageBackend;
+use super::memory_backend::MemoryBackend;
+use super::sled_backend::SledBackend;
 use crate::errors::ZebraErrors;
 
 #[derive(Deserialize, Serialize)]
@@ -29,10 +31,17 @@ pub struct Data<ST> {
     pub hashsum: String,
 }
 
+// the payload exactly as it was written, the hashsum covers these bytes.
+#[derive(Deserialize)]
+struct StoredData<'a> {
+    #[serde(borrow)]
+    payload: &'a RawValue,
+    hashsum: String,
+}
+
 pub struct LocalStorage {
-    tree: Db,
+    backend: Box<dyn StorageBackend>,
     version: u16,
-    path: ProjectDirs,
 }
 
 impl LocalStorage {
@@ -41,67 +50,155 @@ impl LocalStorage {
         organization: &str,
         application: &str,
     ) -> Result<Self, ZebraErrors> {
-        let path = match ProjectDirs::from(qualifier, organization, application) {
-            Some(p) => p,
-            None => return Err(ZebraErrors::StoragePathError),
-        };
-        let tree = match sled::open(path.data_dir()) {
-            Ok(t) => t,
-            Err(_) => return Err(ZebraErrors::StorageAccessError),
-        };
+        let backend = SledBackend::from(qualifier, organization, application)?;
+
+        Ok(LocalStorage::with_backend(Box::new(backend)))
+    }
+
+    pub fn with_backend(backend: Box<dyn StorageBackend>) -> Self {
         let version = 0;
 
-        Ok(LocalStorage {
-            tree,
-            version,
-            path,
-        })
+        LocalStorage { backend, version }
+    }
+
+    pub fn save_as_file(&self, key: &str, path: &Path) -> Result<(), ZebraErrors> {
+        let bytes = self
+            .backend
+            .read(key)?
+            .ok_or(ZebraErrors::StorageDataNotFound)?;
+        let mut file = File::create(path).or(Err(ZebraErrors::FailToCreateFile))?;
+
+        file.write_all(&bytes)
+            .or(Err(ZebraErrors::FailToWriteFile))?;
+
+        Ok(())
+    }
+
+    pub fn get_raw(&self, key: &str) -> Result<Vec<u8>, ZebraErrors> {
+        self.backend
+            .read(key)?
+            .ok_or(ZebraErrors::StorageDataNotFound)
+    }
+
+    pub fn set_raw(&self, key: &str, bytes: &[u8]) -> Result<(), ZebraErrors> {
+        self.backend.write(key, bytes)
     }
 
-    pub fn save_as_file(&self, path: &Path) -> Result<(), ZebraErrors> {
-        let export = self.tree.export();
+    // copies the current raw value of `key` under `prefix`, keeps `limit` newest copies.
+    pub fn snapshot(&self, key: &str, prefix: &str, limit: usize) -> Result<(), ZebraErrors> {
+        let bytes = match self.backend.read(key)? {
+            Some(bytes) => bytes,
+            None => return Ok(()),
+        };
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .or(Err(ZebraErrors::StorageTimeWentBackwards))?;
+        let snapshot_key = format!("{}_{:020}", prefix, since_epoch.as_nanos());
 
-        for (_, _, collection_iter) in export {
-            for mut kv in collection_iter {
-                let bytes = kv.pop().ok_or(ZebraErrors::FailToloadBytesTree)?;
-                let mut file = File::create(path).or(Err(ZebraErrors::FailToCreateFile))?;
+        self.backend.write(&snapshot_key, &bytes)?;
 
-                file.write_all(&bytes)
-                    .or(Err(ZebraErrors::FailToWriteFile))?;
-            }
+        for old in self.snapshots(prefix)?.iter().skip(limit) {
+            self.backend.remove(old)?;
         }
 
         Ok(())
     }
 
-    pub fn get_path(&self) -> &Path {
-        self.path.data_dir()
+    pub fn remove(&self, key: &str) -> Result<(), ZebraErrors> {
+        self.backend.remove(key)
+    }
+
+    // keys which start with `prefix`, in no particular order.
+    pub fn keys(&self, prefix: &str) -> Result<Vec<String>, ZebraErrors> {
+        Ok(self
+            .backend
+            .entries()?
+            .into_iter()
+            .map(|(key, _)| key)
+            .filter(|key| key.starts_with(prefix))
+            .collect())
+    }
+
+    // snapshot keys, newest first.
+    pub fn snapshots(&self, prefix: &str) -> Result<Vec<String>, ZebraErrors> {
+        let mut keys = self.keys(prefix)?;
+
+        keys.sort_unstable_by(|a, b| b.cmp(a));
+
+        Ok(keys)
+    }
+
+    // copies every record into `backend` and reads it back,
+    // the old one is dropped from disk only after that.
+    pub fn move_to(&mut self, backend: Box<dyn StorageBackend>) -> Result<(), ZebraErrors> {
+        let entries = self.backend.entries()?;
+        let copied = entries
+            .iter()
+            .try_for_each(|(key, value)| backend.write(key, value))
+            .and_then(|_| backend.sync())
+            .and_then(|_| {
+                entries
+                    .iter()
+                    .try_for_each(|(key, value)| match backend.read(key)? {
+                        Some(stored) if stored == *value => Ok(()),
+                        _ => Err(ZebraErrors::StorageWriteError),
+                    })
+            });
+
+        if let Err(e) = copied {
+            let _ = backend.destroy();
+
+            return Err(e);
+        }
+
+        let old = std::mem::replace(&mut self.backend, backend);
+
+        old.destroy()
+    }
+
+    // wipes the backend, the storage stays usable in memory until a new one is set.
+    pub fn destroy(&mut self) -> Result<(), ZebraErrors> {
+        let old = std::mem::replace(&mut self.backend, Box::new(MemoryBackend::new()));
+
+        old.destroy()
+    }
+
+    pub fn get_path(&self) -> Option<&Path> {
+        self.backend.path()
     }
 
     pub fn get_db_size(&self) -> u64 {
-        self.tree.size_on_disk().unwrap_or(0)
+        self.backend.size()
     }
 
     pub fn get<ST>(&self, key: &str) -> Result<ST, ZebraErrors>
     where
         ST: for<'a> Deserialize<'a> + Serialize,
     {
-        let some_value = self
-            .tree
-            .get(key)
-            .or(Err(ZebraErrors::StorageAccessError))?;
+        let some_value = self.backend.read(key)?;
         let value = some_value.ok_or(ZebraErrors::StorageDataNotFound)?;
         let json = String::from_utf8_lossy(&value);
 
-        let data: Data<ST> = serde_json::from_str(&json).or(Err(ZebraErrors::StorageDataBroken))?;
-        let json_payload =
-            serde_json::to_string(&data.payload).or(Err(ZebraErrors::StorageDataBroken))?;
-        let hashsum = self.hash(json_payload.as_bytes());
+        let data: StoredData =
+            serde_json::from_str(&json).or(Err(ZebraErrors::StorageDataBroken))?;
+        let hashsum = self.hash(data.payload.get().as_bytes());
 
         if hashsum != data.hashsum {
             return Err(ZebraErrors::StorageHashsumError);
         }
 
+        serde_json::from_str(data.payload.get()).or(Err(ZebraErrors::StorageDataBroken))
+    }
+
+    // same as `get` but ignores the hashsum, used to inspect broken records.
+    pub fn get_unverified<ST>(&self, key: &str) -> Result<ST, ZebraErrors>
+    where
+        ST: for<'a> Deserialize<'a>,
+    {
+        let value = self.get_raw(key)?;
+        let data: Data<ST> =
+            serde_json::from_slice(&value).or(Err(ZebraErrors::StorageDataBroken))?;
+
         Ok(data.payload)
     }
 
@@ -120,13 +217,8 @@ impl LocalStorage {
             version: self.version,
         };
         let json = serde_json::to_string(&data).or(Err(ZebraErrors::StorageDataBroken))?;
-        let vec = IVec::from(json.as_bytes());
-
-        self.tree
-            .insert(key, vec)
-            .or(Err(ZebraErrors::StorageWriteError))?;
 
-        Ok(())
+        self.backend.write(key, json.as_bytes())
     }
 
     fn hash(&self, bytes: &[u8]) -> String {
@@ -151,18 +243,98 @@ impl LocalStorage {
 #[cfg(test)]
 mod storage_tests {
     use super::*;
+    use crate::storage::file_backend::FileBackend;
+
+    const KEY: &str = "TEST_KEY_FOR_STORAGE";
 
     #[test]
     fn test_read_write() {
-        const KEY: &str = "TEST_KEY_FOR_STORAGE";
+        let db = LocalStorage::with_backend(Box::new(MemoryBackend::new()));
+        let payload = vec!["test1", "test2", "test3"];
+
+        db.set(KEY, &payload).unwrap();
+
+        let out = db.get::<Vec<String>>(KEY).unwrap();
+
+        assert_eq!(out, payload);
+        assert!(db.get_path().is_none());
+        assert!(matches!(
+            db.get::<Vec<String>>("NOT_EXISTS_KEY"),
+            Err(ZebraErrors::StorageDataNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_hashsum_check() {
+        let backend = MemoryBackend::new();
+        let db = LocalStorage::with_backend(Box::new(backend.clone()));
+
+        db.set(KEY, vec!["test1"]).unwrap();
+
+        let raw = backend.read(KEY).unwrap().unwrap();
+        let broken = String::from_utf8(raw).unwrap().replace("test1", "test2");
+
+        backend.write(KEY, broken.as_bytes()).unwrap();
 
-        let db = LocalStorage::new("com.test_write", "WriteTest Corp", "WriteTest App").unwrap();
+        assert!(matches!(
+            db.get::<Vec<String>>(KEY),
+            Err(ZebraErrors::StorageHashsumError)
+        ));
+    }
+
+    #[test]
+    fn test_file_backend() {
+        let dir = std::env::temp_dir().join(format!("zebra_file_backend_{}", std::process::id()));
+        let path = dir.join("vault.json");
         let payload = vec!["test1", "test2", "test3"];
 
+        let db = LocalStorage::with_backend(Box::new(FileBackend::new(&path).unwrap()));
+
         db.set(KEY, &payload).unwrap();
+        drop(db);
 
+        let mut db = LocalStorage::with_backend(Box::new(FileBackend::new(&path).unwrap()));
         let out = db.get::<Vec<String>>(KEY).unwrap();
 
         assert_eq!(out, payload);
+        assert_eq!(db.get_path(), Some(path.as_path()));
+
+        db.destroy().unwrap();
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let db = LocalStorage::with_backend(Box::new(MemoryBackend::new()));
+        const PREFIX: &str = "TEST_SNAPSHOT";
+
+        for i in 0..5 {
+            db.snapshot(KEY, PREFIX, 3).unwrap();
+            db.set(KEY, vec![i]).unwrap();
+        }
+
+        let snapshots = db.snapshots(PREFIX).unwrap();
+
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(db.get::<Vec<u8>>(&snapshots[0]).unwrap(), vec![3]);
+        assert_eq!(db.get::<Vec<u8>>(&snapshots[2]).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_move_to() {
+        let mut db = LocalStorage::with_backend(Box::new(MemoryBackend::new()));
+        let dir = std::env::temp_dir().join(format!("zebra_move_to_{}", std::process::id()));
+        let path = dir.join("vault.json");
+        let payload = vec!["test1", "test2", "test3"];
+
+        db.set(KEY, &payload).unwrap();
+        db.move_to(Box::new(FileBackend::new(&path).unwrap()))
+            .unwrap();
+
+        assert_eq!(db.get_path(), Some(path.as_path()));
+        assert_eq!(db.get::<Vec<String>>(KEY).unwrap(), payload);
+
+        db.destroy().unwrap();
+        std::fs::remove_dir(&dir).unwrap();
     }
 }
@@ -0,0 +1,118 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::backend::StorageBackend;
+use super::lock::VaultLock;
+use super::shred::shred;
+use crate::errors::ZebraErrors;
+
+// The whole vault in one json file of hex encoded values.
+pub struct FileBackend {
+    path: PathBuf,
+    map: Mutex<BTreeMap<String, String>>,
+    lock: VaultLock,
+}
+
+impl FileBackend {
+    pub fn new(path: &Path) -> Result<Self, ZebraErrors> {
+        let lock = VaultLock::acquire(&path.with_extension("lock"))?;
+        let map = if path.exists() {
+            let json = fs::read(path).or(Err(ZebraErrors::StorageAccessError))?;
+
+            serde_json::from_slice(&json).or(Err(ZebraErrors::StorageDataBroken))?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            map: Mutex::new(map),
+            lock,
+        })
+    }
+
+    fn flush(&self, map: &BTreeMap<String, String>) -> Result<(), ZebraErrors> {
+        let json = serde_json::to_vec(map).or(Err(ZebraErrors::FailToConvertJson))?;
+        let tmp_path = self.path.with_extension("tmp");
+        let mut file = File::create(&tmp_path).or(Err(ZebraErrors::FailToCreateFile))?;
+
+        file.write_all(&json)
+            .or(Err(ZebraErrors::FailToWriteFile))?;
+        file.sync_all().or(Err(ZebraErrors::FailToWriteFile))?;
+        fs::rename(&tmp_path, &self.path).or(Err(ZebraErrors::FailToWriteFile))?;
+
+        Ok(())
+    }
+}
+
+impl StorageBackend for FileBackend {
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>, ZebraErrors> {
+        let map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+
+        match map.get(key) {
+            Some(value) => {
+                let bytes = hex::decode(value).or(Err(ZebraErrors::StorageDataBroken))?;
+
+                Ok(Some(bytes))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn write(&self, key: &str, value: &[u8]) -> Result<(), ZebraErrors> {
+        let mut map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+
+        map.insert(key.to_string(), hex::encode(value));
+
+        self.flush(&map)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), ZebraErrors> {
+        let mut map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+
+        if map.remove(key).is_some() {
+            self.flush(&map)?;
+        }
+
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, ZebraErrors> {
+        let map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+        let mut entries = Vec::with_capacity(map.len());
+
+        for (key, value) in map.iter() {
+            let bytes = hex::decode(value).or(Err(ZebraErrors::StorageDataBroken))?;
+
+            entries.push((key.clone(), bytes));
+        }
+
+        Ok(entries)
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    // every write is flushed already.
+    fn sync(&self) -> Result<(), ZebraErrors> {
+        Ok(())
+    }
+
+    fn size(&self) -> u64 {
+        fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0)
+    }
+
+    fn destroy(self: Box<Self>) -> Result<(), ZebraErrors> {
+        shred(&self.path)?;
+        shred(&self.path.with_extension("tmp"))?;
+
+        self.lock.release()
+    }
+}
@@ -4,3 +4,14 @@
 
 pub const SLED_STATE_KEY: &str = "ZEBRA_STATE_KEY";
 pub const SLED_DATA_KEY: &str = "ZEBRA_DATA_KEY";
+// random id of this install, kept out of the encrypted state.
+pub const SLED_DEVICE_KEY: &str = "ZEBRA_DEVICE_KEY";
+// prefix of rolling copies of the state, suffixed by a write time.
+pub const SLED_SNAPSHOT_KEY: &str = "ZEBRA_SNAPSHOT_KEY";
+pub const SNAPSHOTS_LIMIT: usize = 5;
+// prefix of encrypted attachments, suffixed by the attachment id.
+pub const SLED_ATTACHMENT_KEY: &str = "ZEBRA_ATTACHMENT_KEY";
+// encrypted last use times of records, kept apart so copying a secret doesn't rotate snapshots.
+pub const SLED_ACCESSED_KEY: &str = "ZEBRA_ACCESSED_KEY";
+// prefix of states replaced by `repair`, kept apart so they don't evict intact snapshots.
+pub const SLED_BROKEN_KEY: &str = "ZEBRA_BROKEN_KEY";
@@ -0,0 +1,90 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use fs2::FileExt;
+
+use crate::errors::ZebraErrors;
+
+pub const LOCK_FILE_NAME: &str = "zebra.lock";
+
+// Advisory lock, held while the vault is open so a second process can't
+// race writes to `SLED_STATE_KEY`. The OS drops it if the process dies.
+#[derive(Debug)]
+pub struct VaultLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl VaultLock {
+    pub fn acquire(path: &Path) -> Result<Self, ZebraErrors> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).or(Err(ZebraErrors::StorageAccessError))?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .or(Err(ZebraErrors::StorageAccessError))?;
+
+        file.try_lock_exclusive()
+            .or(Err(ZebraErrors::StorageLocked))?;
+        file.set_len(0).or(Err(ZebraErrors::StorageWriteError))?;
+        write!(file, "{}", std::process::id()).or(Err(ZebraErrors::StorageWriteError))?;
+
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    // unlocks and deletes the lock file, once the files it guards are gone.
+    pub fn release(self) -> Result<(), ZebraErrors> {
+        let path = self.path.clone();
+
+        drop(self);
+        fs::remove_file(path).or(Err(ZebraErrors::StorageAccessError))
+    }
+}
+
+impl Drop for VaultLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+#[cfg(test)]
+mod lock_tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_lock() {
+        let dir = std::env::temp_dir().join(format!("zebra_lock_{}", std::process::id()));
+        let path = dir.join(LOCK_FILE_NAME);
+        let lock = VaultLock::acquire(&path).unwrap();
+
+        assert!(matches!(
+            VaultLock::acquire(&path),
+            Err(ZebraErrors::StorageLocked)
+        ));
+
+        drop(lock);
+
+        let lock = VaultLock::acquire(&path).unwrap();
+
+        lock.release().unwrap();
+
+        assert!(!path.exists());
+
+        fs::remove_dir(dir).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use super::backend::StorageBackend;
+use crate::errors::ZebraErrors;
+
+// Clones share the same map, so a dropped `Core` can be reopened in tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBackend {
+    map: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>, ZebraErrors> {
+        let map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+
+        Ok(map.get(key).cloned())
+    }
+
+    fn write(&self, key: &str, value: &[u8]) -> Result<(), ZebraErrors> {
+        let mut map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+
+        map.insert(key.to_string(), value.to_vec());
+
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), ZebraErrors> {
+        let mut map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+
+        map.remove(key);
+
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, ZebraErrors> {
+        let map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+
+        Ok(map.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    }
+
+    fn sync(&self) -> Result<(), ZebraErrors> {
+        Ok(())
+    }
+
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn size(&self) -> u64 {
+        self.map
+            .lock()
+            .map(|map| map.values().map(|v| v.len() as u64).sum())
+            .unwrap_or(0)
+    }
+
+    fn destroy(self: Box<Self>) -> Result<(), ZebraErrors> {
+        let mut map = self.map.lock().or(Err(ZebraErrors::SyncStateLock))?;
+
+        map.clear();
+
+        Ok(())
+    }
+}
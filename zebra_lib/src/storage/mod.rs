@@ -2,5 +2,11 @@
 // -- Email: hicarus@yandex.ru
 // -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
 
+pub mod backend;
 pub mod db;
+pub mod file_backend;
 pub mod keys;
+pub mod lock;
+pub mod memory_backend;
+pub mod shred;
+pub mod sled_backend;
@@ -0,0 +1,74 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::errors::ZebraErrors;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// overwrites every file under `path` with zeros before removing it.
+pub fn shred(path: &Path) -> Result<(), ZebraErrors> {
+    if path.is_dir() {
+        let dir = fs::read_dir(path).or(Err(ZebraErrors::StorageAccessError))?;
+
+        for entry in dir.flatten() {
+            shred(&entry.path())?;
+        }
+
+        return fs::remove_dir(path).or(Err(ZebraErrors::StorageWriteError));
+    }
+
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .or(Err(ZebraErrors::StorageAccessError))?;
+    let len = file
+        .metadata()
+        .or(Err(ZebraErrors::StorageAccessError))?
+        .len() as usize;
+    let zeros = [0u8; CHUNK_SIZE];
+    let mut written = 0;
+
+    file.seek(SeekFrom::Start(0))
+        .or(Err(ZebraErrors::StorageWriteError))?;
+
+    while written < len {
+        let chunk = CHUNK_SIZE.min(len - written);
+
+        file.write_all(&zeros[..chunk])
+            .or(Err(ZebraErrors::StorageWriteError))?;
+        written += chunk;
+    }
+
+    file.sync_all().or(Err(ZebraErrors::StorageWriteError))?;
+    drop(file);
+
+    fs::remove_file(path).or(Err(ZebraErrors::StorageWriteError))
+}
+
+#[cfg(test)]
+mod shred_tests {
+    use super::*;
+
+    #[test]
+    fn test_shred() {
+        let dir = std::env::temp_dir().join(format!("zebra_shred_{}", std::process::id()));
+        let nested = dir.join("nested");
+
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.join("a"), vec![1u8; CHUNK_SIZE + 7]).unwrap();
+        fs::write(nested.join("b"), b"secret").unwrap();
+
+        shred(&dir).unwrap();
+
+        assert!(!dir.exists());
+        assert!(shred(&dir).is_ok());
+    }
+}
@@ -0,0 +1,133 @@
+//! -- Copyright (c) 2024 Rina Khasanshin
+//! -- Email: hicarus@yandex.ru
+//! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use sled::{Db, IVec};
+
+use super::backend::StorageBackend;
+use super::lock::{VaultLock, LOCK_FILE_NAME};
+use super::shred::shred;
+use crate::errors::ZebraErrors;
+
+// files sled creates inside of the vault dir.
+const SLED_FILES: [&str; 3] = ["conf", "db", "blobs"];
+const SLED_SNAPSHOT_PREFIX: &str = "snap.";
+
+// `tree` is declared first so sled is closed before the lock is released,
+// `destroy` releases it by hand after the files are shredded.
+pub struct SledBackend {
+    tree: Db,
+    path: PathBuf,
+    _lock: VaultLock,
+}
+
+impl SledBackend {
+    pub fn new(path: &Path) -> Result<Self, ZebraErrors> {
+        let lock = VaultLock::acquire(&path.join(LOCK_FILE_NAME))?;
+        let tree = sled::open(path).or(Err(ZebraErrors::StorageAccessError))?;
+
+        Ok(Self {
+            tree,
+            path: path.to_path_buf(),
+            _lock: lock,
+        })
+    }
+
+    pub fn from(
+        qualifier: &str,
+        organization: &str,
+        application: &str,
+    ) -> Result<Self, ZebraErrors> {
+        let dirs = ProjectDirs::from(qualifier, organization, application)
+            .ok_or(ZebraErrors::StoragePathError)?;
+
+        Self::new(dirs.data_dir())
+    }
+}
+
+impl StorageBackend for SledBackend {
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>, ZebraErrors> {
+        let value = self
+            .tree
+            .get(key)
+            .or(Err(ZebraErrors::StorageAccessError))?;
+
+        Ok(value.map(|v| v.to_vec()))
+    }
+
+    fn write(&self, key: &str, value: &[u8]) -> Result<(), ZebraErrors> {
+        self.tree
+            .insert(key, IVec::from(value))
+            .or(Err(ZebraErrors::StorageWriteError))?;
+
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), ZebraErrors> {
+        self.tree
+            .remove(key)
+            .or(Err(ZebraErrors::StorageWriteError))?;
+
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, ZebraErrors> {
+        let mut entries = Vec::new();
+
+        for kv in self.tree.iter() {
+            let (key, value) = kv.or(Err(ZebraErrors::FailToloadBytesTree))?;
+            let key = String::from_utf8_lossy(&key).to_string();
+
+            entries.push((key, value.to_vec()));
+        }
+
+        Ok(entries)
+    }
+
+    fn sync(&self) -> Result<(), ZebraErrors> {
+        self.tree.flush().or(Err(ZebraErrors::StorageWriteError))?;
+
+        Ok(())
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn size(&self) -> u64 {
+        self.tree.size_on_disk().unwrap_or(0)
+    }
+
+    fn destroy(self: Box<Self>) -> Result<(), ZebraErrors> {
+        let SledBackend {
+            tree,
+            path,
+            _lock: lock,
+        } = *self;
+
+        // sled is closed, but nobody else may open the vault until it is shredded.
+        drop(tree);
+
+        let dir = fs::read_dir(&path).or(Err(ZebraErrors::StorageAccessError))?;
+
+        for entry in dir.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if !SLED_FILES.contains(&name.as_str()) && !name.starts_with(SLED_SNAPSHOT_PREFIX) {
+                continue;
+            }
+
+            shred(&entry.path())?;
+        }
+
+        lock.release()?;
+
+        // keeps the dir when something foreign lives there.
+        let _ = fs::remove_dir(&path);
+
+        Ok(())
+    }
+}
@@ -2,6 +2,17 @@
 //! -- Email: hicarus@yandex.ru
 //! -- Licensed under the GNU General Public License Version 3.0 (GPL-3.0)
 use std::borrow::Cow;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::errors::ZebraErrors;
+
+pub fn unix_time() -> Result<i64, ZebraErrors> {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .or(Err(ZebraErrors::StorageTimeWentBackwards))?;
+
+    Ok(since_epoch.as_secs() as i64)
+}
 
 pub fn truncate_string<'a>(s: impl Into<Cow<'a, str>>, max_length: usize) -> Cow<'a, str> {
     let s = s.into();
@@ -33,6 +33,7 @@ where
     easing: Easing,
     cycle_duration: Duration,
     rotation_duration: Duration,
+    progress: Option<f32>,
 }
 
 impl<Theme> Circular<Theme>
@@ -49,6 +50,7 @@ where
                 .build(),
             cycle_duration: Duration::from_millis(600),
             rotation_duration: Duration::from_secs(2),
+            progress: None,
         }
     }
 
@@ -88,6 +90,12 @@ where
         self.rotation_duration = duration;
         self
     }
+
+    /// Turns the [`Circular`] into a static ring filled from the top by `progress` (0.0 - 1.0).
+    pub fn progress(mut self, progress: f32) -> Self {
+        self.progress = Some(progress.clamp(0.0, 1.0));
+        self
+    }
 }
 
 impl<Theme> Default for Circular<Theme>
@@ -217,6 +225,7 @@ impl Animation {
 struct State {
     animation: Animation,
     cache: canvas::Cache,
+    progress: Option<f32>,
 }
 
 impl<Message, Theme> Widget<Message, Theme, Renderer> for Circular<Theme>
@@ -232,6 +241,15 @@ where
         tree::State::new(State::default())
     }
 
+    fn diff(&self, tree: &mut Tree) {
+        let state = tree.state.downcast_mut::<State>();
+
+        if state.progress != self.progress {
+            state.progress = self.progress;
+            state.cache.clear();
+        }
+    }
+
     fn size(&self) -> Size<Length> {
         Size {
             width: Length::Fixed(self.size),
@@ -261,6 +279,10 @@ where
     ) -> event::Status {
         let state = tree.state.downcast_mut::<State>();
 
+        if self.progress.is_some() {
+            return event::Status::Ignored;
+        }
+
         if let Event::Window(_, window::Event::RedrawRequested(now)) = event {
             state.animation =
                 state
@@ -305,8 +327,16 @@ where
 
             let start = state.animation.rotation() * 2.0 * PI;
 
-            match state.animation {
-                Animation::Expanding { progress, .. } => {
+            match (self.progress, state.animation) {
+                (Some(progress), _) => {
+                    builder.arc(canvas::path::Arc {
+                        center: frame.center(),
+                        radius: track_radius,
+                        start_angle: (-PI / 2.0).into(),
+                        end_angle: (-PI / 2.0 + 2.0 * PI * progress).into(),
+                    });
+                }
+                (None, Animation::Expanding { progress, .. }) => {
                     builder.arc(canvas::path::Arc {
                         center: frame.center(),
                         radius: track_radius,
@@ -317,7 +347,7 @@ where
                         .into(),
                     });
                 }
-                Animation::Contracting { progress, .. } => {
+                (None, Animation::Contracting { progress, .. }) => {
                     builder.arc(canvas::path::Arc {
                         center: frame.center(),
                         radius: track_radius,
@@ -22,6 +22,7 @@ const MAGIC_ICON: &[u8] = include_bytes!("../static/icons/magic.svg");
 const COPY_ICON: &[u8] = include_bytes!("../static/icons/copy.svg");
 const LOCK_ICON: &[u8] = include_bytes!("../static/icons/lock.svg");
 const GEAR_ICON: &[u8] = include_bytes!("../static/icons/gear.svg");
+const SHIELD_ICON: &[u8] = include_bytes!("../static/icons/shield.svg");
 const ZEBRA_LOGO: &[u8] = include_bytes!("../static/imgs/zebra_logo.svg");
 const ZEBRA_BAD: &[u8] = include_bytes!("../static/icons/bad-zebra.svg");
 const TRASH_ICON: &[u8] = include_bytes!("../static/icons/trash.svg");
@@ -195,6 +196,13 @@ pub fn gear_icon<'a>() -> Svg<'a> {
         .style(super::styles::svg::bg_inverse)
 }
 
+pub fn shield_icon<'a>() -> Svg<'a> {
+    let h = Handle::from_memory(SHIELD_ICON);
+    Svg::new(h)
+        .content_fit(ContentFit::Cover)
+        .style(super::styles::svg::bg_inverse)
+}
+
 pub fn back_icon<'a>() -> Svg<'a> {
     let h = Handle::from_memory(BACK_ICON);
     Svg::new(h)